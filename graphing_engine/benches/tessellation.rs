@@ -0,0 +1,55 @@
+//! Benchmarks of stroke tessellation, as a baseline for performance work.
+//!
+//! Run with `cargo bench -p graphing-engine`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use graphing_engine::{sample_polynomial, Tessellation};
+
+/// Width of the view in pixels, as a typical window would show.
+const VIEW_PIXELS: f32 = 1000.0;
+/// Half width of a stroke in world units.
+const WIDTH: f32 = 0.01;
+
+/// Equations as coefficients from x^0 up, from a straight line to one which turns often.
+const EQUATIONS: [(&str, &[f32]); 4] = [
+    ("2x + 1", &[1.0, 2.0]),
+    ("x^2 - 1", &[-1.0, 0.0, 1.0]),
+    ("x^3 - 3x", &[0.0, -3.0, 0.0, 1.0]),
+    ("x^7 - 14x^5 + 49x^3 - 36x", &[0.0, -36.0, 0.0, 49.0, 0.0, -14.0, 0.0, 1.0]),
+];
+
+fn polynomials(c: &mut Criterion) {
+    let mut group = c.benchmark_group("polynomial");
+    for (name, coeffs) in EQUATIONS {
+        // the view around the origin when zoomed in and out
+        for world_per_pixel in [0.001, 0.01, 0.1] {
+            let half_width = VIEW_PIXELS / 2.0 * world_per_pixel;
+            group.bench_with_input(BenchmarkId::new(name, world_per_pixel), &world_per_pixel, |b, &world_per_pixel| {
+                b.iter(|| Tessellation::polynomial(black_box(coeffs), -half_width, half_width, world_per_pixel, WIDTH));
+            });
+        }
+    }
+    group.finish();
+}
+
+fn sampling(c: &mut Criterion) {
+    let (_, coeffs) = EQUATIONS[3];
+    c.bench_function("sample_polynomial", |b| {
+        b.iter(|| sample_polynomial(black_box(coeffs), -5.0, 5.0, 0.01));
+    });
+}
+
+fn polylines(c: &mut Criterion) {
+    let points = (0..10_000)
+        .map(|i| {
+            let x = i as f32 * 0.01;
+            cgmath::vec2(x, x.sin())
+        })
+        .collect::<Vec<_>>();
+    c.bench_function("polyline of 10000 points", |b| {
+        b.iter(|| Tessellation::polyline(black_box(&points), 0.01, WIDTH));
+    });
+}
+
+criterion_group!(benches, polynomials, sampling, polylines);
+criterion_main!(benches);
@@ -0,0 +1,202 @@
+//! Features of polynomial curves, such as their roots and turning points, for describing them to
+//! the user.
+//!
+//! Roots are found by looking for sign changes at evenly spaced samples and narrowing each down by
+//! bisection, so pairs of roots closer together than a sample step can be missed. Roots where the
+//! curve only touches zero are picked up from the turning points instead.
+
+/// Number of intervals the x range is split into when looking for sign changes.
+const SEARCH_STEPS: usize = 1000;
+/// Halvings used to narrow down each sign change, which takes it well below f32 precision.
+const BISECTION_STEPS: usize = 60;
+/// Turning points within this fraction of the largest sampled value from zero count as roots.
+const TOUCH_TOLERANCE: f64 = 1e-7;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Extremum {
+    Minimum,
+    Maximum,
+}
+
+/// Where a curve heads as x goes off to one side.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Trend {
+    Up,
+    Down,
+    /// Constant curves stay at their value.
+    Level(f32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    pub y_intercept: f32,
+    /// x values in the range where the curve crosses or touches zero, in order.
+    pub roots: Vec<f32>,
+    /// Turning points in the range, in order.
+    pub extrema: Vec<(cgmath::Vector2<f32>, Extremum)>,
+    /// Where the curve heads as x goes to -∞ and to +∞.
+    pub ends: (Trend, Trend),
+}
+
+fn evaluate(coeffs: &[f64], x: f64) -> f64 {
+    coeffs.iter().rev().fold(0.0, |y, coeff| y * x + coeff)
+}
+
+fn derivative(coeffs: &[f64]) -> Vec<f64> {
+    coeffs.iter().enumerate().skip(1).map(|(i, coeff)| i as f64 * coeff).collect()
+}
+
+/// Narrows down where the polynomial crosses zero between lo and hi, whose values must have
+/// opposite signs.
+fn bisect(coeffs: &[f64], mut lo: f64, mut hi: f64) -> f64 {
+    let rising = evaluate(coeffs, lo) < 0.0;
+    for _ in 0..BISECTION_STEPS {
+        let mid = (lo + hi) / 2.0;
+        let y = evaluate(coeffs, mid);
+        if y == 0.0 {
+            return mid;
+        }
+        if (y < 0.0) == rising {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Returns where the polynomial changes sign between min and max, in order, and whether it goes
+/// from negative to positive there. Samples which are exactly zero are stepped over, so touching
+/// zero without crossing isn't a change.
+fn sign_changes(coeffs: &[f64], min: f64, max: f64) -> Vec<(f64, bool)> {
+    let step = (max - min) / SEARCH_STEPS as f64;
+    let mut changes = Vec::new();
+    // the last sample which wasn't zero
+    let mut previous: Option<(f64, f64)> = None;
+
+    for i in 0..=SEARCH_STEPS {
+        let x = min + step * i as f64;
+        let y = evaluate(coeffs, x);
+        if y == 0.0 {
+            continue;
+        }
+        if let Some((previous_x, previous_y)) = previous {
+            if (previous_y < 0.0) != (y < 0.0) {
+                changes.push((bisect(coeffs, previous_x, x), y > 0.0));
+            }
+        }
+        previous = Some((x, y));
+    }
+    changes
+}
+
+/// Finds the features of the polynomial with coeffs, ordered from x^0 up, between x_min and
+/// x_max.
+pub fn analyse(coeffs: &[f32], x_min: f32, x_max: f32) -> Analysis {
+    let mut coeffs = coeffs.iter().map(|&coeff| coeff as f64).collect::<Vec<_>>();
+    while coeffs.last() == Some(&0.0) {
+        coeffs.pop();
+    }
+    let (min, max) = (x_min as f64, x_max as f64);
+
+    let ends = match coeffs.split_last() {
+        None => (Trend::Level(0.0), Trend::Level(0.0)),
+        Some((&constant, [])) => (Trend::Level(constant as f32), Trend::Level(constant as f32)),
+        Some((&leading, rest)) => {
+            let right = if leading > 0.0 { Trend::Up } else { Trend::Down };
+            // the degree is the number of coefficients below the leading one, and odd powers head
+            // the other way on the left
+            let left = match (rest.len() % 2 == 1, right) {
+                (false, _) => right,
+                (true, Trend::Up) => Trend::Down,
+                (true, _) => Trend::Up,
+            };
+            (left, right)
+        }
+    };
+
+    let extrema = sign_changes(&derivative(&coeffs), min, max)
+        .into_iter()
+        .map(|(x, rising)| {
+            // the slope going from negative to positive is the bottom of a dip
+            let kind = if rising { Extremum::Minimum } else { Extremum::Maximum };
+            (x, evaluate(&coeffs, x), kind)
+        })
+        .collect::<Vec<_>>();
+
+    let largest = (0..=SEARCH_STEPS)
+        .map(|i| evaluate(&coeffs, min + (max - min) * i as f64 / SEARCH_STEPS as f64).abs())
+        .fold(0.0, f64::max);
+    let mut roots = sign_changes(&coeffs, min, max).into_iter().map(|(x, _)| x).collect::<Vec<_>>();
+    roots.extend(extrema.iter().filter(|(_, y, _)| y.abs() <= largest * TOUCH_TOLERANCE).map(|(x, _, _)| *x));
+    roots.sort_by(f64::total_cmp);
+
+    Analysis {
+        y_intercept: coeffs.first().copied().unwrap_or(0.0) as f32,
+        roots: roots.into_iter().map(|x| x as f32).collect(),
+        extrema: extrema.into_iter().map(|(x, y, kind)| (cgmath::vec2(x as f32, y as f32), kind)).collect(),
+        ends,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyse_parabola() {
+        use approx::relative_eq;
+
+        let analysis = analyse(&[-1.0, 0.0, 1.0], -5.0, 5.0);
+
+        assert_eq!(analysis.y_intercept, -1.0);
+        assert_eq!(analysis.roots.len(), 2);
+        assert!(relative_eq!(analysis.roots[0], -1.0) && relative_eq!(analysis.roots[1], 1.0));
+        assert_eq!(analysis.extrema, [(cgmath::vec2(0.0, -1.0), Extremum::Minimum)]);
+        assert_eq!(analysis.ends, (Trend::Up, Trend::Up));
+    }
+
+    #[test]
+    fn test_analyse_cubic() {
+        use approx::relative_eq;
+
+        let analysis = analyse(&[0.0, -1.0, 0.0, 1.0], -10.0, 10.0);
+
+        assert_eq!(analysis.roots.len(), 3);
+        for (root, expected) in analysis.roots.iter().zip([-1.0, 0.0, 1.0]) {
+            assert!(relative_eq!(*root, expected, epsilon = 1e-6));
+        }
+        let turning = 1.0 / 3.0_f32.sqrt();
+        assert_eq!(analysis.extrema.len(), 2);
+        assert!(relative_eq!(analysis.extrema[0].0.x, -turning) && analysis.extrema[0].1 == Extremum::Maximum);
+        assert!(relative_eq!(analysis.extrema[1].0.x, turning) && analysis.extrema[1].1 == Extremum::Minimum);
+        assert_eq!(analysis.ends, (Trend::Down, Trend::Up));
+    }
+
+    #[test]
+    fn touching_zero_is_a_root_but_not_a_crossing() {
+        let analysis = analyse(&[0.0, 0.0, 1.0], -4.0, 4.0);
+        assert_eq!(analysis.roots, [0.0]);
+
+        // x^3 flattens out at the origin without turning
+        let analysis = analyse(&[0.0, 0.0, 0.0, 1.0], -4.0, 4.0);
+        assert_eq!(analysis.roots, [0.0]);
+        assert!(analysis.extrema.is_empty());
+    }
+
+    #[test]
+    fn only_features_in_range_are_found() {
+        let analysis = analyse(&[-1.0, 0.0, 1.0], 2.0, 5.0);
+
+        assert!(analysis.roots.is_empty());
+        assert!(analysis.extrema.is_empty());
+    }
+
+    #[test]
+    fn constants_stay_level() {
+        let analysis = analyse(&[2.0, 0.0], -1.0, 1.0);
+
+        assert_eq!(analysis.ends, (Trend::Level(2.0), Trend::Level(2.0)));
+        assert!(analysis.roots.is_empty());
+    }
+}
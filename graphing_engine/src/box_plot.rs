@@ -0,0 +1,134 @@
+//! Box-and-whisker summaries of data series, drawn side by side along a categorical x axis with
+//! one box at each whole number.
+//!
+//! Whiskers reach the furthest values within one and a half times the interquartile range of the
+//! box, and values beyond them are drawn as outliers.
+
+use crate::geometry::{Color, Marker, Point, Run};
+
+/// Width of a box in world units, leaving a gap between neighbouring categories.
+const BOX_WIDTH: f32 = 0.5;
+/// Width of the caps across the ends of the whiskers, as a fraction of BOX_WIDTH.
+const CAP_FRACTION: f32 = 0.5;
+/// How many interquartile ranges the whiskers can reach beyond the box.
+const WHISKER_REACH: f32 = 1.5;
+
+/// The quartiles of a set of values, with the whiskers and outliers beyond them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxStats {
+    pub lower_whisker: f32,
+    pub lower_quartile: f32,
+    pub median: f32,
+    pub upper_quartile: f32,
+    pub upper_whisker: f32,
+    /// Values beyond the whiskers, in ascending order.
+    pub outliers: Vec<f32>,
+}
+
+/// Returns the quantile q of sorted values, interpolating linearly between the values either side.
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let position = q * (sorted.len() - 1) as f32;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f32)
+}
+
+impl BoxStats {
+    /// Summarises values, ignoring any which aren't finite. Returns None if none are left.
+    pub fn new(values: impl Iterator<Item = f32>) -> Option<Self> {
+        let mut sorted = values.filter(|value| value.is_finite()).collect::<Vec<_>>();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f32::total_cmp);
+
+        let lower_quartile = quantile(&sorted, 0.25);
+        let upper_quartile = quantile(&sorted, 0.75);
+        let reach = WHISKER_REACH * (upper_quartile - lower_quartile);
+        let inside = |value: &&f32| (lower_quartile - reach..=upper_quartile + reach).contains(*value);
+
+        Some(Self {
+            lower_whisker: *sorted.iter().find(inside)?,
+            lower_quartile,
+            median: quantile(&sorted, 0.5),
+            upper_quartile,
+            upper_whisker: *sorted.iter().rev().find(inside)?,
+            outliers: sorted.iter().copied().filter(|value| !inside(&value)).collect(),
+        })
+    }
+
+    /// Returns the lines drawing the box centred on x, with its median and whiskers, and a point
+    /// drawn with marker for each outlier.
+    pub fn shapes(&self, x: f32, color: Color<f32>, marker: Marker) -> (Vec<Run>, Vec<Point>) {
+        let half = BOX_WIDTH / 2.0;
+        let cap = half * CAP_FRACTION;
+        let run = |points: &[(f32, f32)]| Run {
+            points: points.iter().map(|&(x, y)| cgmath::vec2(x, y)).collect(),
+            color,
+            width: None,
+        };
+
+        let runs = vec![
+            run(&[
+                (x - half, self.lower_quartile),
+                (x + half, self.lower_quartile),
+                (x + half, self.upper_quartile),
+                (x - half, self.upper_quartile),
+                (x - half, self.lower_quartile),
+            ]),
+            run(&[(x - half, self.median), (x + half, self.median)]),
+            run(&[(x, self.upper_quartile), (x, self.upper_whisker)]),
+            run(&[(x - cap, self.upper_whisker), (x + cap, self.upper_whisker)]),
+            run(&[(x, self.lower_quartile), (x, self.lower_whisker)]),
+            run(&[(x - cap, self.lower_whisker), (x + cap, self.lower_whisker)]),
+        ];
+        let outliers = self.outliers.iter()
+            .map(|&y| Point { color, marker, ..Point::new(cgmath::vec2(x, y)) })
+            .collect();
+
+        (runs, outliers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quartiles_interpolate_between_values() {
+        let stats = BoxStats::new([4.0, 1.0, 3.0, 2.0].into_iter()).unwrap();
+
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.lower_quartile, 1.75);
+        assert_eq!(stats.upper_quartile, 3.25);
+        assert_eq!((stats.lower_whisker, stats.upper_whisker), (1.0, 4.0));
+        assert!(stats.outliers.is_empty());
+    }
+
+    #[test]
+    fn values_far_from_the_box_are_outliers() {
+        let stats = BoxStats::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 100.0, f32::NAN].into_iter()).unwrap();
+
+        assert_eq!(stats.median, 5.0);
+        assert_eq!((stats.lower_quartile, stats.upper_quartile), (3.0, 7.0));
+        assert_eq!((stats.lower_whisker, stats.upper_whisker), (1.0, 8.0));
+        assert_eq!(stats.outliers, [100.0]);
+    }
+
+    #[test]
+    fn no_values_have_no_box() {
+        assert_eq!(BoxStats::new(std::iter::empty()), None);
+        assert_eq!(BoxStats::new([f32::NAN].into_iter()), None);
+    }
+
+    #[test]
+    fn shapes_are_centred_on_the_category() {
+        let stats = BoxStats::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 100.0].into_iter()).unwrap();
+
+        let (runs, outliers) = stats.shapes(2.0, Color { r: 0.0, g: 0.0, b: 1.0, a: 1.0 }, Marker::Cross);
+
+        assert_eq!(runs.len(), 6);
+        assert!(runs.iter().flat_map(|run| &run.points).all(|point| (point.x - 2.0).abs() <= BOX_WIDTH / 2.0));
+        assert_eq!(outliers.iter().map(|point| point.position).collect::<Vec<_>>(), [cgmath::vec2(2.0, 100.0)]);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::EngineError;
+
+/// A GPU buffer which is recreated with a larger size when written past its end, up to the
+/// largest buffer the device allows.
+///
+/// Buffers are bound fresh every frame with slice, so replacing the underlying buffer needs no
+/// further bookkeeping by the pipelines that own them.
+pub struct DynamicBuffer {
+    buffer: wgpu::Buffer,
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    /// The device's largest buffer size in bytes.
+    limit: u64,
+}
+
+/// Returns the size a buffer should be grown to so that it can hold required bytes, which must be
+/// no more than limit.
+fn grown_capacity(capacity: u64, required: u64, limit: u64) -> u64 {
+    if required <= capacity {
+        capacity
+    } else {
+        required.next_power_of_two().min(limit)
+    }
+}
+
+impl DynamicBuffer {
+    pub fn new(device: &wgpu::Device, label: &'static str, usage: wgpu::BufferUsages, capacity: u64) -> Self {
+        let usage = usage | wgpu::BufferUsages::COPY_DST;
+        let limit = device.limits().max_buffer_size;
+        let buffer = Self::create(device, label, usage, capacity.min(limit));
+
+        Self {
+            buffer,
+            label,
+            usage,
+            limit,
+        }
+    }
+
+    fn create(device: &wgpu::Device, label: &'static str, usage: wgpu::BufferUsages, capacity: u64) -> wgpu::Buffer {
+        device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some(label),
+                size: capacity.max(wgpu::COPY_BUFFER_ALIGNMENT),
+                usage,
+                mapped_at_creation: false,
+            }
+        )
+    }
+
+    /// Returns an error if the buffer can't grow to hold required bytes.
+    pub fn check_fits(&self, required: u64) -> Result<(), EngineError> {
+        if required > self.limit {
+            return Err(EngineError::BufferOverflow {
+                buffer: self.label,
+                required,
+                limit: self.limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Writes data to the start of the buffer, growing it first if needed. Returns whether the
+    /// buffer was recreated, or an error without writing anything if data is more than the
+    /// device allows in a buffer.
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[u8]) -> Result<bool, EngineError> {
+        // writes must be a multiple of four bytes, which u16 index data is not always
+        let padding = data.len().next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize) - data.len();
+        let required = (data.len() + padding) as u64;
+
+        self.check_fits(required)?;
+        let capacity = grown_capacity(self.buffer.size(), required, self.limit);
+        let grew = capacity != self.buffer.size();
+        if grew {
+            log::debug!("growing {} from {} to {capacity} bytes", self.label, self.buffer.size());
+            self.buffer.destroy();
+            self.buffer = Self::create(device, self.label, self.usage, capacity);
+        }
+
+        if padding == 0 {
+            queue.write_buffer(&self.buffer, 0, data);
+        } else {
+            let mut padded = data.to_vec();
+            padded.resize(data.len() + padding, 0);
+            queue.write_buffer(&self.buffer, 0, &padded);
+        }
+
+        Ok(grew)
+    }
+
+    /// Writes data at offset bytes into the buffer if it fits without growing, returning false
+    /// otherwise so the caller can write everything with write instead. Both must be a multiple
+    /// of four bytes.
+    pub fn write_at(&self, queue: &wgpu::Queue, offset: u64, data: &[u8]) -> bool {
+        if offset + data.len() as u64 > self.buffer.size() {
+            return false;
+        }
+        queue.write_buffer(&self.buffer, offset, data);
+        true
+    }
+
+    pub fn slice(&self) -> wgpu::BufferSlice<'_> {
+        self.buffer.slice(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grown_capacity() {
+        const LIMIT: u64 = 1 << 20;
+        assert_eq!(grown_capacity(1024, 1000, LIMIT), 1024);
+        assert_eq!(grown_capacity(1024, 1024, LIMIT), 1024);
+        assert_eq!(grown_capacity(1024, 1025, LIMIT), 2048);
+        assert_eq!(grown_capacity(1024, 5000, LIMIT), 8192);
+        // never shrinks
+        assert_eq!(grown_capacity(4096, 4, LIMIT), 4096);
+        // never grows past the limit, even where the next power of two would
+        assert_eq!(grown_capacity(1024, 5000, 6000), 6000);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::RenderTargetConfig;
+
+/// Identifies a render pipeline by its shader, topology and pipeline layout.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    shader: String,
+    topology: wgpu::PrimitiveTopology,
+    layout: wgpu::Id<wgpu::PipelineLayout>,
+}
+
+/// Shares shader modules and render pipelines between the engine's pipelines, backed by wgpu's
+/// pipeline cache where the device supports it so that compiled pipelines survive restarts.
+pub struct PipelineCache {
+    shaders: HashMap<String, Rc<wgpu::ShaderModule>>,
+    pipelines: HashMap<PipelineKey, Rc<wgpu::RenderPipeline>>,
+    cache: Option<wgpu::PipelineCache>,
+    path: Option<PathBuf>,
+}
+
+impl PipelineCache {
+    /// Creates the cache, seeding wgpu's pipeline cache from path if the file exists.
+    pub fn new(device: &wgpu::Device, path: Option<&Path>) -> Self {
+        let cache = if device.features().contains(wgpu::Features::PIPELINE_CACHE) {
+            let data = path.and_then(|path| std::fs::read(path).ok());
+            // SAFETY: the data was written by save from a cache of the same adapter, as the file
+            // name is keyed by the adapter, and wgpu falls back to an empty cache if it is stale
+            Some(unsafe {
+                device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("Pipeline Cache"),
+                    data: data.as_deref(),
+                    fallback: true,
+                })
+            })
+        } else {
+            None
+        };
+
+        Self {
+            shaders: HashMap::new(),
+            pipelines: HashMap::new(),
+            cache,
+            path: path.map(Path::to_path_buf),
+        }
+    }
+
+    fn shader(&mut self, device: &wgpu::Device, shader: wgpu::ShaderModuleDescriptor) -> Rc<wgpu::ShaderModule> {
+        let name = shader.label.unwrap_or_default().to_owned();
+        self.shaders
+            .entry(name)
+            .or_insert_with(|| Rc::new(device.create_shader_module(shader)))
+            .clone()
+    }
+
+    /// Returns the pipeline for the given shader, topology and layout, creating it the first time.
+    ///
+    /// Shaders are told apart by their label, which include_wgsl sets to the file name.
+    pub fn render_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        target: &RenderTargetConfig,
+        vertex_layouts: &[wgpu::VertexBufferLayout],
+        shader: wgpu::ShaderModuleDescriptor,
+        topology: wgpu::PrimitiveTopology,
+    ) -> Rc<wgpu::RenderPipeline> {
+        let key = PipelineKey {
+            shader: shader.label.unwrap_or_default().to_owned(),
+            topology,
+            layout: layout.global_id(),
+        };
+        if let Some(pipeline) = self.pipelines.get(&key) {
+            return pipeline.clone();
+        }
+
+        let shader = self.shader(device, shader);
+        let pipeline = Rc::new(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: vertex_layouts,
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: target.multisample_state(),
+            multiview: None,
+            cache: self.cache.as_ref(),
+        }));
+
+        self.pipelines.insert(key, pipeline.clone());
+        pipeline
+    }
+
+    /// Writes wgpu's pipeline cache to disk so the next start can skip compiling pipelines.
+    pub fn save(&self) -> std::io::Result<()> {
+        let (Some(cache), Some(path)) = (&self.cache, &self.path) else {
+            return Ok(());
+        };
+        let Some(data) = cache.get_data() else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // written to a temporary file first so a crash can't leave a truncated cache behind
+        let temp = path.with_extension("temp");
+        std::fs::write(&temp, data)?;
+        std::fs::rename(&temp, path)
+    }
+}
@@ -0,0 +1,1136 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use dpi::{PhysicalPosition, PhysicalSize};
+
+use crate::input::{CursorIcon, EngineInput, Key, Modifiers, PointerButton, ScrollDelta, TouchPhase};
+
+#[rustfmt::skip]
+pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 0.5, 0.5,
+    0.0, 0.0, 0.0, 1.0,
+);
+
+/// Eye height the camera starts at and returns to when the view is reset.
+pub const HOME_ZOOM: f32 = 4.0;
+/// Factor the zoom target changes by for each line of scrolling.
+const ZOOM_STEP: f32 = 1.25;
+/// How quickly the zoom closes in on its target. The remaining distance, in log space, shrinks
+/// by a factor of e every 1 / ZOOM_SHARPNESS seconds.
+const ZOOM_SHARPNESS: f32 = 15.0;
+/// Precise scrolling distance in physical pixels treated as one line when zooming.
+const PIXELS_PER_LINE: f32 = 50.0;
+/// Longest gap between two clicks, and furthest the pointer can move, for a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+/// Double clicks within this many physical pixels of an axis recentre on the origin.
+const AXIS_SNAP_PIXELS: f32 = 6.0;
+/// Longest frame the easing accounts for, so a stall, or the first frame after the app has been
+/// idle, doesn't make the zoom jump.
+const MAX_ZOOM_STEP_TIME: Duration = Duration::from_millis(33);
+
+/// Moves zoom towards target by the amount of easing that happens in dt, interpolating in log
+/// space so that zooming in and out feel the same.
+fn ease_zoom(zoom: f32, target: f32, dt: Duration) -> f32 {
+    let t = 1.0 - (-ZOOM_SHARPNESS * dt.as_secs_f32()).exp();
+    let log_zoom = zoom.ln() + (target.ln() - zoom.ln()) * t;
+
+    log_zoom.exp()
+}
+
+/// Returns true if a click at pos and now follows the previous click closely enough to make a
+/// double click.
+fn is_double_click(
+    previous: Option<(Instant, PhysicalPosition<f32>)>,
+    now: Instant,
+    pos: PhysicalPosition<f32>,
+) -> bool {
+    previous.is_some_and(|(time, previous)| {
+        now.duration_since(time) <= DOUBLE_CLICK_TIME
+            && (pos.x - previous.x).hypot(pos.y - previous.y) <= DOUBLE_CLICK_DISTANCE
+    })
+}
+
+/// Maps orthographic depth from OpenGL's [-1, 1] to wgpu's [0, 1] without touching w.
+///
+/// OPENGL_TO_WGPU_MATRIX also feeds depth into w, which the perspective projection and
+/// screen_to_view_space have been tuned around but which would scale an orthographic view.
+#[rustfmt::skip]
+pub const ORTHOGRAPHIC_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 0.5, 0.0,
+    0.0, 0.0, 0.5, 1.0,
+);
+
+fn calculate_screen_space(pos: cgmath::Vector2<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+    let x = (size.width as f32 * (pos.x + 1.0)) / 2.0;
+    let y = (size.height as f32 * (pos.y - 1.0)) / -2.0;
+
+    cgmath::Vector2 { x, y }
+}
+
+/// Projects pos through view_proj to screen space.
+fn project(view_proj: cgmath::Matrix4<f32>, pos: cgmath::Vector3<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+    // convert from world space to clip space
+    let clip_pos = view_proj * cgmath::vec4(pos.x, pos.y, pos.z, 1.0);
+    // convert from clip space to normalised space
+    let normal_pos = cgmath::Vector2 { x: clip_pos.x / clip_pos.w, y: clip_pos.y / clip_pos.w };
+    // convert from normalised space to screen space
+    calculate_screen_space(normal_pos, size)
+}
+
+fn normalise_screen_space(pos: cgmath::Vector2<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+    let x = ((2.0 / size.width as f32) * pos.x) - 1.0;
+    let y = ((-2.0 / size.height as f32) * pos.y) + 1.0;
+
+    cgmath::Vector2 { x, y }
+}
+
+/// An axis aligned region of the graph in world units.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub x_min: f32,
+    pub x_max: f32,
+    pub y_min: f32,
+    pub y_max: f32,
+}
+
+/// How the camera projects the graph onto the screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Projection {
+    /// Perspective from the eye, which distorts slightly towards the edges of the view.
+    Perspective,
+    /// Parallel projection, where every pixel covers the same area of the graph. The distance
+    /// from the eye to the target sets the zoom, as world_units_per_pixel.
+    #[default]
+    Orthographic,
+}
+
+/// Smallest and largest height of the graph, in world units, the camera can be zoomed to show.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ZoomLimits {
+    pub min_extent: f32,
+    pub max_extent: f32,
+}
+
+impl Default for ZoomLimits {
+    fn default() -> Self {
+        Self {
+            min_extent: 1e-4,
+            max_extent: 1e8,
+        }
+    }
+}
+
+impl ZoomLimits {
+    /// Clamps an eye height for camera so the visible height stays within the limits.
+    pub(crate) fn clamp(&self, camera: &Camera, zoom: f32) -> f32 {
+        zoom.clamp(camera.zoom_for_extent(self.min_extent), camera.zoom_for_extent(self.max_extent))
+    }
+}
+
+pub struct Camera {
+    pub projection: Projection,
+    pub eye: cgmath::Point3<f32>,
+    pub target: cgmath::Point3<f32>,
+    pub up: cgmath::Vector3<f32>,
+    pub aspect: f32,
+    pub fovy: f32,
+    pub znear: f32,
+    pub zfar: f32,
+}
+
+impl Camera {
+    pub fn build_view_projection_matrix(&self) -> cgmath::Matrix4<f32> {
+        let view = cgmath::Matrix4::look_at_rh(self.eye, self.target, self.up);
+
+        self.build_proj_matrix() * view
+    }
+
+    /// Builds the view projection matrix for geometry placed relative to the point under the eye,
+    /// which keeps its precision far from the origin where world positions would not.
+    pub fn build_relative_view_projection_matrix(&self) -> cgmath::Matrix4<f32> {
+        let shift = cgmath::vec3(self.eye.x, self.eye.y, 0.0);
+        let view = cgmath::Matrix4::look_at_rh(self.eye - shift, self.target - shift, self.up);
+
+        self.build_proj_matrix() * view
+    }
+
+    /// Half the height of the graph visible in orthographic mode, which is the height of the
+    /// perspective frustum at the target.
+    fn orthographic_half_height(&self) -> f32 {
+        use cgmath::{Angle, InnerSpace};
+        let distance = (self.target - self.eye).magnitude();
+
+        distance * (cgmath::Deg(self.fovy) / 2.0).tan()
+    }
+
+    /// Returns the eye height at which extent world units of the graph fill the view vertically.
+    pub fn zoom_for_extent(&self, extent: f32) -> f32 {
+        use cgmath::Angle;
+        extent / (2.0 * (cgmath::Deg(self.fovy) / 2.0).tan())
+    }
+
+    fn build_proj_matrix(&self) -> cgmath::Matrix4<f32> {
+        match self.projection {
+            Projection::Perspective => {
+                let proj = cgmath::perspective(cgmath::Deg(self.fovy), self.aspect, self.znear, self.zfar);
+
+                OPENGL_TO_WGPU_MATRIX * proj
+            }
+            Projection::Orthographic => {
+                let half_height = self.orthographic_half_height();
+                let half_width = half_height * self.aspect;
+                // the clip planes follow the graph, so it isn't cut off however far the eye is
+                let distance = self.eye.z - self.target.z;
+                let proj = cgmath::ortho(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    distance - self.zfar,
+                    distance + self.zfar,
+                );
+
+                ORTHOGRAPHIC_TO_WGPU_MATRIX * proj
+            }
+        }
+    }
+
+    pub fn world_to_screen_space(&self, pos: cgmath::Vector3<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+        project(self.build_view_projection_matrix(), pos, size)
+    }
+
+    /// Returns the screen position of a point given as an offset from the point under the eye.
+    #[cfg(feature = "text")]
+    pub fn offset_to_screen_space(&self, offset: cgmath::Vector3<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+        project(self.build_relative_view_projection_matrix(), offset, size)
+    }
+
+    pub fn screen_to_view_space(&self, pos: cgmath::Vector2<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+        use cgmath::SquareMatrix;
+        // convert from screen space to normalised space
+        let normal_pos = normalise_screen_space(pos, size);
+        // convert from normalised space to view space
+        let pos = self.build_proj_matrix().invert().unwrap() * cgmath::vec4(normal_pos.x, normal_pos.y, 0.0, 1.0);
+
+        // callers scale by eye.z to get world units, which is what the perspective near plane
+        // needs after the fudge, so the orthographic view space is divided through to match
+        match self.projection {
+            Projection::Perspective => cgmath::Vector2 { x: pos.x * 1.5, y: pos.y * 1.5 },
+            Projection::Orthographic => cgmath::Vector2 { x: pos.x / self.eye.z, y: pos.y / self.eye.z },
+        }
+    }
+
+    /// Returns the region of the z = 0 plane visible to the camera.
+    ///
+    /// Each corner of the viewport is unprojected into a ray from the near plane to the far plane
+    /// and intersected with the graph plane.
+    pub fn visible_bounds(&self) -> Bounds {
+        use cgmath::SquareMatrix;
+        let inverse = self.build_view_projection_matrix().invert().unwrap();
+
+        let unproject = |x: f32, y: f32, z: f32| {
+            let pos = inverse * cgmath::vec4(x, y, z, 1.0);
+            cgmath::vec3(pos.x / pos.w, pos.y / pos.w, pos.z / pos.w)
+        };
+
+        let mut bounds = Bounds {
+            x_min: f32::INFINITY,
+            x_max: f32::NEG_INFINITY,
+            y_min: f32::INFINITY,
+            y_max: f32::NEG_INFINITY,
+        };
+
+        for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            let near = unproject(x, y, 0.0);
+            let far = unproject(x, y, 1.0);
+            // fraction of the way along the ray where it crosses z = 0
+            let t = near.z / (near.z - far.z);
+            let corner = near + (far - near) * t;
+
+            bounds.x_min = bounds.x_min.min(corner.x);
+            bounds.x_max = bounds.x_max.max(corner.x);
+            bounds.y_min = bounds.y_min.min(corner.y);
+            bounds.y_max = bounds.y_max.max(corner.y);
+        }
+
+        bounds
+    }
+
+    /// Returns the size of one physical pixel in world units on the z = 0 plane.
+    pub fn world_units_per_pixel(&self, size: PhysicalSize<u32>) -> f32 {
+        let height = match self.projection {
+            Projection::Perspective => {
+                let bounds = self.visible_bounds();
+                bounds.y_max - bounds.y_min
+            }
+            Projection::Orthographic => self.orthographic_half_height() * 2.0,
+        };
+
+        height / size.height.max(1) as f32
+    }
+
+    /// Returns the point on the z = 0 plane under a screen position.
+    pub fn screen_to_world_space(&self, pos: cgmath::Vector2<f32>, size: PhysicalSize<u32>) -> cgmath::Vector2<f32> {
+        use cgmath::SquareMatrix;
+        let inverse = self.build_view_projection_matrix().invert().unwrap();
+        let normal_pos = normalise_screen_space(pos, size);
+
+        let unproject = |z: f32| {
+            let pos = inverse * cgmath::vec4(normal_pos.x, normal_pos.y, z, 1.0);
+            cgmath::vec3(pos.x / pos.w, pos.y / pos.w, pos.z / pos.w)
+        };
+        let near = unproject(0.0);
+        let far = unproject(1.0);
+        let t = near.z / (near.z - far.z);
+        let point = near + (far - near) * t;
+
+        cgmath::vec2(point.x, point.y)
+    }
+
+    /// Centres the camera on bounds and zooms so that all of it is visible.
+    pub fn fit_bounds(&mut self, bounds: Bounds) {
+        let centre = cgmath::vec2((bounds.x_min + bounds.x_max) / 2.0, (bounds.y_min + bounds.y_max) / 2.0);
+        let shift = cgmath::vec3(centre.x - self.target.x, centre.y - self.target.y, 0.0);
+        self.eye += shift;
+        self.target += shift;
+
+        // the visible height is proportional to the eye height in both projections
+        let visible = self.visible_bounds();
+        let visible_height = visible.y_max - visible.y_min;
+        let needed_height = (bounds.y_max - bounds.y_min).max((bounds.x_max - bounds.x_min) / self.aspect);
+        if visible_height > 0.0 && needed_height > 0.0 {
+            self.eye.z *= needed_height / visible_height;
+        }
+    }
+
+    /// Calculates the distance from the origin of this transformation to the cursor_location and
+    /// adjusts the pan/translation in the x and y axes.
+    pub fn adjust_pan_with_cursor_position(
+        &mut self,
+        cursor_location: PhysicalPosition<f32>, 
+        origin: cgmath::Vector2<f32>, 
+        modifier: f32, 
+        size: PhysicalSize<u32>
+    ) {
+        // calculate view space positions for the cursor and origin
+        let cursor_view = self.screen_to_view_space(cgmath::vec2(cursor_location.x, cursor_location.y), size);
+        let origin_view = self.screen_to_view_space(origin, size);
+        // calculate the distance from the cursor to the origin
+        let distance = cgmath::vec2(cursor_view.x - origin_view.x, cursor_view.y - origin_view.y);
+        // set the rate at which the pan is adjusted
+        let speed = 0.1;
+        // use the angle from the point to the origin to determine a base change
+        // make the values negative if it is a zoom in and close to origin to snap it to the origin
+        let theta = if modifier > 0.0 && distance.x.abs() + distance.y.abs() < 0.1 {
+            f32::atan2(-distance.y, -distance.x)
+        } else {
+            f32::atan2(distance.y, distance.x)
+        };
+        let unit_change = cgmath::vec2(f32::cos(theta) * speed, f32::sin(theta) * speed);
+        // change by the smallest of either the change or the remaining distance
+        let change = cgmath::vec3(
+            (unit_change.x.abs()).min(distance.x.abs()) * self.eye.z * unit_change.x.signum(),
+            (unit_change.y.abs()).min(distance.y.abs()) * self.eye.z * unit_change.y.signum(),
+            0.0,
+        );
+        // apply a modifier which decides the direction the change should be in
+        self.eye += change * modifier;
+        self.target += change * modifier;
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CameraUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+impl CameraUniform {
+    pub fn new() -> Self {
+        use cgmath::SquareMatrix;
+        Self {
+            view_proj: cgmath::Matrix4::identity().into(),
+        }
+    }
+
+    pub fn update_view_proj(&mut self, camera: &Camera) {
+        self.view_proj = camera.build_view_projection_matrix().into();
+    }
+
+    /// Sets the matrix for geometry placed relative to the point under the eye.
+    pub fn update_relative_view_proj(&mut self, camera: &Camera) {
+        self.view_proj = camera.build_relative_view_projection_matrix().into();
+    }
+}
+
+pub struct CameraController {
+    speed: f32,
+    limits: ZoomLimits,
+    origin: PhysicalPosition<f32>,
+    cursor_location: PhysicalPosition<f32>,
+    mouse_clicked_at: Option<PhysicalPosition<f32>>,
+    is_up_pressed: bool,
+    is_down_pressed: bool,
+    is_left_pressed: bool,
+    is_right_pressed: bool,
+    is_mouse_pressed: bool,
+    is_mouse_released: bool,
+    /// The button holding the current drag.
+    pan_button: Option<PointerButton>,
+    scroll: f32,
+    /// The eye height being eased towards, while a zoom is in progress.
+    zoom_target: Option<f32>,
+    /// Whether the zoom in progress keeps the centre of the view still rather than the point
+    /// under the cursor, as for the zoom buttons.
+    zoom_centred: bool,
+    last_update: Option<Instant>,
+    /// Fingers currently on the screen and where they are.
+    touches: Vec<(u64, PhysicalPosition<f32>)>,
+    /// Distance between two fingers when the pinch was last measured.
+    pinch_distance: Option<f32>,
+    /// Magnification from pinching since the last update, applied immediately rather than eased.
+    pinch_scale: f32,
+    /// Precise scrolling since the last update, in physical pixels, which pans the graph.
+    pan_pixels: cgmath::Vector2<f32>,
+    modifiers: Modifiers,
+    /// Where a shift drag to select a region to zoom to started.
+    box_start: Option<PhysicalPosition<f32>>,
+    /// A finished box selection, in screen space, waiting to be zoomed to.
+    box_zoom: Option<(PhysicalPosition<f32>, PhysicalPosition<f32>)>,
+    /// When and where the primary button was last pressed, to spot double clicks.
+    last_click: Option<(Instant, PhysicalPosition<f32>)>,
+    /// A double click in screen space waiting to be turned into a pan target.
+    recenter_at: Option<PhysicalPosition<f32>>,
+    /// The point the view is being eased towards centring on after a double click.
+    pan_target: Option<cgmath::Vector2<f32>>,
+}
+
+impl CameraController {
+    pub fn new(speed: f32, limits: ZoomLimits) -> Self {
+        Self {
+            speed,
+            limits,
+            origin: PhysicalPosition { x: 0.0, y: 0.0 },
+            cursor_location: PhysicalPosition { x: 0.0, y: 0.0 },
+            mouse_clicked_at: None,
+            is_up_pressed: false,
+            is_down_pressed: false,
+            is_left_pressed: false,
+            is_right_pressed: false,
+            is_mouse_pressed: false,
+            is_mouse_released: true,
+            pan_button: None,
+            scroll: 0.0,
+            zoom_target: None,
+            zoom_centred: false,
+            last_update: None,
+            touches: Vec::new(),
+            pinch_distance: None,
+            pinch_scale: 1.0,
+            pan_pixels: cgmath::vec2(0.0, 0.0),
+            modifiers: Modifiers::default(),
+            box_start: None,
+            box_zoom: None,
+            last_click: None,
+            recenter_at: None,
+            pan_target: None,
+        }
+    }
+
+    /// Sets the window position of the top left of the graph, which cursor positions are made
+    /// relative to.
+    pub fn set_origin(&mut self, origin: PhysicalPosition<f32>) {
+        self.origin = origin;
+    }
+
+    pub fn process_events(&mut self, event: &EngineInput) -> bool {
+        match event {
+            EngineInput::Key { key, pressed } => {
+                match key {
+                    Key::Up => self.is_up_pressed = *pressed,
+                    Key::Down => self.is_down_pressed = *pressed,
+                    Key::Left => self.is_left_pressed = *pressed,
+                    Key::Right => self.is_right_pressed = *pressed,
+                }
+                true
+            },
+            EngineInput::Scroll(delta) => {
+                match delta {
+                    ScrollDelta::Lines { y, .. } => {
+                        self.scroll += *y;
+                        self.zoom_centred = false;
+                        true
+
+                    }
+                    // trackpads scroll in pixels, and pan with two fingers unless ctrl is held,
+                    // which is also how many of them report a pinch
+                    ScrollDelta::Pixels { y, .. } if self.modifiers.ctrl => {
+                        self.scroll += y / PIXELS_PER_LINE;
+                        self.zoom_centred = false;
+                        true
+                    }
+                    ScrollDelta::Pixels { x, y } => {
+                        self.pan_pixels += cgmath::vec2(*x, *y);
+                        true
+                    }
+                }
+            },
+            EngineInput::PointerMove { x, y } => {
+                self.cursor_location.x = x - self.origin.x;
+                self.cursor_location.y = y - self.origin.y;
+                true
+            },
+            EngineInput::PointerButton { button: PointerButton::Primary, pressed: true } if self.modifiers.shift => {
+                self.box_start = Some(self.cursor_location);
+                true
+            }
+            EngineInput::PointerButton { button: PointerButton::Primary, pressed: false } if self.box_start.is_some() => {
+                self.box_zoom = self.box_start.take().map(|start| (start, self.cursor_location));
+                true
+            }
+            // any button drags the graph, and the drag lasts until the button which started it is
+            // released, so pressing a second button part way through doesn't interrupt it
+            EngineInput::PointerButton { button, pressed: true } => {
+                if *button == PointerButton::Primary {
+                    let now = Instant::now();
+                    if is_double_click(self.last_click, now, self.cursor_location) {
+                        self.recenter_at = Some(self.cursor_location);
+                        self.last_click = None;
+                    } else {
+                        self.last_click = Some((now, self.cursor_location));
+                    }
+                }
+                if self.pan_button.is_none() {
+                    self.pan_button = Some(*button);
+                    self.is_mouse_pressed = true;
+                    self.is_mouse_released = false;
+                }
+                true
+            }
+            EngineInput::PointerButton { button, pressed: false } => {
+                if self.pan_button == Some(*button) {
+                    self.pan_button = None;
+                    self.is_mouse_pressed = false;
+                    self.is_mouse_released = true;
+                }
+                true
+            }
+            EngineInput::Touch { id, phase, x, y } => {
+                self.process_touch(*id, *phase, PhysicalPosition::new(x - self.origin.x, y - self.origin.y));
+                true
+            }
+            EngineInput::Pinch { delta } => {
+                self.pinch_scale *= 1.0 + delta;
+                true
+            }
+            EngineInput::Modifiers(modifiers) => {
+                self.modifiers = *modifiers;
+                false
+            }
+        }
+    }
+
+    /// Zooms in by steps lines of scrolling, or out if steps is negative, about the centre of the
+    /// view.
+    pub fn zoom_steps(&mut self, steps: f32) {
+        self.scroll += steps;
+        self.zoom_centred = true;
+    }
+
+    /// Eases the view back to the origin at the zoom it started at.
+    pub fn reset(&mut self, camera: &Camera) {
+        self.scroll = 0.0;
+        self.zoom_target = Some(self.limits.clamp(camera, HOME_ZOOM));
+        self.zoom_centred = true;
+        self.pan_target = Some(cgmath::vec2(0.0, 0.0));
+    }
+
+    /// Drives the pan and pinch zoom from touches. One finger drags the graph like the mouse. Two
+    /// fingers drag by their midpoint and zoom about it as they spread or close.
+    fn process_touch(&mut self, id: u64, phase: TouchPhase, location: PhysicalPosition<f32>) {
+        let count = self.touches.len();
+        match phase {
+            TouchPhase::Started => self.touches.push((id, location)),
+            TouchPhase::Moved => {
+                if let Some(touch) = self.touches.iter_mut().find(|(touch_id, _)| *touch_id == id) {
+                    touch.1 = location;
+                }
+            }
+            TouchPhase::Ended => self.touches.retain(|(touch_id, _)| *touch_id != id),
+        }
+
+        if self.touches.len() != count {
+            // start a new drag and pinch so the graph doesn't jump to the new midpoint
+            self.mouse_clicked_at = None;
+            self.pinch_distance = None;
+        }
+
+        match self.touches.as_slice() {
+            [] => {
+                self.is_mouse_pressed = false;
+                self.is_mouse_released = true;
+            }
+            [(_, location)] => {
+                self.cursor_location = *location;
+                self.is_mouse_pressed = true;
+                self.is_mouse_released = false;
+            }
+            [(_, a), (_, b), ..] => {
+                self.cursor_location = PhysicalPosition::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+                self.is_mouse_pressed = true;
+                self.is_mouse_released = false;
+
+                let distance = (a.x - b.x).hypot(a.y - b.y);
+                if let Some(previous) = self.pinch_distance.filter(|previous| *previous > 0.0) {
+                    self.pinch_scale *= distance / previous;
+                }
+                self.pinch_distance = Some(distance);
+            }
+        }
+    }
+
+    /// Returns the last position of the pointer, in physical pixels relative to the origin.
+    pub fn cursor_location(&self) -> PhysicalPosition<f32> {
+        self.cursor_location
+    }
+
+    /// Returns the corners of the box being dragged out to zoom to, if any, in screen space.
+    pub fn selection(&self) -> Option<(PhysicalPosition<f32>, PhysicalPosition<f32>)> {
+        self.box_start.map(|start| (start, self.cursor_location))
+    }
+
+    /// Returns the modifier keys last reported as held.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn cursor_icon(&self) -> CursorIcon {
+        if self.box_start.is_some() {
+            CursorIcon::Crosshair
+        } else if self.is_mouse_pressed {
+            CursorIcon::Grabbing
+        } else {
+            CursorIcon::Default
+        }
+    }
+
+    /// Returns true while a held key or zoom animation is moving the camera, which needs a new
+    /// frame every update.
+    pub fn is_moving(&self) -> bool {
+        self.is_up_pressed
+            || self.is_down_pressed
+            || self.is_left_pressed
+            || self.is_right_pressed
+            || self.zoom_target.is_some()
+            || self.recenter_at.is_some()
+            || self.pan_target.is_some()
+    }
+
+    /// Sets the eye height, moving the camera so the point under the cursor, or the centre of the
+    /// view for a centred zoom, stays put.
+    fn zoom_about_cursor(&self, camera: &mut Camera, size: PhysicalSize<u32>, zoom: f32) {
+        let anchor = if self.zoom_centred {
+            cgmath::vec2(size.width as f32 / 2.0, size.height as f32 / 2.0)
+        } else {
+            cgmath::vec2(self.cursor_location.x, self.cursor_location.y)
+        };
+        let before = camera.screen_to_world_space(anchor, size);
+        camera.eye.z = zoom;
+        let after = camera.screen_to_world_space(anchor, size);
+
+        let shift = cgmath::vec3(before.x - after.x, before.y - after.y, 0.0);
+        camera.eye += shift;
+        camera.target += shift;
+    }
+
+    /// Eases the eye height towards the zoom target, keeping the point under the cursor fixed.
+    fn update_zoom(&mut self, camera: &mut Camera, size: PhysicalSize<u32>, dt: Duration) {
+        if self.pinch_scale != 1.0 {
+            // pinching tracks the fingers directly, taking over from any scroll zoom
+            self.zoom_centred = false;
+            let zoom = self.limits.clamp(camera, camera.eye.z / self.pinch_scale);
+            self.zoom_about_cursor(camera, size, zoom);
+            self.pinch_scale = 1.0;
+            self.zoom_target = None;
+        }
+
+        if self.scroll != 0.0 {
+            let target = self.zoom_target.unwrap_or(camera.eye.z) * ZOOM_STEP.powf(-self.scroll);
+            self.zoom_target = Some(self.limits.clamp(camera, target));
+            self.scroll = 0.0;
+        }
+
+        let Some(target) = self.zoom_target else {
+            return;
+        };
+
+        let zoom = ease_zoom(camera.eye.z, target, dt);
+        // stop once the remaining change is too small to see
+        let finished = (zoom / target).ln().abs() < 1e-3;
+        let zoom = if finished { target } else { zoom };
+
+        self.zoom_about_cursor(camera, size, zoom);
+        if finished {
+            self.zoom_target = None;
+            self.zoom_centred = false;
+        }
+    }
+
+    /// Eases the centre of the view towards the point picked by a double click, which is the
+    /// origin if an axis was clicked.
+    fn update_recenter(&mut self, camera: &mut Camera, size: PhysicalSize<u32>, dt: Duration) {
+        if let Some(click) = self.recenter_at.take() {
+            let origin = camera.world_to_screen_space(cgmath::vec3(0.0, 0.0, 0.0), size);
+            let on_axis = (click.x - origin.x).abs() <= AXIS_SNAP_PIXELS || (click.y - origin.y).abs() <= AXIS_SNAP_PIXELS;
+            self.pan_target = Some(if on_axis {
+                cgmath::vec2(0.0, 0.0)
+            } else {
+                camera.screen_to_world_space(cgmath::vec2(click.x, click.y), size)
+            });
+        }
+
+        let Some(target) = self.pan_target else {
+            return;
+        };
+
+        let centre = cgmath::vec2(camera.target.x, camera.target.y);
+        let t = 1.0 - (-ZOOM_SHARPNESS * dt.as_secs_f32()).exp();
+        let remaining = target - centre;
+        let mut step = remaining * t;
+        // finish off once the rest of the way is under half a pixel
+        let left = remaining - step;
+        if left.x.hypot(left.y) < camera.world_units_per_pixel(size) / 2.0 {
+            self.pan_target = None;
+            step = remaining;
+        }
+
+        let shift = cgmath::vec3(step.x, step.y, 0.0);
+        camera.eye += shift;
+        camera.target += shift;
+    }
+
+    pub fn update_camera(&mut self, camera: &mut Camera, size: PhysicalSize<u32>) {
+        let now = Instant::now();
+        let dt = self.last_update
+            .map_or(Duration::ZERO, |last_update| now - last_update)
+            .min(MAX_ZOOM_STEP_TIME);
+        self.last_update = Some(now);
+
+        self.update_zoom(camera, size, dt);
+        self.update_recenter(camera, size, dt);
+
+        if let Some((start, end)) = self.box_zoom.take() {
+            // ignore clicks and slips which would zoom in absurdly far
+            if (start.x - end.x).abs() >= 4.0 && (start.y - end.y).abs() >= 4.0 {
+                let a = camera.screen_to_world_space(cgmath::vec2(start.x, start.y), size);
+                let b = camera.screen_to_world_space(cgmath::vec2(end.x, end.y), size);
+                camera.fit_bounds(Bounds {
+                    x_min: a.x.min(b.x),
+                    x_max: a.x.max(b.x),
+                    y_min: a.y.min(b.y),
+                    y_max: a.y.max(b.y),
+                });
+                camera.eye.z = self.limits.clamp(camera, camera.eye.z);
+                self.zoom_target = None;
+            }
+        }
+
+        if self.pan_pixels != cgmath::vec2(0.0, 0.0) {
+            // the graph follows the fingers, and screen y points down
+            let world_per_pixel = camera.world_units_per_pixel(size);
+            let shift = cgmath::vec3(-self.pan_pixels.x, self.pan_pixels.y, 0.0) * world_per_pixel;
+            camera.eye += shift;
+            camera.target += shift;
+            self.pan_pixels = cgmath::vec2(0.0, 0.0);
+        }
+
+        if self.is_mouse_pressed {
+            if self.mouse_clicked_at.is_none() {
+                // record click location
+                self.mouse_clicked_at = Some(self.cursor_location);
+            } else {
+                // moving mouse_clicked_at to cursor_location
+                let mouse_clicked_at_pos = cgmath::vec2(self.mouse_clicked_at.unwrap().x, self.mouse_clicked_at.unwrap().y);
+                camera.adjust_pan_with_cursor_position(self.cursor_location, mouse_clicked_at_pos, -1.0, size);
+                // update the click location now that the movement has occurred
+                self.mouse_clicked_at = Some(self.cursor_location);
+            }
+        }
+        if self.is_mouse_released {
+            self.mouse_clicked_at = None;
+        }
+
+        if self.is_up_pressed {
+            camera.eye.y += self.speed * camera.eye.z;
+            camera.target.y += self.speed * camera.eye.z;
+        }
+        if self.is_down_pressed {
+            camera.eye.y -= self.speed * camera.eye.z;
+            camera.target.y -= self.speed * camera.eye.z;
+        }
+        if self.is_left_pressed {
+            camera.eye.x -= self.speed * camera.eye.z;
+            camera.target.x -= self.speed * camera.eye.z;
+        }
+        if self.is_right_pressed {
+            camera.eye.x += self.speed * camera.eye.z;
+            camera.target.x += self.speed * camera.eye.z;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_screen_space() {
+        let size = PhysicalSize::new(256, 256);
+        
+        let pos = cgmath::Vector2 { x: 0.0, y: 0.0 };
+        assert_eq!(calculate_screen_space(pos, size), cgmath::vec2(128.0, 128.0));
+
+        let pos = cgmath::Vector2 { x: 1.0, y: -1.0 };
+        assert_eq!(calculate_screen_space(pos, size), cgmath::vec2(256.0, 256.0));
+
+        let pos = cgmath::Vector2 { x: -1.0, y: 1.0 };
+        assert_eq!(calculate_screen_space(pos, size), cgmath::vec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_normalise_screen_space() {
+        let size = PhysicalSize::new(256, 256);
+        
+        let pos = cgmath::Vector2 { x: 128.0, y: 128.0 };
+        assert_eq!(normalise_screen_space(pos, size), cgmath::vec2(0.0, 0.0));
+
+        let pos = cgmath::Vector2 { x: 256.0, y: 256.0 };
+        assert_eq!(normalise_screen_space(pos, size), cgmath::vec2(1.0, -1.0));
+
+        let pos = cgmath::Vector2 { x: 0.0, y: 0.0 };
+        assert_eq!(normalise_screen_space(pos, size), cgmath::vec2(-1.0, 1.0));
+    }
+
+    #[test]
+    fn zoom_limits_bound_the_visible_height() {
+        use approx::relative_eq;
+
+        let camera = Camera {
+            projection: Projection::Orthographic,
+            eye: (0.0, 0.0, 4.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+        let limits = ZoomLimits { min_extent: 0.5, max_extent: 1e9 };
+
+        let mut zoomed = Camera { eye: (0.0, 0.0, limits.clamp(&camera, 1e-6)).into(), ..camera };
+        let bounds = zoomed.visible_bounds();
+        assert!(relative_eq!(bounds.y_max - bounds.y_min, 0.5, max_relative = 1e-3));
+
+        zoomed.eye.z = limits.clamp(&camera, 1e12);
+        let bounds = zoomed.visible_bounds();
+        assert!(relative_eq!(bounds.y_max - bounds.y_min, 1e9, max_relative = 1e-3));
+    }
+
+    #[test]
+    fn test_cursor_icon_while_dragging() {
+        let mut controller = CameraController::new(0.1, ZoomLimits::default());
+        assert_eq!(controller.cursor_icon(), CursorIcon::Default);
+
+        controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed: true });
+        assert_eq!(controller.cursor_icon(), CursorIcon::Grabbing);
+
+        controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed: false });
+        assert_eq!(controller.cursor_icon(), CursorIcon::Default);
+    }
+
+    #[test]
+    fn test_visible_bounds() {
+        use approx::relative_eq;
+
+        let size = PhysicalSize::new(512, 256);
+        let camera = Camera {
+            projection: Projection::Perspective,
+            eye: (5.0, -1.0, 4.0).into(),
+            target: (5.0, -1.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 2.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let bounds = camera.visible_bounds();
+
+        // the corners of the bounds are the corners of the screen
+        let top_left = camera.world_to_screen_space(cgmath::vec3(bounds.x_min, bounds.y_max, 0.0), size);
+        let bottom_right = camera.world_to_screen_space(cgmath::vec3(bounds.x_max, bounds.y_min, 0.0), size);
+        assert!(relative_eq!(top_left.x, 0.0, epsilon = 1e-2));
+        assert!(relative_eq!(top_left.y, 0.0, epsilon = 1e-2));
+        assert!(relative_eq!(bottom_right.x, 512.0, epsilon = 1e-2));
+        assert!(relative_eq!(bottom_right.y, 256.0, epsilon = 1e-2));
+
+        // and are centred on the camera
+        assert!(relative_eq!((bounds.x_min + bounds.x_max) / 2.0, 5.0, epsilon = 1e-4));
+        assert!(relative_eq!((bounds.y_min + bounds.y_max) / 2.0, -1.0, epsilon = 1e-4));
+    }
+
+    #[test]
+    fn world_units_per_pixel_scales_with_zoom() {
+        use approx::relative_eq;
+
+        let size = PhysicalSize::new(256, 256);
+        let mut camera = Camera {
+            projection: Projection::Perspective,
+            eye: (0.0, 0.0, 4.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let near = camera.world_units_per_pixel(size);
+        let bounds = camera.visible_bounds();
+        assert!(relative_eq!(near, (bounds.y_max - bounds.y_min) / 256.0));
+
+        camera.eye.z = 8.0;
+        assert!(relative_eq!(camera.world_units_per_pixel(size), near * 2.0, max_relative = 0.01));
+    }
+
+    #[test]
+    fn orthographic_pixels_are_uniform() {
+        use approx::relative_eq;
+
+        let size = PhysicalSize::new(400, 200);
+        let mut camera = Camera {
+            projection: Projection::Orthographic,
+            eye: (3.0, 2.0, 4.0).into(),
+            target: (3.0, 2.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 2.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let wpp = camera.world_units_per_pixel(size);
+        let bounds = camera.visible_bounds();
+        assert!(relative_eq!(bounds.x_max - bounds.x_min, wpp * 400.0, max_relative = 1e-4));
+        assert!(relative_eq!(bounds.y_max - bounds.y_min, wpp * 200.0, max_relative = 1e-4));
+
+        // a pixel step is the same size in world units anywhere on screen
+        let centre = camera.world_to_screen_space(cgmath::vec3(3.0, 2.0, 0.0), size);
+        let edge = camera.world_to_screen_space(cgmath::vec3(3.0 + wpp * 150.0, 2.0, 0.0), size);
+        assert!(relative_eq!(edge.x - centre.x, 150.0, epsilon = 1e-2));
+
+        camera.eye.z = 8.0;
+        assert!(relative_eq!(camera.world_units_per_pixel(size), wpp * 2.0, max_relative = 1e-4));
+    }
+
+    #[test]
+    fn ease_zoom_is_frame_rate_independent() {
+        use approx::relative_eq;
+
+        let once = ease_zoom(4.0, 16.0, Duration::from_millis(20));
+        let half = ease_zoom(4.0, 16.0, Duration::from_millis(10));
+        let twice = ease_zoom(half, 16.0, Duration::from_millis(10));
+
+        assert!(relative_eq!(once, twice, max_relative = 1e-4));
+        assert!(once > 4.0 && once < 16.0);
+        assert_eq!(ease_zoom(4.0, 16.0, Duration::ZERO), 4.0);
+    }
+
+    #[test]
+    fn screen_to_world_space_inverts_world_to_screen_space() {
+        use approx::relative_eq;
+
+        let size = PhysicalSize::new(300, 200);
+        for projection in [Projection::Orthographic, Projection::Perspective] {
+            let camera = Camera {
+                projection,
+                eye: (1.0, 2.0, 6.0).into(),
+                target: (1.0, 2.0, 0.0).into(),
+                up: cgmath::Vector3::unit_y(),
+                aspect: 1.5,
+                fovy: 45.0,
+                znear: 0.1,
+                zfar: 100.0,
+            };
+
+            let screen = camera.world_to_screen_space(cgmath::vec3(2.5, 0.5, 0.0), size);
+            let world = camera.screen_to_world_space(screen, size);
+            assert!(relative_eq!(world.x, 2.5, epsilon = 1e-3));
+            assert!(relative_eq!(world.y, 0.5, epsilon = 1e-3));
+        }
+    }
+
+    #[test]
+    fn two_finger_pinch_scales_about_midpoint() {
+        use approx::relative_eq;
+
+        let mut controller = CameraController::new(0.1, ZoomLimits::default());
+        let touch = |id, phase, x, y| EngineInput::Touch { id, phase, x, y };
+
+        controller.process_events(&touch(0, TouchPhase::Started, 100.0, 100.0));
+        controller.process_events(&touch(1, TouchPhase::Started, 200.0, 100.0));
+        // the second finger landing doesn't count as a pinch
+        assert_eq!(controller.pinch_scale, 1.0);
+
+        controller.process_events(&touch(1, TouchPhase::Moved, 300.0, 100.0));
+        assert!(relative_eq!(controller.pinch_scale, 2.0));
+        assert_eq!(controller.cursor_location, PhysicalPosition::new(200.0, 100.0));
+
+        controller.process_events(&touch(0, TouchPhase::Ended, 100.0, 100.0));
+        controller.process_events(&touch(1, TouchPhase::Ended, 300.0, 100.0));
+        assert!(!controller.is_mouse_pressed);
+    }
+
+    #[test]
+    fn precise_scroll_pans_unless_ctrl_is_held() {
+        let mut controller = CameraController::new(0.1, ZoomLimits::default());
+
+        controller.process_events(&EngineInput::Scroll(ScrollDelta::Pixels { x: 10.0, y: -20.0 }));
+        assert_eq!(controller.pan_pixels, cgmath::vec2(10.0, -20.0));
+        assert_eq!(controller.scroll, 0.0);
+
+        controller.process_events(&EngineInput::Modifiers(Modifiers { ctrl: true, ..Default::default() }));
+        controller.process_events(&EngineInput::Scroll(ScrollDelta::Pixels { x: 0.0, y: PIXELS_PER_LINE }));
+        assert_eq!(controller.scroll, 1.0);
+    }
+
+    #[test]
+    fn fit_bounds_shows_whole_region() {
+        use approx::relative_eq;
+
+        let mut camera = Camera {
+            projection: Projection::Orthographic,
+            eye: (0.0, 0.0, 4.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 2.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let region = Bounds { x_min: 10.0, x_max: 30.0, y_min: -2.0, y_max: 2.0 };
+        camera.fit_bounds(region);
+        let visible = camera.visible_bounds();
+
+        assert!(visible.x_min <= region.x_min + 1e-3 && visible.x_max >= region.x_max - 1e-3);
+        assert!(visible.y_min <= region.y_min + 1e-3 && visible.y_max >= region.y_max - 1e-3);
+        // the wider dimension fills the view exactly
+        assert!(relative_eq!(visible.x_max - visible.x_min, 20.0, max_relative = 1e-3));
+    }
+
+    #[test]
+    fn zoom_buttons_keep_the_centre_still() {
+        use approx::relative_eq;
+
+        let size = PhysicalSize::new(256, 256);
+        let mut camera = Camera {
+            projection: Projection::Orthographic,
+            eye: (1.0, 2.0, 4.0).into(),
+            target: (1.0, 2.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+        let mut controller = CameraController::new(0.1, ZoomLimits::default());
+
+        // the pointer is over a button in the corner, which shouldn't be zoomed towards
+        controller.process_events(&EngineInput::PointerMove { x: 250.0, y: 250.0 });
+        controller.zoom_steps(1.0);
+        controller.update_zoom(&mut camera, size, Duration::from_secs(1));
+
+        assert!(relative_eq!(camera.eye.z, 4.0 / ZOOM_STEP));
+        assert!(relative_eq!(camera.target.x, 1.0) && relative_eq!(camera.target.y, 2.0));
+        assert!(!controller.zoom_centred);
+    }
+
+    #[test]
+    fn test_is_double_click() {
+        let start = Instant::now();
+        let pos = PhysicalPosition::new(100.0, 100.0);
+        let previous = Some((start, pos));
+
+        assert!(is_double_click(previous, start + Duration::from_millis(200), PhysicalPosition::new(102.0, 101.0)));
+        assert!(!is_double_click(previous, start + Duration::from_millis(600), pos));
+        assert!(!is_double_click(previous, start + Duration::from_millis(200), PhysicalPosition::new(120.0, 100.0)));
+        assert!(!is_double_click(None, start, pos));
+    }
+
+    #[test]
+    fn double_click_on_an_axis_recenters_on_the_origin() {
+        let size = PhysicalSize::new(256, 256);
+        let mut camera = Camera {
+            projection: Projection::Orthographic,
+            eye: (1.0, 0.0, 4.0).into(),
+            target: (1.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+        let mut controller = CameraController::new(0.1, ZoomLimits::default());
+
+        // the x axis runs through the middle of the screen
+        controller.process_events(&EngineInput::PointerMove { x: 200.0, y: 128.0 });
+        for pressed in [true, false, true, false] {
+            controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed });
+        }
+        assert!(controller.is_moving());
+
+        controller.update_recenter(&mut camera, size, Duration::from_secs(1));
+        assert!(!controller.is_moving());
+        assert_eq!((camera.target.x, camera.target.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn middle_and_right_buttons_pan() {
+        for button in [PointerButton::Middle, PointerButton::Secondary] {
+            let mut controller = CameraController::new(0.1, ZoomLimits::default());
+            controller.process_events(&EngineInput::PointerButton { button, pressed: true });
+            assert_eq!(controller.cursor_icon(), CursorIcon::Grabbing);
+
+            // releasing a different button doesn't end the drag
+            controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed: true });
+            controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed: false });
+            assert!(controller.is_mouse_pressed);
+
+            controller.process_events(&EngineInput::PointerButton { button, pressed: false });
+            assert_eq!(controller.cursor_icon(), CursorIcon::Default);
+        }
+    }
+
+    #[test]
+    fn shift_drag_selects_instead_of_panning() {
+        let mut controller = CameraController::new(0.1, ZoomLimits::default());
+        controller.process_events(&EngineInput::Modifiers(Modifiers { shift: true, ..Default::default() }));
+        controller.process_events(&EngineInput::PointerMove { x: 10.0, y: 20.0 });
+        controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed: true });
+        controller.process_events(&EngineInput::PointerMove { x: 50.0, y: 60.0 });
+
+        assert!(!controller.is_mouse_pressed);
+        assert_eq!(controller.cursor_icon(), CursorIcon::Crosshair);
+        assert_eq!(controller.selection(), Some((PhysicalPosition::new(10.0, 20.0), PhysicalPosition::new(50.0, 60.0))));
+
+        controller.process_events(&EngineInput::PointerButton { button: PointerButton::Primary, pressed: false });
+        assert_eq!(controller.selection(), None);
+        assert!(controller.box_zoom.is_some());
+    }
+}
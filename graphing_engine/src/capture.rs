@@ -0,0 +1,195 @@
+use std::time::{Duration, Instant};
+
+use crate::{EngineError, RenderTargetConfig, State, Viewport};
+
+/// Longest a capture waits for lines to be tessellated at the new size.
+const TESSELLATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Returns the number of bytes in each row of a texture copy, which wgpu requires to be padded to
+/// a multiple of COPY_BYTES_PER_ROW_ALIGNMENT.
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * 4;
+    unpadded.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+}
+
+/// Strips the row padding from copied texture data, returning tightly packed RGBA pixels.
+/// BGRA data has its red and blue channels swapped.
+fn unpad_rows(data: &[u8], width: u32, padded_bytes_per_row: u32, bgra: bool) -> Vec<u8> {
+    let row_bytes = width as usize * 4;
+    let mut pixels = data.chunks(padded_bytes_per_row as usize)
+        .flat_map(|row| &row[..row_bytes])
+        .copied()
+        .collect::<Vec<_>>();
+    if bgra {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    pixels
+}
+
+/// Draws engine into a new texture of size and reads it back as RGBA pixels, row by row from the
+/// top, waiting for lines still being tessellated. The engine's viewport is set to the whole
+/// texture.
+pub fn capture(
+    engine: &mut State,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    target: &RenderTargetConfig,
+    size: dpi::PhysicalSize<u32>,
+) -> Result<Vec<u8>, EngineError> {
+    let bgra = check_target(device, target, size)?;
+    engine.set_viewport(Viewport { x: 0.0, y: 0.0, size });
+    engine.update(device, queue);
+    // lines which are slow to tessellate carry on in the background, so wait for them rather than
+    // capturing the geometry for the old size
+    let start = Instant::now();
+    while engine.is_tessellating() && start.elapsed() < TESSELLATION_TIMEOUT {
+        std::thread::sleep(Duration::from_millis(10));
+        engine.update(device, queue);
+    }
+    read_back(engine, device, queue, target, size, bgra)
+}
+
+/// Like capture, but returns None rather than waiting if lines are still being tessellated at
+/// size, so that hosts drawing frames can try again on a later one.
+pub fn try_capture(
+    engine: &mut State,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    target: &RenderTargetConfig,
+    size: dpi::PhysicalSize<u32>,
+) -> Result<Option<Vec<u8>>, EngineError> {
+    let bgra = check_target(device, target, size)?;
+    engine.set_viewport(Viewport { x: 0.0, y: 0.0, size });
+    engine.update(device, queue);
+    if engine.is_tessellating() {
+        return Ok(None);
+    }
+    read_back(engine, device, queue, target, size, bgra).map(Some)
+}
+
+/// Checks that a texture of size can be drawn to target and read back, returning whether its
+/// pixels are BGRA.
+fn check_target(device: &wgpu::Device, target: &RenderTargetConfig, size: dpi::PhysicalSize<u32>) -> Result<bool, EngineError> {
+    let max = device.limits().max_texture_dimension_2d;
+    if size.width > max || size.height > max {
+        return Err(EngineError::Capture(format!(
+            "a {}×{} image is larger than the graphics device allows ({max}×{max})",
+            size.width, size.height,
+        )));
+    }
+    let bgra = match target.format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        format => return Err(EngineError::Capture(format!("{format:?} targets can't be read back"))),
+    };
+    if target.sample_count != 1 {
+        return Err(EngineError::Capture("multisampled targets can't be read back".to_owned()));
+    }
+    Ok(bgra)
+}
+
+/// Draws engine as it is into a new texture of size and reads it back.
+fn read_back(
+    engine: &mut State,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    target: &RenderTargetConfig,
+    size: dpi::PhysicalSize<u32>,
+    bgra: bool,
+) -> Result<Vec<u8>, EngineError> {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Capture Texture"),
+        size: wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: target.sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: target.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let bytes_per_row = padded_bytes_per_row(size.width);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Capture Buffer"),
+        size: bytes_per_row as u64 * size.height as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Capture Encoder"),
+    });
+    {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Capture Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(engine.theme().clear_color()),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        engine.grid_prepare(device, queue);
+        engine.render(&mut render_pass, size).map_err(|e| EngineError::Capture(e.to_string()))?;
+    }
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(size.height),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    receiver.recv()
+        .map_err(|e| EngineError::Capture(e.to_string()))?
+        .map_err(|e| EngineError::Capture(e.to_string()))?;
+    let pixels = unpad_rows(&slice.get_mapped_range(), size.width, bytes_per_row, bgra);
+    buffer.unmap();
+    texture.destroy();
+
+    Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_bytes_per_row() {
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+        assert_eq!(padded_bytes_per_row(1), 256);
+    }
+
+    #[test]
+    fn test_unpad_rows() {
+        // two rows of one pixel each, padded out to 8 bytes
+        let data = [1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0];
+
+        assert_eq!(unpad_rows(&data, 1, 8, false), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(unpad_rows(&data, 1, 8, true), [3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+}
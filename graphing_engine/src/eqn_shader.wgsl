@@ -0,0 +1,56 @@
+struct CameraUniform {
+  view_proj: mat4x4<f32>,
+};
+
+struct LineUniform {
+  color: vec4<f32>,
+  // lengths of the drawn and skipped parts of the dash pattern in pixels, both 0 for a solid line
+  dash: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> line: LineUniform;
+
+struct VertexInput {
+  @location(0) position: vec3<f32>,
+  @location(1) side: f32,
+  @location(2) distance: f32,
+}
+
+struct VertexOutput {
+  @builtin(position) clip_position: vec4<f32>,
+  @location(0) side: f32,
+  @location(1) color: vec4<f32>,
+  @location(2) distance: f32,
+};
+
+@vertex
+fn vs_main(
+  model: VertexInput,
+) -> VertexOutput {
+  var out: VertexOutput;
+  out.clip_position = camera.view_proj * vec4<f32>(model.position, 1.0);
+  out.side = model.side;
+  out.color = line.color;
+  out.distance = model.distance;
+  return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  // rate of change of side per pixel, used to measure the distance to the stroke edge in pixels
+  let side_per_pixel = length(vec2<f32>(dpdx(in.side), dpdy(in.side)));
+  let edge_distance = (1.0 - abs(in.side)) / max(side_per_pixel, 1e-6);
+  var coverage = clamp(edge_distance, 0.0, 1.0);
+
+  let period = line.dash.x + line.dash.y;
+  if period > 0.0 {
+    // fade over a pixel at each end of a dash, as with the edges
+    let phase = in.distance - floor(in.distance / period) * period;
+    coverage *= clamp(min(phase, line.dash.x - phase) + 0.5, 0.0, 1.0);
+  }
+  return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
@@ -0,0 +1,26 @@
+use crate::{LineId, ParseError};
+
+/// Why a change to the graph couldn't be made, or drawing it failed.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum EngineError {
+    /// An equation couldn't be parsed. Its line is cleared until it can be.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("there is no line with id {}", .0.to_raw())]
+    UnknownLine(LineId),
+    #[error("there is no point at index {0}")]
+    UnknownPoint(usize),
+    /// More was to be drawn than a GPU buffer can hold on this device.
+    #[error("{buffer} needs {required} bytes, more than the {limit} the device allows")]
+    BufferOverflow {
+        buffer: &'static str,
+        required: u64,
+        limit: u64,
+    },
+    /// The background thread tessellating an equation stopped without finishing.
+    #[error("tessellating {equation:?} failed")]
+    Tessellation { equation: String },
+    /// The graph couldn't be drawn to an image.
+    #[error("capturing the graph failed: {0}")]
+    Capture(String),
+}
@@ -0,0 +1,95 @@
+//! Number formatting shared by the axis labels and anything else which shows graph coordinates.
+
+/// Values of at least 10^SCIENTIFIC_ABOVE are shown in scientific notation.
+const SCIENTIFIC_ABOVE: i32 = 6;
+/// Values only known to within a step below 10^SCIENTIFIC_BELOW are shown in scientific notation.
+const SCIENTIFIC_BELOW: i32 = -4;
+
+/// Formats a value known to within step, showing only the digits step can tell apart.
+///
+/// Values are written as plain decimals, such as 12.5, until they get very large or the step
+/// gets very small, when they switch to scientific notation, such as 1.9e-6, rather than
+/// growing into long strings of digits.
+pub fn format_number(value: f32, step: f32) -> String {
+    if value == 0.0 {
+        return "0".to_owned();
+    }
+
+    let step_exponent = step.abs().log10().floor() as i32;
+    let value_exponent = value.abs().log10().floor() as i32;
+
+    if value_exponent >= SCIENTIFIC_ABOVE || step_exponent < SCIENTIFIC_BELOW {
+        let precision = (value_exponent - step_exponent).max(0) as usize;
+        format!("{value:.precision$e}")
+    } else {
+        let decimals = (-step_exponent).max(0) as usize;
+        format!("{value:.decimals$}")
+    }
+}
+
+/// Formats digits * 10^exponent exactly, switching to scientific notation at the same points as
+/// format_number.
+///
+/// This is for values which are known to be exact decimals, such as gridline positions, which
+/// would pick up noise like 0.30000001 if they went through f32 first.
+#[cfg(feature = "text")]
+pub fn format_decimal(digits: i64, exponent: i32) -> String {
+    if digits == 0 {
+        return "0".to_owned();
+    }
+
+    let sign = if digits < 0 { "-" } else { "" };
+    let digits = digits.unsigned_abs().to_string();
+    let value_exponent = digits.len() as i32 - 1 + exponent;
+
+    if value_exponent >= SCIENTIFIC_ABOVE || exponent < SCIENTIFIC_BELOW {
+        let (first, rest) = digits.split_at(1);
+        let rest = rest.trim_end_matches('0');
+        if rest.is_empty() {
+            format!("{sign}{first}e{value_exponent}")
+        } else {
+            format!("{sign}{first}.{rest}e{value_exponent}")
+        }
+    } else if exponent >= 0 {
+        format!("{sign}{digits}{}", "0".repeat(exponent as usize))
+    } else {
+        let decimals = -exponent as usize;
+        let digits = format!("{digits:0>width$}", width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        format!("{sign}{whole}.{fraction}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number_decimal() {
+        assert_eq!(format_number(0.0, 0.1), "0");
+        assert_eq!(format_number(12.0, 2.0), "12");
+        assert_eq!(format_number(-0.3, 0.1), "-0.3");
+        assert_eq!(format_number(1.25, 0.004), "1.250");
+    }
+
+    #[test]
+    fn test_format_number_scientific() {
+        assert_eq!(format_number(0.0000019073486, 0.0000001), "1.9e-6");
+        assert_eq!(format_number(-0.00003, 0.00001), "-3e-5");
+        assert_eq!(format_number(25_000_000.0, 5_000_000.0), "2.5e7");
+    }
+
+    #[test]
+    #[cfg(feature = "text")]
+    fn test_format_decimal() {
+        assert_eq!(format_decimal(0, -1), "0");
+        assert_eq!(format_decimal(3, -1), "0.3");
+        assert_eq!(format_decimal(-15, -1), "-1.5");
+        assert_eq!(format_decimal(6, -2), "0.06");
+        assert_eq!(format_decimal(12, 0), "12");
+        assert_eq!(format_decimal(5, 2), "500");
+        assert_eq!(format_decimal(19, -7), "1.9e-6");
+        assert_eq!(format_decimal(25, 6), "2.5e7");
+        assert_eq!(format_decimal(10, 6), "1e7");
+    }
+}
@@ -0,0 +1,685 @@
+use serde::{Deserialize, Serialize};
+use wgpu::{self, util::DeviceExt};
+
+use crate::buffer::DynamicBuffer;
+use crate::tessellation::{sample_polynomial, Tessellation, MAX_SAMPLES};
+use crate::EngineError;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vertex {
+    pub position: [f32; 3],
+}
+
+impl Vertex {
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ]
+        }
+    }
+}
+
+/// A vertex of an equation stroke.
+///
+/// side is -1.0 or 1.0 on the two edges of the stroke, so that once interpolated its absolute
+/// value is the distance from the centreline relative to the half width. distance is how far
+/// along the curve the vertex is in physical pixels, which dash patterns are measured against.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct StrokeVertex {
+    pub position: [f32; 3],
+    pub side: f32,
+    pub distance: f32,
+}
+
+impl StrokeVertex {
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<StrokeVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32,
+                },
+            ]
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Color<T> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+    pub a: T,
+}
+
+impl<T: Copy> Color<T> {
+    pub fn to_raw(self) -> [T; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+/// Dash patterns for equation strokes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Dash {
+    /// Returns the lengths of the drawn and skipped parts of the pattern for a stroke width wide,
+    /// or None for a solid line.
+    pub fn lengths(self, width: f32) -> Option<(f32, f32)> {
+        match self {
+            Dash::Solid => None,
+            Dash::Dashed => Some((4.0 * width, 3.0 * width)),
+            Dash::Dotted => Some((width, 2.0 * width)),
+        }
+    }
+}
+
+/// How the curve of an equation is stroked.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineStyle {
+    /// Stroke width in logical pixels.
+    pub width: f32,
+    pub dash: Dash,
+    /// Multiplies the alpha of the line's colour.
+    pub opacity: f32,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self {
+            width: 3.0,
+            dash: Dash::Solid,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// Default radius of points in logical pixels.
+pub const POINT_RADIUS: f32 = 4.0;
+
+/// A point plotted on the graph and how it is drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    pub position: cgmath::Vector2<f32>,
+    pub color: Color<f32>,
+    /// Radius in logical pixels.
+    pub radius: f32,
+    pub marker: Marker,
+    /// Joined to the point before it by a line, so a series can be drawn as a polyline in data
+    /// order.
+    pub connected: bool,
+    /// Text drawn beside the point, if any.
+    pub label: Option<Annotation>,
+}
+
+/// What a point's annotation says.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnnotationText {
+    Custom(String),
+    /// The point's coordinates, such as (2, 5), to the precision of a pixel.
+    Coordinates,
+}
+
+/// Which side of its point an annotation sits on.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    Above,
+    Below,
+    Left,
+    Right,
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 8] = [
+        Anchor::Above,
+        Anchor::Below,
+        Anchor::Left,
+        Anchor::Right,
+        Anchor::TopLeft,
+        Anchor::TopRight,
+        Anchor::BottomLeft,
+        Anchor::BottomRight,
+    ];
+
+    /// Returns the direction of the anchor from the point in screen space, -1, 0 or 1 along
+    /// each axis with y down.
+    pub fn direction(self) -> cgmath::Vector2<f32> {
+        let (x, y) = match self {
+            Anchor::Above => (0.0, -1.0),
+            Anchor::Below => (0.0, 1.0),
+            Anchor::Left => (-1.0, 0.0),
+            Anchor::Right => (1.0, 0.0),
+            Anchor::TopLeft => (-1.0, -1.0),
+            Anchor::TopRight => (1.0, -1.0),
+            Anchor::BottomLeft => (-1.0, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        };
+        cgmath::vec2(x, y)
+    }
+}
+
+/// Text drawn beside a point, which follows it as the view moves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub text: AnnotationText,
+    pub anchor: Anchor,
+    /// Shift from the anchored position in logical pixels, with y down.
+    pub offset: cgmath::Vector2<f32>,
+}
+
+impl Annotation {
+    /// Custom text at the default anchor, without an offset.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: AnnotationText::Custom(text.into()),
+            anchor: Anchor::default(),
+            offset: cgmath::vec2(0.0, 0.0),
+        }
+    }
+
+    /// The point's coordinates at the default anchor, without an offset.
+    pub fn coordinates() -> Self {
+        Self {
+            text: AnnotationText::Coordinates,
+            ..Self::new("")
+        }
+    }
+}
+
+impl Point {
+    /// A black circle of the default radius at position, without a label.
+    pub fn new(position: cgmath::Vector2<f32>) -> Self {
+        Self {
+            position,
+            color: Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+            radius: POINT_RADIUS,
+            marker: Marker::default(),
+            connected: false,
+            label: None,
+        }
+    }
+}
+
+/// Per line shader inputs. dash holds the lengths of the drawn and skipped parts of the dash
+/// pattern in physical pixels, which are both 0 for a solid line.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LineUniform {
+    color: [f32; 4],
+    dash: [f32; 4],
+}
+
+impl LineUniform {
+    pub fn new(color: Color<f32>, style: LineStyle, scale_factor: f32) -> Self {
+        let (on, off) = style.dash.lengths(style.width * scale_factor).unwrap_or((0.0, 0.0));
+        Self {
+            color: Color { a: color.a * style.opacity, ..color }.to_raw(),
+            dash: [on, off, 0.0, 0.0],
+        }
+    }
+}
+
+pub struct Instance {
+    pub position: cgmath::Vector3<f32>,
+    pub rotation: cgmath::Quaternion<f32>,
+    /// Scale along each axis, applied before rotating.
+    pub scale: cgmath::Vector3<f32>,
+    pub color: Color<f32>,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InstanceRaw {
+    pub model: [[f32; 4]; 4],
+    pub color: [f32; 4],
+}
+
+impl Instance {
+    pub fn to_raw(&self) -> InstanceRaw {
+        InstanceRaw {
+            model: (cgmath::Matrix4::from_translation(self.position)
+                * cgmath::Matrix4::from(self.rotation)
+                * cgmath::Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z))
+                .into(),
+            color: self.color.to_raw(),
+        }
+    }
+}
+
+impl InstanceRaw {
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<InstanceRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 12]>() as wgpu::BufferAddress,
+                    shader_location: 8,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 16]>() as wgpu::BufferAddress,
+                    shader_location: 9,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+pub struct Circle {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u16>,
+}
+
+impl Circle {
+    pub fn new(radius: f32, segments: u16) -> Self {
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u16> = Vec::new();
+
+        vertices.push(Vertex { position: [0.0, 0.0, 0.0] });
+        indices.append(&mut [0, segments, 1].to_vec());
+
+        for s in 0..segments {
+            // trace the circle and place points along it
+            let current_seg = (2.0 * std::f32::consts::PI) * (s as f32 / segments as f32);
+
+            let x = radius * current_seg.cos();
+            let y = radius * current_seg.sin();
+            let z = 0.0;
+
+            vertices.push(Vertex { position: [x, y, z] });
+        }
+
+        for i in 1..segments {
+            indices.append(&mut [0, i, i+1].to_vec());
+        }
+
+        Self {
+            vertices,
+            indices,
+        }
+    }
+}
+
+/// Segments in the outline of the circle marker.
+const CIRCLE_SEGMENTS: u16 = 32;
+/// Half the width of the arms of the cross marker, relative to its radius.
+const CROSS_ARM: f32 = 0.3;
+
+/// Triangles sharing one vertex buffer.
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u16>,
+}
+
+/// Triangulates a polygon around the origin as a fan from its centre, which works for any
+/// polygon whose whole outline can be seen from the centre.
+fn fan(outline: &[[f32; 2]]) -> Mesh {
+    let count = outline.len() as u16;
+    let vertices = std::iter::once([0.0, 0.0])
+        .chain(outline.iter().copied())
+        .map(|[x, y]| Vertex { position: [x, y, 0.0] })
+        .collect();
+    let indices = (1..=count).flat_map(|i| [0, i, i % count + 1]).collect();
+    Mesh { vertices, indices }
+}
+
+/// The shape a point is drawn as.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Marker {
+    #[default]
+    Circle,
+    Square,
+    Triangle,
+    Cross,
+    Diamond,
+}
+
+impl Marker {
+    pub const ALL: [Marker; 5] = [Marker::Circle, Marker::Square, Marker::Triangle, Marker::Cross, Marker::Diamond];
+
+    /// Returns the triangles of the marker, which reaches radius from its centre.
+    pub(crate) fn mesh(self, radius: f32) -> Mesh {
+        match self {
+            Marker::Circle => {
+                let Circle { vertices, indices } = Circle::new(radius, CIRCLE_SEGMENTS);
+                Mesh { vertices, indices }
+            }
+            Marker::Square => {
+                let half = radius * std::f32::consts::FRAC_1_SQRT_2;
+                fan(&[[half, half], [-half, half], [-half, -half], [half, -half]])
+            }
+            Marker::Triangle => {
+                // pointing up, with its corners a third of a turn apart
+                let corners = [90.0f32, 210.0, 330.0].map(|angle| {
+                    let (sin, cos) = angle.to_radians().sin_cos();
+                    [radius * cos, radius * sin]
+                });
+                fan(&corners)
+            }
+            Marker::Cross => {
+                // the arms are shortened so their outer corners stay within radius
+                let w = radius * CROSS_ARM;
+                let r = (radius * radius - w * w).sqrt();
+                fan(&[
+                    [w, w], [w, r], [-w, r], [-w, w],
+                    [-r, w], [-r, -w], [-w, -w], [-w, -r],
+                    [w, -r], [w, -w], [r, -w], [r, w],
+                ])
+            }
+            Marker::Diamond => fan(&[[radius, 0.0], [0.0, radius], [-radius, 0.0], [0.0, -radius]]),
+        }
+    }
+}
+
+/// Points joined one after another by a line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    pub points: Vec<cgmath::Vector2<f32>>,
+    pub color: Color<f32>,
+    /// Width of the line in world units, so it scales with the graph as a bar does, or None for
+    /// the usual width of a line joining points.
+    pub width: Option<f32>,
+}
+
+/// Returns the polylines joining connected points in data order, each in the colour of the point
+/// it starts from. Long runs are split, sharing a point so there is no gap, to keep each within
+/// what u16 indices can address.
+pub fn connected_runs(points: &[Point]) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for pair in points.windows(2) {
+        let (previous, point) = (&pair[0], &pair[1]);
+        if !point.connected {
+            continue;
+        }
+        match runs.last_mut() {
+            // the previous point was joined on to the end of the last run, so this carries it on
+            Some(run) if previous.connected && run.points.len() < MAX_SAMPLES => run.points.push(point.position),
+            _ => runs.push(Run { points: vec![previous.position, point.position], color: previous.color, width: None }),
+        }
+    }
+    runs
+}
+
+/// Returns the point on a polyline through points closest to p, or None if there are no points.
+fn nearest_on_polyline(points: &[cgmath::Vector2<f32>], p: cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>> {
+    use cgmath::InnerSpace;
+
+    let nearest_on_segment = |a: cgmath::Vector2<f32>, b: cgmath::Vector2<f32>| {
+        let ab = b - a;
+        let t = if ab.magnitude2() > 0.0 { ((p - a).dot(ab) / ab.magnitude2()).clamp(0.0, 1.0) } else { 0.0 };
+        a + ab * t
+    };
+
+    match points {
+        [point] => Some(*point),
+        _ => points.windows(2)
+            .map(|pair| nearest_on_segment(pair[0], pair[1]))
+            .min_by(|a, b| (a - p).magnitude2().total_cmp(&(b - p).magnitude2())),
+    }
+}
+
+pub struct Line {
+    /// The equation as written, kept so it can be parsed again when a parameter changes.
+    pub equation: String,
+    pub coeffs: Vec<f32>,
+    pub color: Color<f32>,
+    pub style: LineStyle,
+    pub visible: bool,
+    /// Set when the coefficients or the view change and the line needs tessellating again.
+    pub dirty: bool,
+    pub vertices: Vec<StrokeVertex>,
+    pub indices: Vec<u16>,
+    pub uniform_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    pub vertex_buffer: DynamicBuffer,
+    pub index_buffer: DynamicBuffer,
+}
+
+impl Line {
+    pub fn new(device: &wgpu::Device,
+        coeffs: Vec<f32>,
+        buffer_size: u64,
+        color: Color<f32>,
+        scale_factor: f32,
+        bind_group_layout: &wgpu::BindGroupLayout
+    ) -> Self {
+        let vertices = Vec::new();
+        let indices = Vec::new();
+
+        let vertex_buffer = DynamicBuffer::new(
+            device,
+            "Equation Vertex Buffer",
+            wgpu::BufferUsages::VERTEX,
+            buffer_size,
+        );
+
+        let index_buffer = DynamicBuffer::new(
+            device,
+            "Equation Index Buffer",
+            wgpu::BufferUsages::INDEX,
+            buffer_size,
+        );
+
+        let style = LineStyle::default();
+        let uniform = LineUniform::new(color, style, scale_factor);
+        
+        let uniform_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Line Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let bind_group = device.create_bind_group(
+            &wgpu::BindGroupDescriptor {
+                layout: bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniform_buffer.as_entire_binding(),
+                    }
+                ],
+                label: Some("Line Bind Group"),
+            }
+        );
+
+        Self {
+            equation: String::new(),
+            coeffs,
+            color,
+            style,
+            visible: true,
+            dirty: true,
+            vertices,
+            indices,
+            uniform_buffer,
+            bind_group,
+            vertex_buffer,
+            index_buffer,
+        }
+    }
+
+    /// Writes the colour and style to the line's uniform, with dashes sized for scale_factor.
+    pub fn write_uniform(&self, queue: &wgpu::Queue, scale_factor: f32) {
+        let uniform = LineUniform::new(self.color, self.style, scale_factor);
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    pub fn set_tessellation(&mut self, tessellation: Tessellation) {
+        self.vertices = tessellation.vertices;
+        self.indices = tessellation.indices;
+    }
+
+    /// Returns the distance in world units from p to the curve, if the line is shown and passes
+    /// within radius of p.
+    pub fn distance_to(&self, p: cgmath::Vector2<f32>, radius: f32, world_per_pixel: f32) -> Option<f32> {
+        use cgmath::InnerSpace;
+
+        self.nearest_point(p, radius, world_per_pixel).map(|nearest| (nearest - p).magnitude())
+    }
+
+    /// Returns the point on the curve closest to p, if the line is shown and passes within
+    /// radius of p.
+    pub fn nearest_point(&self, p: cgmath::Vector2<f32>, radius: f32, world_per_pixel: f32) -> Option<cgmath::Vector2<f32>> {
+        use cgmath::InnerSpace;
+
+        if !self.visible || self.coeffs.is_empty() {
+            return None;
+        }
+
+        // only the part of the curve above and below the window around p can be close enough
+        let points = sample_polynomial(&self.coeffs, p.x - radius, p.x + radius, world_per_pixel);
+        nearest_on_polyline(&points, p).filter(|nearest| (nearest - p).magnitude() <= radius)
+    }
+
+    /// Writes the tessellation to the GPU. If it is too large to, the line is cleared so that
+    /// nothing is drawn from the old buffers.
+    pub fn update_buffers(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<(), EngineError> {
+        let written = self.vertex_buffer.write(device, queue, bytemuck::cast_slice(&self.vertices))
+            .and_then(|_| self.index_buffer.write(device, queue, bytemuck::cast_slice(&self.indices)));
+        if written.is_err() {
+            self.vertices.clear();
+            self.indices.clear();
+        }
+        written.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_to_polyline() {
+        use approx::relative_eq;
+        use cgmath::InnerSpace;
+
+        let points = [cgmath::vec2(0.0, 0.0), cgmath::vec2(2.0, 0.0), cgmath::vec2(2.0, 2.0)];
+        let distance = |p: cgmath::Vector2<f32>| (nearest_on_polyline(&points, p).unwrap() - p).magnitude();
+
+        assert!(relative_eq!(distance(cgmath::vec2(1.0, 1.0)), 1.0));
+        assert!(relative_eq!(distance(cgmath::vec2(3.0, 1.0)), 1.0));
+        assert!(relative_eq!(distance(cgmath::vec2(-3.0, 4.0)), 5.0));
+        assert_eq!(nearest_on_polyline(&points, cgmath::vec2(3.0, 1.0)), Some(cgmath::vec2(2.0, 1.0)));
+        assert_eq!(nearest_on_polyline(&[], cgmath::vec2(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn line_uniform_scales_dashes_and_opacity() {
+        let color = Color { r: 1.0, g: 0.0, b: 0.0, a: 0.8 };
+        let style = LineStyle { width: 2.0, dash: Dash::Dashed, opacity: 0.5 };
+        let uniform = LineUniform::new(color, style, 2.0);
+
+        assert_eq!(uniform.color, [1.0, 0.0, 0.0, 0.4]);
+        assert_eq!(uniform.dash, [16.0, 12.0, 0.0, 0.0]);
+        assert_eq!(LineUniform::new(color, LineStyle::default(), 2.0).dash, [0.0; 4]);
+    }
+
+    #[test]
+    fn connected_points_form_runs() {
+        let point = |x: f32, connected: bool| Point { connected, ..Point::new(cgmath::vec2(x, 0.0)) };
+        let points = [point(0.0, false), point(1.0, true), point(2.0, true), point(3.0, false), point(4.0, true)];
+
+        let runs = connected_runs(&points);
+
+        let xs = runs.iter().map(|run| run.points.iter().map(|p| p.x).collect::<Vec<_>>()).collect::<Vec<_>>();
+        assert_eq!(xs, [vec![0.0, 1.0, 2.0], vec![3.0, 4.0]]);
+        // the first point is never joined to anything before it
+        assert!(connected_runs(&[point(0.0, true)]).is_empty());
+    }
+
+    #[test]
+    fn markers_fit_within_their_radius() {
+        for marker in Marker::ALL {
+            let mesh = marker.mesh(2.0);
+
+            assert!(mesh.indices.len() % 3 == 0);
+            assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.vertices.len()));
+            for vertex in &mesh.vertices {
+                let [x, y, _] = vertex.position;
+                assert!(x.hypot(y) <= 2.0 + 1e-5, "{marker:?} reaches {}", x.hypot(y));
+            }
+        }
+    }
+
+    #[test]
+    fn fans_are_wound_consistently() {
+        // every triangle of a convex outline listed anticlockwise is anticlockwise
+        let mesh = Marker::Square.mesh(1.0);
+
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].position);
+            let cross = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+            assert!(cross > 0.0);
+        }
+    }
+
+    #[test]
+    fn circle_vertices_on_circle() {
+        use approx::relative_eq;
+
+        let radius = 1.0;
+        let circle = Circle::new(radius, 32);
+
+        for vertex in circle.vertices {
+            if vertex.position != [0.0, 0.0, 0.0] {
+                let x_squared = vertex.position[0].powf(2.0);
+                let y_squared = vertex.position[1].powf(2.0);
+                let r_squared = radius.powf(2.0);
+                
+                assert!(relative_eq!(x_squared + y_squared, r_squared));
+            }
+        }
+    }
+}
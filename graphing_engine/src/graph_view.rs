@@ -0,0 +1,371 @@
+//! An egui widget showing an interactive graph, for egui applications drawing with wgpu.
+
+use std::sync::Arc;
+
+use crate::{Config, CursorIcon, EngineInput, Key, Modifiers, PointerButton, RenderTargetConfig, ScrollDelta, State, TouchPhase, Viewport};
+
+/// A graph which can be shown in any egui panel, drawn through an egui_wgpu paint callback.
+///
+/// The graph is rendered into a texture of its own each time it is shown, which the callback then
+/// draws into egui's pass, so the engine stays on the thread that owns it. Pointer, scroll and
+/// touch input over the widget, and keys while it has focus, are passed on to the engine.
+///
+/// ```no_run
+/// # fn ui(ui: &mut egui::Ui, render_state: &egui_wgpu::RenderState, view: &mut Option<graphing_engine::GraphView>) {
+/// let view = view.get_or_insert_with(|| graphing_engine::GraphView::new(render_state, &Default::default()));
+/// if ui.button("Add parabola").clicked() {
+///     let (engine, device) = view.engine_and_device();
+///     let line = engine.add_line(device, Vec::new(), graphing_engine::Color { r: 0.8, g: 0.2, b: 0.2, a: 1.0 });
+///     engine.update_line(line, "x^2 - 1").unwrap();
+/// }
+/// view.show(ui);
+/// # }
+/// ```
+pub struct GraphView {
+    engine: State,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    target: RenderTargetConfig,
+    pipeline: Arc<wgpu::RenderPipeline>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// The texture the graph was last drawn into and the bind group sampling it.
+    texture: Option<(wgpu::Texture, Arc<wgpu::BindGroup>)>,
+    modifiers: Modifiers,
+    cursor: CursorIcon,
+}
+
+/// Draws the graph's texture over the rect of a paint callback.
+struct GraphPaint {
+    pipeline: Arc<wgpu::RenderPipeline>,
+    bind_group: Arc<wgpu::BindGroup>,
+}
+
+impl egui_wgpu::CallbackTrait for GraphPaint {
+    fn paint(
+        &self,
+        _info: egui::PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'static>,
+        _callback_resources: &egui_wgpu::CallbackResources,
+    ) {
+        // egui has set the viewport to the callback's rect, which the triangle covers
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Returns the engine key an egui key pans with. Keep in step with KEY_BINDINGS.
+fn engine_key(key: egui::Key) -> Option<Key> {
+    match key {
+        egui::Key::W | egui::Key::ArrowUp => Some(Key::Up),
+        egui::Key::S | egui::Key::ArrowDown => Some(Key::Down),
+        egui::Key::A | egui::Key::ArrowLeft => Some(Key::Left),
+        egui::Key::D | egui::Key::ArrowRight => Some(Key::Right),
+        _ => None,
+    }
+}
+
+/// Translates an egui event to engine input, with positions in physical pixels from the top left
+/// of rect. Returns None for events the engine has no use for.
+fn engine_input(event: &egui::Event, rect: egui::Rect, pixels_per_point: f32) -> Option<EngineInput> {
+    let physical = |pos: egui::Pos2| (pos - rect.min) * pixels_per_point;
+    match event {
+        egui::Event::PointerMoved(pos) => {
+            let pos = physical(*pos);
+            Some(EngineInput::PointerMove { x: pos.x, y: pos.y })
+        }
+        egui::Event::PointerButton { button, pressed, .. } => {
+            let button = match button {
+                egui::PointerButton::Primary => PointerButton::Primary,
+                egui::PointerButton::Secondary => PointerButton::Secondary,
+                egui::PointerButton::Middle => PointerButton::Middle,
+                _ => return None,
+            };
+            Some(EngineInput::PointerButton { button, pressed: *pressed })
+        }
+        egui::Event::MouseWheel { unit, delta, .. } => Some(EngineInput::Scroll(match unit {
+            egui::MouseWheelUnit::Point => ScrollDelta::Pixels { x: delta.x * pixels_per_point, y: delta.y * pixels_per_point },
+            egui::MouseWheelUnit::Line | egui::MouseWheelUnit::Page => ScrollDelta::Lines { x: delta.x, y: delta.y },
+        })),
+        egui::Event::Zoom(factor) => Some(EngineInput::Pinch { delta: factor - 1.0 }),
+        egui::Event::Touch { id, phase, pos, .. } => {
+            let phase = match phase {
+                egui::TouchPhase::Start => TouchPhase::Started,
+                egui::TouchPhase::Move => TouchPhase::Moved,
+                egui::TouchPhase::End | egui::TouchPhase::Cancel => TouchPhase::Ended,
+            };
+            let pos = physical(*pos);
+            Some(EngineInput::Touch { id: id.0, phase, x: pos.x, y: pos.y })
+        }
+        egui::Event::Key { key, pressed, .. } => Some(EngineInput::Key { key: engine_key(*key)?, pressed: *pressed }),
+        _ => None,
+    }
+}
+
+impl GraphView {
+    /// Creates a graph drawing with the device and into the target format of egui's renderer.
+    pub fn new(render_state: &egui_wgpu::RenderState, config: &Config) -> Self {
+        let device = render_state.device.clone();
+        let queue = render_state.queue.clone();
+        let target = RenderTargetConfig::new(render_state.target_format);
+        let engine = State::new(&device, &queue, dpi::PhysicalSize::new(1, 1), &target, config, 1.0);
+
+        let shader = device.create_shader_module(wgpu::include_wgsl!("graph_view.wgsl"));
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Graph View Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Graph View Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Graph View Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target.format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Graph View Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            engine,
+            device,
+            queue,
+            target,
+            pipeline: Arc::new(pipeline),
+            bind_group_layout,
+            sampler,
+            texture: None,
+            modifiers: Modifiers::default(),
+            cursor: CursorIcon::default(),
+        }
+    }
+
+    pub fn engine(&self) -> &State {
+        &self.engine
+    }
+
+    pub fn engine_mut(&mut self) -> &mut State {
+        &mut self.engine
+    }
+
+    /// Returns the engine along with the device it draws with, for the calls which need both,
+    /// such as adding a line.
+    pub fn engine_and_device(&mut self) -> (&mut State, &wgpu::Device) {
+        (&mut self.engine, &self.device)
+    }
+
+    /// Shows the graph filling the space available, passing the input over it to the engine.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+        if response.clicked() || response.drag_started() {
+            response.request_focus();
+        }
+        let pixels_per_point = ui.ctx().pixels_per_point();
+
+        self.route_input(ui, rect, &response, pixels_per_point);
+        if response.hovered() || response.dragged() {
+            let cursor = match self.cursor {
+                CursorIcon::Default => egui::CursorIcon::Default,
+                CursorIcon::Grabbing => egui::CursorIcon::Grabbing,
+                CursorIcon::Crosshair => egui::CursorIcon::Crosshair,
+            };
+            ui.ctx().set_cursor_icon(cursor);
+        }
+
+        let size = dpi::PhysicalSize::new(
+            ((rect.width() * pixels_per_point).round() as u32).max(1),
+            ((rect.height() * pixels_per_point).round() as u32).max(1),
+        );
+        self.engine.set_scale_factor(pixels_per_point);
+        if let Some(bind_group) = self.draw(size) {
+            ui.painter().add(egui_wgpu::Callback::new_paint_callback(rect, GraphPaint {
+                pipeline: self.pipeline.clone(),
+                bind_group,
+            }));
+        }
+        if self.engine.needs_redraw() {
+            ui.ctx().request_repaint();
+        }
+
+        response
+    }
+
+    /// Passes this frame's input over the widget on to the engine. Pointer input goes to it while
+    /// the pointer is over it or dragging from it, and keys while it has focus.
+    fn route_input(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, pixels_per_point: f32) {
+        let (events, modifiers) = ui.input(|input| (input.events.clone(), input.modifiers));
+        let modifiers = Modifiers { ctrl: modifiers.ctrl, shift: modifiers.shift, alt: modifiers.alt };
+        if modifiers != self.modifiers {
+            self.modifiers = modifiers;
+            self.cursor = self.engine.input(&EngineInput::Modifiers(modifiers)).cursor;
+        }
+
+        let pointer = response.hovered() || response.dragged();
+        let mut consumed = false;
+        for event in &events {
+            let routed = match event {
+                egui::Event::Key { .. } => response.has_focus(),
+                // releases still go to the engine once the pointer has left, so drags finish
+                egui::Event::PointerButton { pressed: false, .. } => true,
+                egui::Event::Touch { pos, .. } => rect.contains(*pos),
+                _ => pointer,
+            };
+            if let Some(input) = engine_input(event, rect, pixels_per_point).filter(|_| routed) {
+                let response = self.engine.input(&input);
+                consumed |= response.consumed;
+                self.cursor = response.cursor;
+            }
+        }
+        if consumed {
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// Draws the graph into a texture of size, recreating it if the size has changed, and returns
+    /// the bind group sampling it.
+    fn draw(&mut self, size: dpi::PhysicalSize<u32>) -> Option<Arc<wgpu::BindGroup>> {
+        let unchanged = self.texture.as_ref().is_some_and(|(texture, _)| {
+            texture.width() == size.width && texture.height() == size.height
+        });
+        if !unchanged {
+            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Graph View Texture"),
+                size: wgpu::Extent3d {
+                    width: size.width,
+                    height: size.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.target.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Graph View Bind Group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+            if let Some((old, _)) = self.texture.replace((texture, Arc::new(bind_group))) {
+                old.destroy();
+            }
+        }
+        let (texture, bind_group) = self.texture.as_ref()?;
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Graph View Encoder"),
+        });
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Graph View Clear Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(self.engine.theme().clear_color()),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        let viewport = Viewport { x: 0.0, y: 0.0, size };
+        if let Err(e) = self.engine.render_to_view(&self.device, &self.queue, &mut encoder, &view, size, viewport) {
+            log::error!("drawing the graph view failed: {e}");
+            return None;
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.engine.read_profiler(&self.device);
+        self.engine.trim_atlas();
+
+        Some(bind_group.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_physical_pixels_from_the_widget() {
+        let rect = egui::Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(200.0, 100.0));
+
+        let moved = engine_input(&egui::Event::PointerMoved(egui::pos2(110.0, 60.0)), rect, 2.0);
+        assert_eq!(moved, Some(EngineInput::PointerMove { x: 20.0, y: 20.0 }));
+
+        let wheel = egui::Event::MouseWheel {
+            unit: egui::MouseWheelUnit::Point,
+            delta: egui::vec2(0.0, 3.0),
+            modifiers: egui::Modifiers::NONE,
+        };
+        assert_eq!(engine_input(&wheel, rect, 2.0), Some(EngineInput::Scroll(ScrollDelta::Pixels { x: 0.0, y: 6.0 })));
+    }
+
+    #[test]
+    fn only_bound_keys_are_passed_on() {
+        let key = |key| egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        };
+
+        assert_eq!(engine_input(&key(egui::Key::W), egui::Rect::ZERO, 1.0), Some(EngineInput::Key { key: Key::Up, pressed: true }));
+        assert_eq!(engine_input(&key(egui::Key::Q), egui::Rect::ZERO, 1.0), None);
+    }
+}
@@ -0,0 +1,26 @@
+// Draws the texture a GraphView rendered the graph into over the rect of its paint callback.
+
+@group(0) @binding(0)
+var graph_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var graph_sampler: sampler;
+
+struct VertexOutput {
+  @builtin(position) clip_position: vec4<f32>,
+  @location(0) tex_coords: vec2<f32>,
+};
+
+// a single triangle covering the viewport, with texture coordinates 0 to 1 across it
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+  let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+  var out: VertexOutput;
+  out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+  out.tex_coords = uv;
+  return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  return textureSample(graph_texture, graph_sampler, in.tex_coords);
+}
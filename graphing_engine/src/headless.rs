@@ -0,0 +1,134 @@
+use pollster::FutureExt;
+
+use crate::{Bounds, Color, Config, EngineError, Point, RenderTargetConfig, State, Viewport};
+
+/// Colours given to the equations of a plot in turn.
+const COLORS: [Color<f32>; 4] = [
+    Color { r: 0.85, g: 0.2, b: 0.2, a: 1.0 },
+    Color { r: 0.2, g: 0.4, b: 0.85, a: 1.0 },
+    Color { r: 0.15, g: 0.65, b: 0.3, a: 1.0 },
+    Color { r: 0.6, g: 0.3, b: 0.75, a: 1.0 },
+];
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Equations and points drawn to an image without a window, for notebooks and scripts.
+///
+/// ```no_run
+/// use graphing_engine::{Bounds, Plot};
+///
+/// let plot = Plot {
+///     equations: vec!["x^2 - 1".to_owned()],
+///     bounds: Bounds { x_min: -2.0, x_max: 2.0, y_min: -2.0, y_max: 4.0 },
+///     ..Default::default()
+/// };
+/// // in evcxr, shows the plot below the cell
+/// plot.evcxr_display();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plot {
+    /// Equations in the same syntax as the calculator's, coloured in turn.
+    pub equations: Vec<String>,
+    pub points: Vec<Point>,
+    /// The region of the graph drawn, which is widened or heightened to keep the axes to the
+    /// same scale.
+    pub bounds: Bounds,
+    /// Size of the image in pixels.
+    pub size: dpi::PhysicalSize<u32>,
+}
+
+impl Default for Plot {
+    fn default() -> Self {
+        Self {
+            equations: Vec::new(),
+            points: Vec::new(),
+            bounds: Bounds { x_min: -5.0, x_max: 5.0, y_min: -5.0, y_max: 5.0 },
+            size: dpi::PhysicalSize::new(600, 400),
+        }
+    }
+}
+
+impl Plot {
+    /// Draws the plot on a new graphics device and returns its pixels as rows of RGBA bytes, top
+    /// row first.
+    pub fn to_rgba(&self) -> Result<Vec<u8>, EngineError> {
+        let instance = wgpu::Instance::default();
+        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default())
+            .block_on()
+            .ok_or_else(|| EngineError::Capture("no graphics adapter was found".to_owned()))?;
+        let (device, queue) = adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
+            },
+            None,
+        ).block_on().map_err(|e| EngineError::Capture(e.to_string()))?;
+
+        let target = RenderTargetConfig::new(wgpu::TextureFormat::Rgba8UnormSrgb);
+        let mut engine = State::new(&device, &queue, self.size, &target, &Config::default(), 1.0);
+        for (text, color) in self.equations.iter().zip(COLORS.iter().cycle()) {
+            let id = engine.add_line(&device, Vec::new(), *color);
+            engine.update_line(id, text)?;
+        }
+        engine.set_points(&self.points)?;
+        engine.set_viewport(Viewport { x: 0.0, y: 0.0, size: self.size });
+        engine.fit_bounds(self.bounds);
+
+        engine.capture(&device, &queue, &target, self.size)
+    }
+
+    /// Draws the plot on a new graphics device and returns it as a PNG file.
+    pub fn to_png(&self) -> Result<Vec<u8>, EngineError> {
+        let pixels = self.to_rgba()?;
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.size.width, self.size.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|e| EngineError::Capture(e.to_string()))?;
+        Ok(png)
+    }
+
+    /// Prints the plot as a PNG in evcxr's display protocol, so a notebook shows it inline, or
+    /// prints why it couldn't be drawn.
+    pub fn evcxr_display(&self) {
+        match self.to_png() {
+            Ok(png) => println!("EVCXR_BEGIN_CONTENT image/png\n{}\nEVCXR_END_CONTENT", base64(&png)),
+            Err(e) => println!("the plot couldn't be drawn: {e}"),
+        }
+    }
+}
+
+/// Encodes bytes as padded standard base64.
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_is_padded_to_groups_of_four() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe]), "//4=");
+    }
+}
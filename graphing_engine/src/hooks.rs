@@ -0,0 +1,65 @@
+use crate::{Bounds, EngineError, LineId, PickResult};
+
+/// Events emitted by the engine when the document or view changes, or drawing it fails.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EngineEvent {
+    /// The equation of a line was replaced, or its coefficients changed with a parameter.
+    EquationChanged { id: LineId },
+    /// A point was added at index, at x, y in world units.
+    PointAdded { index: usize, x: f32, y: f32 },
+    /// The camera moved or zoomed, or the view was resized. x and y are the centre of the view,
+    /// zoom is the camera distance from the graph and bounds is the region now visible.
+    ViewportChanged { x: f32, y: f32, zoom: f32, bounds: Bounds },
+    /// A different line or point was clicked, or the selected line was removed.
+    SelectionChanged { selected: Option<PickResult> },
+    /// The point at index was dragged to x, y in world units. Hosts use this to let a point
+    /// drive a parameter, such as the centre of a circle.
+    PointMoved { index: usize, x: f32, y: f32 },
+    /// Updating the graph failed, leaving part of it undrawn. The error is also returned by
+    /// take_errors. Errors returned directly by other calls aren't emitted.
+    Error(EngineError),
+}
+
+type Hook = Box<dyn FnMut(&EngineEvent)>;
+
+/// Callbacks registered against engine events, run in registration order.
+#[derive(Default)]
+pub struct Hooks {
+    hooks: Vec<Hook>,
+}
+
+impl Hooks {
+    pub fn register(&mut self, hook: impl FnMut(&EngineEvent) + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    pub fn emit(&mut self, event: EngineEvent) {
+        for hook in &mut self.hooks {
+            hook(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const BOUNDS: Bounds = Bounds { x_min: -1.0, x_max: 1.0, y_min: -1.0, y_max: 1.0 };
+
+    #[test]
+    fn test_hooks_receive_events_in_order() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let mut hooks = Hooks::default();
+
+        let first = received.clone();
+        hooks.register(move |_| first.borrow_mut().push(1));
+        let second = received.clone();
+        hooks.register(move |_| second.borrow_mut().push(2));
+
+        hooks.emit(EngineEvent::ViewportChanged { x: 0.0, y: 0.0, zoom: 4.0, bounds: BOUNDS });
+
+        assert_eq!(*received.borrow(), [1, 2]);
+    }
+}
@@ -0,0 +1,329 @@
+//! Input events understood by the engine.
+//!
+//! Hosts translate events from their own windowing framework into EngineInput, so the engine does
+//! not depend on how the events were produced. from_window_event does this for winit, with the
+//! winit feature enabled.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "winit")]
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, Touch, WindowEvent};
+#[cfg(feature = "winit")]
+use winit::keyboard::PhysicalKey;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointerButton {
+    Primary,
+    Secondary,
+    Middle,
+}
+
+/// Keys the engine responds to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Key {
+    /// What the key does, for the help.
+    pub fn description(self) -> &'static str {
+        match self {
+            Key::Up => "Pan up",
+            Key::Down => "Pan down",
+            Key::Left => "Pan left",
+            Key::Right => "Pan right",
+        }
+    }
+}
+
+/// Physical keys, named by their code in the UI Events standard as browsers and winit do, and
+/// the engine keys they are translated to.
+pub const KEY_BINDINGS: [(&str, Key); 8] = [
+    ("KeyW", Key::Up),
+    ("ArrowUp", Key::Up),
+    ("KeyS", Key::Down),
+    ("ArrowDown", Key::Down),
+    ("KeyA", Key::Left),
+    ("ArrowLeft", Key::Left),
+    ("KeyD", Key::Right),
+    ("ArrowRight", Key::Right),
+];
+
+/// Returns the engine key bound to the physical key with the UI Events code, if any.
+pub fn key_for_code(code: &str) -> Option<Key> {
+    KEY_BINDINGS.iter().find(|(bound, _)| *bound == code).map(|&(_, key)| key)
+}
+
+/// KEY_BINDINGS with the codes of some engine keys replaced, such as to pan with IJKL. Keys
+/// without their own codes keep the ones KEY_BINDINGS gives them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyBindings {
+    overrides: BTreeMap<Key, Vec<String>>,
+}
+
+impl KeyBindings {
+    /// Binds key to the physical keys with the UI Events codes in place of its default ones.
+    pub fn bind(&mut self, key: Key, codes: Vec<String>) {
+        self.overrides.insert(key, codes);
+    }
+
+    /// Returns key to the codes KEY_BINDINGS gives it.
+    pub fn reset(&mut self, key: Key) {
+        self.overrides.remove(&key);
+    }
+
+    /// Returns each code with the engine key it is bound to, in the order of KEY_BINDINGS.
+    pub fn codes(&self) -> Vec<(&str, Key)> {
+        let mut codes: Vec<(&str, Key)> = Vec::new();
+        for (code, key) in KEY_BINDINGS {
+            match self.overrides.get(&key) {
+                // a replaced key's codes go where its first default one was
+                Some(overrides) if !codes.iter().any(|&(_, bound)| bound == key) => {
+                    codes.extend(overrides.iter().map(|code| (code.as_str(), key)));
+                }
+                Some(_) => {}
+                None => codes.push((code, key)),
+            }
+        }
+        codes
+    }
+
+    /// Returns the engine key bound to the physical key with the UI Events code, if any.
+    pub fn key_for_code(&self, code: &str) -> Option<Key> {
+        self.codes().into_iter().find(|(bound, _)| *bound == code).map(|(_, key)| key)
+    }
+
+    /// Returns a line of help for each keyboard and pointer control, as controls_help does for
+    /// the default bindings.
+    pub fn controls_help(&self) -> Vec<(String, &'static str)> {
+        let mut keys: Vec<(Key, Vec<String>)> = Vec::new();
+        for (code, key) in self.codes() {
+            let name = match code {
+                "ArrowUp" => "↑",
+                "ArrowDown" => "↓",
+                "ArrowLeft" => "←",
+                "ArrowRight" => "→",
+                // letter keys are named like KeyW
+                _ => code.trim_start_matches("Key"),
+            }.to_owned();
+            match keys.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, names)) => names.push(name),
+                None => keys.push((key, vec![name])),
+            }
+        }
+
+        keys.into_iter()
+            .map(|(key, names)| (names.join(" / "), key.description()))
+            .chain(POINTER_CONTROLS.iter().map(|&(control, description)| (control.to_owned(), description)))
+            .collect()
+    }
+}
+
+/// Mouse, trackpad and touch controls of the graph, for the help. Keep in step with
+/// CameraController::process_events and State::drag_point.
+pub const POINTER_CONTROLS: [(&str, &str); 11] = [
+    ("Drag", "Pan, with any button"),
+    ("Click a point", "Select it, showing its coordinates"),
+    ("Shift + click a point", "Add it to or remove it from the selection"),
+    ("Drag a point", "Move the point"),
+    ("Alt + drag a point", "Move the point without snapping"),
+    ("Scroll wheel", "Zoom about the pointer"),
+    ("Trackpad scroll", "Pan, or zoom with Ctrl held"),
+    ("Pinch", "Zoom about the fingers"),
+    ("Shift + drag", "Zoom to the box dragged out"),
+    ("Double click", "Centre on the point clicked"),
+    ("Double click an axis", "Centre on the origin"),
+];
+
+/// Returns a line of help for each keyboard and pointer control, built from KEY_BINDINGS and
+/// POINTER_CONTROLS. Keys bound to the same action share a line.
+pub fn controls_help() -> Vec<(String, &'static str)> {
+    KeyBindings::default().controls_help()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScrollDelta {
+    /// Scrolling in lines, as produced by a mouse wheel.
+    Lines { x: f32, y: f32 },
+    /// Precise scrolling in physical pixels, as produced by a trackpad.
+    Pixels { x: f32, y: f32 },
+}
+
+/// Modifier keys held while other input happens.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    /// The finger was lifted, or the touch was cancelled by the system.
+    Ended,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EngineInput {
+    /// The pointer moved to a position in physical pixels relative to the render target.
+    PointerMove { x: f32, y: f32 },
+    PointerButton { button: PointerButton, pressed: bool },
+    Scroll(ScrollDelta),
+    Key { key: Key, pressed: bool },
+    /// A finger touched, moved on or left the screen at a position in physical pixels relative to
+    /// the render target. id tells apart fingers which are down at the same time.
+    Touch { id: u64, phase: TouchPhase, x: f32, y: f32 },
+    /// A trackpad pinch, where positive deltas zoom in. A delta of 0.1 magnifies by 10%.
+    Pinch { delta: f32 },
+    /// The set of held modifier keys changed.
+    Modifiers(Modifiers),
+}
+
+/// The cursor the host should show over the graph.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CursorIcon {
+    #[default]
+    Default,
+    Grabbing,
+    /// Shown while dragging out a box to zoom to.
+    Crosshair,
+}
+
+/// Feedback for the host after an input has been handled.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputResponse {
+    /// Whether the engine used the input, in which case the host should not handle it further.
+    pub consumed: bool,
+    pub cursor: CursorIcon,
+}
+
+#[cfg(feature = "winit")]
+impl EngineInput {
+    /// Translates a winit window event, returning None for events the engine has no use for.
+    /// winit names its key codes after the UI Events ones that KEY_BINDINGS uses.
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        Self::from_window_event_with(event, &KeyBindings::default())
+    }
+
+    /// Translates a winit window event as from_window_event does, with keys bound by bindings.
+    pub fn from_window_event_with(event: &WindowEvent, bindings: &KeyBindings) -> Option<Self> {
+        match event {
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state,
+                        physical_key: PhysicalKey::Code(keycode),
+                        ..
+                    },
+                    ..
+            } => {
+                let key = bindings.key_for_code(&format!("{keycode:?}"))?;
+                Some(EngineInput::Key { key, pressed: *state == ElementState::Pressed })
+            },
+            WindowEvent::MouseWheel {
+                delta,
+                ..
+            } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Lines { x: *x, y: *y },
+                    MouseScrollDelta::PixelDelta(position) => ScrollDelta::Pixels {
+                        x: position.x as f32,
+                        y: position.y as f32,
+                    },
+                };
+                Some(EngineInput::Scroll(delta))
+            },
+            WindowEvent::CursorMoved {
+                position,
+                ..
+            } => Some(EngineInput::PointerMove { x: position.x as f32, y: position.y as f32 }),
+            WindowEvent::MouseInput {
+                state,
+                button,
+                ..
+            } => {
+                let button = match button {
+                    MouseButton::Left => PointerButton::Primary,
+                    MouseButton::Right => PointerButton::Secondary,
+                    MouseButton::Middle => PointerButton::Middle,
+                    _ => return None,
+                };
+                Some(EngineInput::PointerButton { button, pressed: *state == ElementState::Pressed })
+            }
+            WindowEvent::Touch(Touch {
+                id,
+                phase,
+                location,
+                ..
+            }) => {
+                let phase = match phase {
+                    winit::event::TouchPhase::Started => TouchPhase::Started,
+                    winit::event::TouchPhase::Moved => TouchPhase::Moved,
+                    winit::event::TouchPhase::Ended | winit::event::TouchPhase::Cancelled => TouchPhase::Ended,
+                };
+                Some(EngineInput::Touch { id: *id, phase, x: location.x as f32, y: location.y as f32 })
+            }
+            WindowEvent::PinchGesture {
+                delta,
+                ..
+            } => Some(EngineInput::Pinch { delta: *delta as f32 }),
+            WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                Some(EngineInput::Modifiers(Modifiers {
+                    ctrl: state.control_key(),
+                    shift: state.shift_key(),
+                    alt: state.alt_key(),
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_controls_help() {
+        let help = controls_help();
+
+        assert_eq!(help[0], ("W / ↑".to_owned(), "Pan up"));
+        assert_eq!(help.len(), 4 + POINTER_CONTROLS.len());
+    }
+
+    #[test]
+    fn keys_are_found_by_code() {
+        assert_eq!(key_for_code("ArrowLeft"), Some(Key::Left));
+        assert_eq!(key_for_code("KeyQ"), None);
+    }
+
+    #[test]
+    fn replaced_keys_lose_their_default_codes() {
+        let mut bindings = KeyBindings::default();
+        bindings.bind(Key::Up, vec!["KeyI".to_owned()]);
+
+        assert_eq!(bindings.key_for_code("KeyI"), Some(Key::Up));
+        assert_eq!(bindings.key_for_code("KeyW"), None);
+        assert_eq!(bindings.key_for_code("ArrowDown"), Some(Key::Down));
+        assert_eq!(bindings.controls_help()[0], ("I".to_owned(), "Pan up"));
+
+        bindings.reset(Key::Up);
+        assert_eq!(bindings, KeyBindings::default());
+        assert_eq!(bindings.codes(), KEY_BINDINGS);
+    }
+
+    #[cfg(feature = "winit")]
+    #[test]
+    fn winit_key_codes_are_named_like_the_bindings() {
+        assert_eq!(format!("{:?}", winit::keyboard::KeyCode::ArrowLeft), "ArrowLeft");
+        assert_eq!(format!("{:?}", winit::keyboard::KeyCode::KeyW), "KeyW");
+    }
+}
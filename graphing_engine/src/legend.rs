@@ -0,0 +1,125 @@
+//! A key in a corner of the graph, naming the curve drawn in each colour.
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Color;
+
+/// Length of the colour swatch beside each name, relative to the text size.
+const SWATCH_LENGTH: f32 = 1.5;
+/// Thickness of the swatch relative to the text size.
+const SWATCH_THICKNESS: f32 = 0.2;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 4] = [Corner::TopLeft, Corner::TopRight, Corner::BottomLeft, Corner::BottomRight];
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegendEntry {
+    pub name: String,
+    pub color: Color<f32>,
+}
+
+/// The curves listed in the legend, in order, and where it sits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Legend {
+    pub corner: Corner,
+    pub entries: Vec<LegendEntry>,
+}
+
+/// A rectangle in physical pixels from the top left of the viewport.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub min: cgmath::Vector2<f32>,
+    pub size: cgmath::Vector2<f32>,
+}
+
+/// Where the parts of the legend are drawn, in physical pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub frame: Rect,
+    /// Top left of the names, which are laid out one per line.
+    pub text: cgmath::Vector2<f32>,
+    /// The colour swatch for each entry, in order.
+    pub swatches: Vec<Rect>,
+}
+
+/// Lays out a legend of rows names, the widest of which is text_width, in the corner of a
+/// viewport of size. Returns None if there are no rows.
+pub fn layout(corner: Corner, size: dpi::PhysicalSize<u32>, text_width: f32, rows: usize, text_size: f32) -> Option<Layout> {
+    if rows == 0 {
+        return None;
+    }
+
+    // the same margin the titles keep from the edges
+    let margin = text_size / 2.0;
+    let padding = text_size / 2.0;
+    let swatch = cgmath::vec2(text_size * SWATCH_LENGTH, (text_size * SWATCH_THICKNESS).max(1.0));
+    let frame_size = cgmath::vec2(
+        padding * 3.0 + swatch.x + text_width,
+        padding * 2.0 + rows as f32 * text_size,
+    );
+
+    let (width, height) = (size.width as f32, size.height as f32);
+    let left = match corner {
+        Corner::TopLeft | Corner::BottomLeft => margin,
+        Corner::TopRight | Corner::BottomRight => width - margin - frame_size.x,
+    };
+    let top = match corner {
+        Corner::TopLeft | Corner::TopRight => margin,
+        Corner::BottomLeft | Corner::BottomRight => height - margin - frame_size.y,
+    };
+    let min = cgmath::vec2(left, top);
+
+    let swatches = (0..rows)
+        .map(|i| Rect {
+            min: min + cgmath::vec2(padding, padding + i as f32 * text_size + (text_size - swatch.y) / 2.0),
+            size: swatch,
+        })
+        .collect();
+
+    Some(Layout {
+        frame: Rect { min, size: frame_size },
+        text: min + cgmath::vec2(padding * 2.0 + swatch.x, padding),
+        swatches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_top_right() {
+        let size = dpi::PhysicalSize::new(400, 300);
+
+        let layout = layout(Corner::TopRight, size, 50.0, 2, 10.0).unwrap();
+
+        // 5 padding either side and between the 15 wide swatch and the text, 2 rows of 10
+        assert_eq!(layout.frame, Rect { min: cgmath::vec2(315.0, 5.0), size: cgmath::vec2(80.0, 30.0) });
+        assert_eq!(layout.text, cgmath::vec2(340.0, 10.0));
+        assert_eq!(layout.swatches[1], Rect { min: cgmath::vec2(320.0, 24.0), size: cgmath::vec2(15.0, 2.0) });
+    }
+
+    #[test]
+    fn test_layout_bottom_left() {
+        let size = dpi::PhysicalSize::new(400, 300);
+
+        let layout = layout(Corner::BottomLeft, size, 50.0, 1, 10.0).unwrap();
+
+        assert_eq!(layout.frame.min, cgmath::vec2(5.0, 300.0 - 5.0 - 20.0));
+    }
+
+    #[test]
+    fn empty_legends_are_not_drawn() {
+        assert_eq!(layout(Corner::TopLeft, dpi::PhysicalSize::new(400, 300), 0.0, 0, 10.0), None);
+    }
+}
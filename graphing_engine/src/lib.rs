@@ -0,0 +1,1372 @@
+//! A wgpu renderer for graphs of equations, points and data series.
+//!
+//! [`State`] holds everything on the graph and draws it into part of a render pass, so it can be
+//! embedded in any application with a wgpu device. It knows nothing of windows: the host passes
+//! pointer and key input in as [`EngineInput`] and sizes in physical pixels as the [`dpi`] types
+//! winit uses. With the `winit` feature, `EngineInput::from_window_event` translates winit's
+//! events.
+//!
+//! Each frame the host feeds in input, then calls [`State::update`] and [`State::grid_prepare`]
+//! before [`State::render`] in a pass drawing into a target described by the same
+//! [`RenderTargetConfig`] the engine was created with.
+//!
+//! Hosts which would rather not manage the pass can call [`State::render_to_view`], which does all
+//! of this in a pass of its own drawing over part of a texture view.
+//!
+//! In egui applications drawing with egui_wgpu, the `egui` feature adds `GraphView`, a widget
+//! showing an interactive graph in any panel, with input taken from egui.
+//!
+//! Hosts learn of changes to the graph, such as the view moving or a point being dragged, from
+//! the [`EngineEvent`]s passed to callbacks registered with [`State::on_event`].
+//!
+//! ```no_run
+//! use graphing_engine::{Color, Config, EngineInput, PhysicalSize, RenderTargetConfig, State, Viewport};
+//!
+//! fn draw(device: &wgpu::Device, queue: &wgpu::Queue, view: &wgpu::TextureView) {
+//!     let target = RenderTargetConfig::new(wgpu::TextureFormat::Bgra8UnormSrgb);
+//!     let size = PhysicalSize::new(800, 600);
+//!     let mut engine = State::new(device, queue, size, &target, &Config::default(), 1.0);
+//!     engine.set_viewport(Viewport { x: 0.0, y: 0.0, size });
+//!
+//!     let line = engine.add_line(device, Vec::new(), Color { r: 0.8, g: 0.2, b: 0.2, a: 1.0 });
+//!     engine.update_line(line, "x^2 - 1").unwrap();
+//!     engine.input(&EngineInput::PointerMove { x: 400.0, y: 300.0 });
+//!
+//!     engine.update(device, queue);
+//!     engine.grid_prepare(device, queue);
+//!     let mut encoder = device.create_command_encoder(&Default::default());
+//!     {
+//!         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+//!             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+//!                 view,
+//!                 resolve_target: None,
+//!                 ops: wgpu::Operations {
+//!                     load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+//!                     store: wgpu::StoreOp::Store,
+//!                 },
+//!             })],
+//!             ..Default::default()
+//!         });
+//!         engine.render(&mut render_pass, size).unwrap();
+//!     }
+//!     queue.submit([encoder.finish()]);
+//! }
+//! ```
+
+#[cfg(feature = "text")]
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use wgpu::{self, util::DeviceExt};
+
+mod analysis;
+mod box_plot;
+mod buffer;
+mod cache;
+mod capture;
+mod format;
+mod geometry;
+mod camera;
+mod error;
+#[cfg(feature = "egui")]
+mod graph_view;
+#[cfg(feature = "headless")]
+mod headless;
+mod hooks;
+mod input;
+mod legend;
+mod mapping;
+mod pipeline;
+mod polar;
+mod profiler;
+mod series;
+mod snap;
+mod streamlines;
+mod tessellation;
+mod text;
+mod theme;
+
+pub use analysis::{analyse, Analysis, Extremum, Trend};
+pub use error::EngineError;
+pub use format::format_number;
+pub use geometry::{Anchor, Annotation, AnnotationText, Color, Dash, LineStyle, Marker, Point, StrokeVertex};
+#[cfg(feature = "egui")]
+pub use graph_view::GraphView;
+#[cfg(feature = "headless")]
+pub use headless::Plot;
+pub use hooks::EngineEvent;
+pub use input::{controls_help, key_for_code, CursorIcon, EngineInput, InputResponse, Key, KeyBindings, Modifiers, PointerButton, ScrollDelta, TouchPhase};
+pub use legend::{Corner, Legend, LegendEntry};
+pub use mapping::GridMapping;
+pub use pipeline::{equation_parameters, LineId, LineInfo, ParseError, SYNTAX as EQUATION_SYNTAX};
+pub use polar::{PolarRegion, PolarSide};
+pub use camera::{Bounds, Projection, ZoomLimits};
+pub use dpi::{self, PhysicalPosition, PhysicalSize};
+pub use text::{Titles, TEXT_SIZE as DEFAULT_LABEL_SIZE};
+pub use series::{Chart, Series};
+pub use snap::{Snap, SnapTarget};
+pub use streamlines::VectorField;
+pub use tessellation::{sample_polynomial, Tessellation};
+pub use theme::{GridStyle, Theme};
+
+/*
+pub enum EquationType {
+    Polynomial,
+    Exponential, // TODO
+    Trigonometric, // TODO
+    Circle, // TODO
+}
+*/
+
+/// Resource sizes and paths used when creating the engine.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Initial size in bytes of each equation's vertex and index buffers, which grow as needed.
+    pub line_buffer_size: u64,
+    /// Initial size in bytes of the point instance buffer, which grows as needed.
+    pub point_buffer_size: u64,
+    /// File the compiled pipeline cache is loaded from and saved to, where the device supports it.
+    pub pipeline_cache_path: Option<std::path::PathBuf>,
+    /// Time each part of the render pass on the GPU, if the device supports timestamp queries.
+    pub gpu_profiling: bool,
+    /// How far in and out the graph can be zoomed.
+    pub zoom_limits: camera::ZoomLimits,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            line_buffer_size: 1_000_000,
+            point_buffer_size: 100_000,
+            pipeline_cache_path: None,
+            gpu_profiling: false,
+            zoom_limits: camera::ZoomLimits::default(),
+        }
+    }
+}
+
+impl Config {
+    /// A configuration with small buffers for use on problematic GPUs and drivers.
+    pub fn safe_mode() -> Self {
+        Self {
+            line_buffer_size: 256_000,
+            point_buffer_size: 16_000,
+            pipeline_cache_path: None,
+            gpu_profiling: false,
+            zoom_limits: camera::ZoomLimits::default(),
+        }
+    }
+}
+
+/// Format and sample count of the texture the graph is drawn into.
+///
+/// Every pipeline drawing into the same pass, including the text renderer and the GUI, has to be
+/// built from the same target or wgpu will reject the draw, so this is passed to all of them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderTargetConfig {
+    pub format: wgpu::TextureFormat,
+    pub sample_count: u32,
+}
+
+impl RenderTargetConfig {
+    pub fn new(format: wgpu::TextureFormat) -> Self {
+        Self {
+            format,
+            sample_count: 1,
+        }
+    }
+
+    pub fn multisample_state(&self) -> wgpu::MultisampleState {
+        wgpu::MultisampleState {
+            count: self.sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        }
+    }
+}
+
+/// The region of the render target the graph is drawn into, in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub size: dpi::PhysicalSize<u32>,
+}
+
+/// Where the pointer is over the graph, for drawing guide lines through it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Crosshair {
+    /// The pointer position in physical pixels relative to the render target.
+    pub position: dpi::PhysicalPosition<f32>,
+    /// The point on the graph under the pointer.
+    pub world: cgmath::Vector2<f32>,
+    /// Where the axes cross in physical pixels relative to the render target, which may be
+    /// outside the viewport.
+    pub origin: dpi::PhysicalPosition<f32>,
+    /// Size of one physical pixel in world units, which tells how precisely world is known.
+    pub world_per_pixel: f32,
+}
+
+/// A point under the pointer, for describing it in a tooltip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HoveredPoint {
+    /// Where the point is drawn in physical pixels relative to the render target.
+    pub position: dpi::PhysicalPosition<f32>,
+    pub world: cgmath::Vector2<f32>,
+    /// The name of the data series the point belongs to, or None for a point of its own.
+    pub series: Option<String>,
+}
+
+/// Returns the part of viewport within a target of target_size as the x, y, width and height of
+/// a scissor rect, or None if none of it is, as wgpu rejects scissor rects outside the target.
+fn scissor_rect(viewport: Viewport, target_size: dpi::PhysicalSize<u32>) -> Option<(u32, u32, u32, u32)> {
+    let left = viewport.x.max(0.0) as u32;
+    let top = viewport.y.max(0.0) as u32;
+    let right = ((viewport.x + viewport.size.width as f32).max(0.0) as u32).min(target_size.width);
+    let bottom = ((viewport.y + viewport.size.height as f32).max(0.0) as u32).min(target_size.height);
+    (right > left && bottom > top).then(|| (left, top, right - left, bottom - top))
+}
+
+/// Distance in logical pixels from a curve which still counts as pointing at it.
+const PICK_TOLERANCE: f32 = 6.0;
+/// How long the view must stay still before streamlines are traced across it again. Tracing
+/// calls the field many times on this thread, so it isn't done on every frame of a pan or zoom.
+const STREAMLINE_SETTLE: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// A vector field drawn as streamlines, with the views they were traced across.
+struct TracedField {
+    field: VectorField,
+    /// The view the streamlines on screen were traced across, if they have been.
+    traced: Option<Bounds>,
+    /// The view since it last changed and when, while it differs from the traced one.
+    moved: Option<(Bounds, std::time::Instant)>,
+}
+
+/// What is drawn under a position on the graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PickResult {
+    /// The line with this id.
+    Line(LineId),
+    /// The point at this index.
+    Point(usize),
+}
+
+/// Everything on a graph, with the GPU resources to draw it: the camera, the equations, points and
+/// data series, the grid, labels and legend, and the theme they are drawn in.
+pub struct State {
+    camera: camera::Camera,
+    camera_uniform: camera::CameraUniform,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    /// Camera for the grid, which is placed relative to the point under the eye.
+    relative_camera_uniform: camera::CameraUniform,
+    relative_camera_buffer: wgpu::Buffer,
+    relative_camera_bind_group: wgpu::BindGroup,
+    camera_controller: camera::CameraController,
+    grid_text: text::GridText,
+    grid_pipeline: pipeline::GridPipeline,
+    point_pipeline: pipeline::PointPipeline,
+    equation_pipeline: pipeline::EquationPipeline,
+    legend_pipeline: pipeline::LegendPipeline,
+    legend: Option<Legend>,
+    /// The data series last set, kept to tell when they change.
+    series: Vec<Series>,
+    /// Labels of the data series' categories, drawn along the x axis.
+    #[cfg(feature = "text")]
+    category_labels: Vec<series::CategoryLabel>,
+    /// The complex function whose image of the grid is drawn, if any.
+    grid_mapping: Option<GridMapping>,
+    /// The vector field drawn as streamlines, if any, with the view they were last traced across.
+    vector_field: Option<TracedField>,
+    /// The polar region shaded, if any, with the view its shading last reached across.
+    polar_region: Option<(polar::SampledRegion, Option<Bounds>)>,
+    pipeline_cache: cache::PipelineCache,
+    profiler: Option<profiler::GpuProfiler>,
+    theme: Theme,
+    scale_factor: f32,
+    viewport: Viewport,
+    hooks: hooks::Hooks,
+    /// The point being dragged with the primary button, if any.
+    dragged_point: Option<usize>,
+    /// The line or point last clicked.
+    selected: Option<PickResult>,
+    /// The points picked by clicking them, with shift held to pick more than one.
+    selected_points: BTreeSet<usize>,
+    snap: Snap,
+    last_bounds: Option<Bounds>,
+    /// Failures while updating since the host last took them.
+    errors: Vec<EngineError>,
+}
+
+impl State {
+    /// Creates the pipelines for drawing into target, with the view starting at the origin. size
+    /// is that of the area drawn into, which set_viewport changes later.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: dpi::PhysicalSize<u32>,
+        target: &RenderTargetConfig,
+        engine_config: &Config,
+        scale_factor: f32,
+    ) -> State {
+        let camera = camera::Camera {
+            projection: Projection::default(),
+            eye: (0.0, 0.0, camera::HOME_ZOOM).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: size.width as f32 / size.height as f32,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let mut camera_uniform = camera::CameraUniform::new();
+        camera_uniform.update_view_proj(&camera);
+        
+        let camera_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Camera Buffer"),
+                contents: bytemuck::cast_slice(&[camera_uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries:  &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }
+            ],
+            label: Some("Bind Group Layout"),
+        });
+
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                }
+            ],
+            label: Some("Camera Bind Group"),
+        });
+
+        let mut relative_camera_uniform = camera::CameraUniform::new();
+        relative_camera_uniform.update_relative_view_proj(&camera);
+
+        let relative_camera_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Relative Camera Buffer"),
+                contents: bytemuck::cast_slice(&[relative_camera_uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let relative_camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: relative_camera_buffer.as_entire_binding(),
+                }
+            ],
+            label: Some("Relative Camera Bind Group"),
+        });
+
+        let camera_controller = camera::CameraController::new(0.1, engine_config.zoom_limits);
+
+        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[
+                &bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let color_render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[
+                &bind_group_layout,
+                &bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let mut pipeline_cache = cache::PipelineCache::new(device, engine_config.pipeline_cache_path.as_deref());
+
+        let point_pipeline = pipeline::PointPipeline::new(
+            device,
+            &mut pipeline_cache,
+            &render_pipeline_layout,
+            target,
+            engine_config.point_buffer_size,
+        );
+        let grid_pipeline = pipeline::GridPipeline::new(
+            device,
+            &mut pipeline_cache,
+            &render_pipeline_layout,
+            target,
+        );
+        let grid_text = text::GridText::new(device, queue, target, scale_factor);
+        let legend_pipeline = pipeline::LegendPipeline::new(
+            device,
+            &mut pipeline_cache,
+            &render_pipeline_layout,
+            &bind_group_layout,
+            target,
+        );
+
+        let equation_pipeline = pipeline::EquationPipeline::new(
+            device,
+            &mut pipeline_cache,
+            &color_render_pipeline_layout,
+            bind_group_layout,
+            target,
+            engine_config.line_buffer_size,
+        );
+
+
+        Self {
+            camera,
+            camera_uniform,
+            camera_buffer,
+            camera_bind_group,
+            relative_camera_uniform,
+            relative_camera_buffer,
+            relative_camera_bind_group,
+            camera_controller,
+            grid_text,
+            grid_pipeline,
+            point_pipeline,
+            equation_pipeline,
+            legend_pipeline,
+            legend: None,
+            series: Vec::new(),
+            #[cfg(feature = "text")]
+            category_labels: Vec::new(),
+            grid_mapping: None,
+            vector_field: None,
+            polar_region: None,
+            pipeline_cache,
+            profiler: engine_config.gpu_profiling
+                .then(|| profiler::GpuProfiler::new(device, queue))
+                .flatten(),
+            theme: Theme::default(),
+            scale_factor,
+            viewport: Viewport {
+                x: 0.0,
+                y: 0.0,
+                size,
+            },
+            hooks: hooks::Hooks::default(),
+            dragged_point: None,
+            selected: None,
+            selected_points: BTreeSet::new(),
+            snap: Snap::default(),
+            last_bounds: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Sets the region of the render target to draw into, which also becomes the area used for
+    /// cursor positions and the camera aspect ratio.
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        if viewport == self.viewport || viewport.size.width == 0 || viewport.size.height == 0 {
+            return;
+        }
+        self.viewport = viewport;
+        self.camera_controller.set_origin(dpi::PhysicalPosition::new(viewport.x, viewport.y));
+
+        let new_size = viewport.size;
+        let new_aspect = new_size.width as f32 / new_size.height as f32;
+        if new_aspect <= 3.0 {
+            self.camera.aspect = new_aspect;
+        }
+    }
+
+    /// Registers a callback which is run for every EngineEvent emitted by the engine, so hosts can
+    /// react to changes without polling.
+    pub fn on_event(&mut self, hook: impl FnMut(&EngineEvent) + 'static) {
+        self.hooks.register(hook);
+    }
+
+    /// Registers a callback which is run with the visible region of the graph whenever it
+    /// changes, for keeping other views in step with the graph.
+    pub fn on_viewport_changed(&mut self, mut callback: impl FnMut(&Bounds) + 'static) {
+        self.hooks.register(move |event| {
+            if let EngineEvent::ViewportChanged { bounds, .. } = event {
+                callback(bounds);
+            }
+        });
+    }
+
+    /// Registers a callback which is run with the new selection whenever a different line or
+    /// point is clicked, or the selection is cleared.
+    pub fn on_selection_changed(&mut self, mut callback: impl FnMut(Option<PickResult>) + 'static) {
+        self.hooks.register(move |event| {
+            if let EngineEvent::SelectionChanged { selected } = event {
+                callback(*selected);
+            }
+        });
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.camera.projection = projection;
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Changes how the axes and gridlines are drawn, keeping the rest of the theme.
+    pub fn set_grid_style(&mut self, style: GridStyle) {
+        self.theme.grid = style;
+    }
+
+    /// Sets what dragged points snap to.
+    pub fn set_snap(&mut self, snap: Snap) {
+        self.snap = snap;
+    }
+
+    /// Returns where a point dragged to world snaps to, which is world itself if snapping is off,
+    /// alt is held or nothing to snap to is within the snap radius.
+    fn snapped(&self, world: cgmath::Vector2<f32>) -> cgmath::Vector2<f32> {
+        use cgmath::InnerSpace;
+
+        if self.camera_controller.modifiers().alt {
+            return world;
+        }
+        let world_per_pixel = self.camera.world_units_per_pixel(self.viewport.size);
+        let radius = self.snap.radius * self.scale_factor * world_per_pixel;
+        let target = match self.snap.target {
+            SnapTarget::Off => None,
+            SnapTarget::Grid => Some(snap::grid_crossing(world, self.grid_pipeline.snap_step)),
+            SnapTarget::Curve => self.equation_pipeline.nearest_point(world, radius, world_per_pixel),
+        };
+        target.filter(|target| (target - world).magnitude() <= radius).unwrap_or(world)
+    }
+
+    /// Sets whether the x axis is spaced and labelled in fractions of π.
+    pub fn set_pi_ticks(&mut self, pi_ticks: bool) {
+        self.grid_pipeline.pi_ticks = pi_ticks;
+    }
+
+    /// Sets the size of the axis labels in logical pixels. Titles are sized to match.
+    pub fn set_label_size(&mut self, size: f32) {
+        self.grid_text.set_font_size(size);
+    }
+
+    /// Sets the curves listed in the legend and where it sits, or hides it for None.
+    pub fn set_legend(&mut self, legend: Option<Legend>) {
+        if legend == self.legend {
+            return;
+        }
+        let names = legend.iter().flat_map(|legend| &legend.entries).map(|entry| entry.name.as_str());
+        self.grid_text.set_legend_names(names);
+        self.legend = legend;
+    }
+
+    pub fn set_titles(&mut self, titles: &Titles) {
+        self.grid_text.set_titles(titles);
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor == self.scale_factor {
+            return;
+        }
+        self.scale_factor = scale_factor;
+        self.grid_text.set_scale_factor(scale_factor);
+    }
+
+    /// Handles pointer and key input over the graph, dragging points or moving the camera.
+    pub fn input(&mut self, event: &EngineInput) -> InputResponse {
+        let _span = tracing::trace_span!("engine_input").entered();
+        let consumed = self.drag_point(event) || self.camera_controller.process_events(event);
+
+        InputResponse {
+            consumed,
+            cursor: if self.dragged_point.is_some() {
+                CursorIcon::Grabbing
+            } else {
+                self.camera_controller.cursor_icon()
+            },
+        }
+    }
+
+    /// Returns what is drawn under a position in physical pixels relative to the render target.
+    ///
+    /// Points are drawn over lines, so they are preferred, and otherwise the nearest line within
+    /// a few pixels is returned.
+    pub fn pick(&self, pos: dpi::PhysicalPosition<f32>) -> Option<PickResult> {
+        let size = self.viewport.size;
+        let pos = cgmath::vec2(pos.x - self.viewport.x, pos.y - self.viewport.y);
+
+        if let Some(index) = self.point_pipeline.point_at(&self.camera, size, self.scale_factor, pos) {
+            return Some(PickResult::Point(index));
+        }
+
+        let world_per_pixel = self.camera.world_units_per_pixel(size);
+        let world = self.camera.screen_to_world_space(pos, size);
+        self.equation_pipeline
+            .line_at(world, PICK_TOLERANCE * self.scale_factor * world_per_pixel, world_per_pixel)
+            .map(PickResult::Line)
+    }
+
+    /// Returns the indices of the selected points, in order.
+    pub fn selected_points(&self) -> &BTreeSet<usize> {
+        &self.selected_points
+    }
+
+    fn set_selected(&mut self, selected: Option<PickResult>) {
+        if selected != self.selected {
+            self.selected = selected;
+            self.hooks.emit(EngineEvent::SelectionChanged { selected });
+        }
+    }
+
+    /// Picks up, moves and drops points with the primary button, returning true if the event
+    /// was used so the camera doesn't pan as well. Clicking a point selects only it, and clicking
+    /// one with shift held adds it to or removes it from the selection without picking it up.
+    fn drag_point(&mut self, event: &EngineInput) -> bool {
+        match (event, self.dragged_point) {
+            (EngineInput::PointerButton { button: PointerButton::Primary, pressed: true }, None) => {
+                let cursor = self.camera_controller.cursor_location();
+                let pos = dpi::PhysicalPosition::new(cursor.x + self.viewport.x, cursor.y + self.viewport.y);
+                let picked = self.pick(pos);
+                if self.camera_controller.modifiers().shift {
+                    // shift dragging anywhere else zooms to a box, so leave it to the camera
+                    let Some(PickResult::Point(index)) = picked else {
+                        return false;
+                    };
+                    let added = self.selected_points.insert(index);
+                    if !added {
+                        self.selected_points.remove(&index);
+                    }
+                    self.set_selected(added.then_some(PickResult::Point(index)));
+                    return true;
+                }
+
+                self.selected_points.clear();
+                if let Some(PickResult::Point(index)) = picked {
+                    self.selected_points.insert(index);
+                }
+                self.set_selected(picked);
+                self.dragged_point = match picked {
+                    Some(PickResult::Point(index)) => Some(index),
+                    _ => None,
+                };
+                self.dragged_point.is_some()
+            }
+            (EngineInput::PointerMove { .. }, Some(index)) => {
+                // keep the controller's cursor up to date, which doesn't pan without a button
+                self.camera_controller.process_events(event);
+                let cursor = self.camera_controller.cursor_location();
+                let world = self.snapped(self.camera.screen_to_world_space(cgmath::vec2(cursor.x, cursor.y), self.viewport.size));
+                self.point_pipeline.move_point(index, world);
+                self.hooks.emit(EngineEvent::PointMoved { index, x: world.x, y: world.y });
+                true
+            }
+            (EngineInput::PointerButton { button: PointerButton::Primary, pressed: false }, Some(_)) => {
+                self.dragged_point = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the graph will change on the next update without any further input, so
+    /// another frame should be drawn.
+    pub fn needs_redraw(&self) -> bool {
+        self.camera_controller.is_moving()
+            || self.equation_pipeline.needs_update()
+            // streamlines waiting for the view to settle
+            || self.vector_field.as_ref().is_some_and(|field| field.moved.is_some())
+    }
+
+    /// Returns anything which has gone wrong while updating since this was last called, for the
+    /// host to show.
+    pub fn take_errors(&mut self) -> Vec<EngineError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Returns true while any line is waiting to be tessellated or for its tessellation to finish
+    /// in the background.
+    pub fn is_tessellating(&self) -> bool {
+        self.equation_pipeline.needs_update()
+    }
+
+    /// Returns the corners of the box being dragged out to zoom to, if any, in physical pixels
+    /// relative to the render target. Hosts draw this over the graph.
+    pub fn selection(&self) -> Option<(dpi::PhysicalPosition<f32>, dpi::PhysicalPosition<f32>)> {
+        self.camera_controller.selection()
+    }
+
+    /// Returns where the pointer is over the graph, or None if it is outside the viewport.
+    pub fn crosshair(&self) -> Option<Crosshair> {
+        let size = self.viewport.size;
+        let cursor = self.camera_controller.cursor_location();
+        if cursor.x < 0.0 || cursor.y < 0.0 || cursor.x >= size.width as f32 || cursor.y >= size.height as f32 {
+            return None;
+        }
+
+        let world = self.camera.screen_to_world_space(cgmath::vec2(cursor.x, cursor.y), size);
+        let origin = self.camera.world_to_screen_space(cgmath::vec3(0.0, 0.0, 0.0), size);
+        let offset = |x: f32, y: f32| dpi::PhysicalPosition::new(x + self.viewport.x, y + self.viewport.y);
+
+        Some(Crosshair {
+            position: offset(cursor.x, cursor.y),
+            world,
+            origin: offset(origin.x, origin.y),
+            world_per_pixel: self.camera.world_units_per_pixel(size),
+        })
+    }
+
+    /// Returns the point or data series point nearest the pointer, if it is over one and no point
+    /// is being dragged.
+    pub fn hovered_point(&self) -> Option<HoveredPoint> {
+        let size = self.viewport.size;
+        let cursor = self.camera_controller.cursor_location();
+        if self.dragged_point.is_some()
+            || cursor.x < 0.0 || cursor.y < 0.0 || cursor.x >= size.width as f32 || cursor.y >= size.height as f32 {
+            return None;
+        }
+
+        let candidates = || {
+            let own = self.point_pipeline.points().iter().map(|point| (point.clone(), None));
+            let series = self.series.iter()
+                .flat_map(|series| series.drawn_points().map(|point| (point, Some(series.name.as_str()))));
+            own.chain(series)
+        };
+        let screen = |point: &Point| self.camera.world_to_screen_space(point.position.extend(0.0), size);
+        let index = pipeline::nearest_within(
+            candidates().map(|(point, _)| (screen(&point), (point.radius + pipeline::POINT_HIT_SLOP) * self.scale_factor)),
+            cgmath::vec2(cursor.x, cursor.y),
+        )?;
+
+        let (point, series) = candidates().nth(index)?;
+        let position = screen(&point);
+        Some(HoveredPoint {
+            position: dpi::PhysicalPosition::new(position.x + self.viewport.x, position.y + self.viewport.y),
+            world: point.position,
+            series: series.map(str::to_owned),
+        })
+    }
+
+    /// Moves the camera for input since the last frame and brings every buffer up to date with
+    /// it. Call once a frame before drawing.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let _span = tracing::debug_span!("update").entered();
+        let size = self.viewport.size;
+        let reported = self.errors.len();
+        self.camera_controller.update_camera(&mut self.camera, size);
+        // the bounds also change with the aspect ratio and projection, not just the eye
+        let bounds = self.camera.visible_bounds();
+        if self.last_bounds != Some(bounds) {
+            self.last_bounds = Some(bounds);
+            self.hooks.emit(EngineEvent::ViewportChanged {
+                x: self.camera.eye.x,
+                y: self.camera.eye.y,
+                zoom: self.camera.eye.z,
+                bounds,
+            });
+        }
+        self.trace_streamlines(bounds);
+        self.shade_polar_region(device, queue, bounds);
+        let upload = tracing::debug_span!("upload").entered();
+        self.camera_uniform.update_view_proj(&self.camera);
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
+        self.relative_camera_uniform.update_relative_view_proj(&self.camera);
+        queue.write_buffer(&self.relative_camera_buffer, 0, bytemuck::cast_slice(&[self.relative_camera_uniform]));
+        let grid = self.grid_pipeline.update_grid(device, queue, &self.camera, &self.theme.grid, size, self.scale_factor);
+        let points = self.point_pipeline.update_points(device, queue, &self.camera, size, self.scale_factor);
+        self.errors.extend(grid.err().into_iter().chain(points.err()));
+        if let Some(runs) = self.point_pipeline.take_connections() {
+            self.equation_pipeline.set_polylines(device, queue, runs);
+        }
+        self.grid_text.resize(queue, size);
+        self.update_legend(device, queue);
+        #[cfg(feature = "text")]
+        self.update_point_labels();
+        self.equation_pipeline.update_equations(device, queue, &self.camera, size, self.scale_factor);
+        drop(upload);
+        self.errors.extend(self.equation_pipeline.take_errors());
+        for error in &self.errors[reported..] {
+            self.hooks.emit(EngineEvent::Error(error.clone()));
+        }
+    }
+
+    /// Traces the vector field's streamlines across bounds, unless they already were. Once traced,
+    /// they are only traced again after the view has settled, and the previous lines stay on
+    /// screen meanwhile.
+    fn trace_streamlines(&mut self, bounds: Bounds) {
+        let Some(TracedField { field, traced, moved }) = &mut self.vector_field else {
+            return;
+        };
+        if *traced == Some(bounds) {
+            *moved = None;
+            return;
+        }
+        if traced.is_some() {
+            match *moved {
+                Some((since, at)) if since == bounds && at.elapsed() >= STREAMLINE_SETTLE => {}
+                Some((since, _)) if since == bounds => return,
+                _ => {
+                    *moved = Some((bounds, std::time::Instant::now()));
+                    return;
+                }
+            }
+        }
+        *traced = Some(bounds);
+        *moved = None;
+
+        let _span = tracing::debug_span!("trace_streamlines").entered();
+        let separation = field.separation * self.scale_factor * self.camera.world_units_per_pixel(self.viewport.size);
+        let runs = streamlines::trace(&*field.field, bounds, separation)
+            .into_iter()
+            .map(|points| geometry::Run { points, color: field.color, width: None })
+            .collect();
+        self.point_pipeline.set_streamlines(runs);
+    }
+
+    /// Shades the polar region across bounds, unless it already was. Shading inside the boundary
+    /// doesn't depend on the view, but is cheap enough to redo with the shading outside it.
+    fn shade_polar_region(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, bounds: Bounds) {
+        let Some((region, shaded)) = &mut self.polar_region else {
+            return;
+        };
+        if *shaded == Some(bounds) {
+            return;
+        }
+        *shaded = Some(bounds);
+        let shading = (region.shading(&bounds), region.region.fill);
+        self.equation_pipeline.set_shading(device, queue, Some(shading));
+    }
+
+    /// Lays out the legend for the viewport and text size, which both change its size and place.
+    fn update_legend(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let entries = self.legend.as_ref().map_or(&[][..], |legend| &legend.entries);
+        let layout = self.legend.as_ref().and_then(|legend| {
+            legend::layout(legend.corner, self.viewport.size, self.grid_text.legend_width(), entries.len(), self.grid_text.text_size)
+        });
+        let legend = self.legend_pipeline.update_legend(device, queue, layout.as_ref(), entries, &self.theme, self.viewport.size, self.scale_factor);
+        self.errors.extend(legend.err());
+        self.grid_text.legend_origin = layout.map(|layout| layout.text);
+    }
+
+    /// Places each point's annotation beside it on the screen, clear of the point, so it follows
+    /// the point through pans and zooms. Category labels go below the tick labels of the x axis,
+    /// staying on screen when the axis leaves it as they do.
+    #[cfg(feature = "text")]
+    fn update_point_labels(&mut self) {
+        let size = self.viewport.size;
+        let world_per_pixel = self.camera.world_units_per_pixel(size);
+        let labels = self.point_pipeline.points().iter().filter_map(|point| {
+            let annotation = point.label.as_ref()?;
+            let text = match &annotation.text {
+                AnnotationText::Custom(text) => Cow::Borrowed(text.as_str()),
+                AnnotationText::Coordinates => Cow::Owned(coordinates(point, world_per_pixel)),
+            };
+            Some(text::PlacedAnnotation {
+                text,
+                point: self.camera.world_to_screen_space(point.position.extend(0.0), size),
+                clearance: point.radius * self.scale_factor,
+                anchor: annotation.anchor,
+                offset: annotation.offset * self.scale_factor,
+            })
+        });
+
+        let text_size = self.grid_text.text_size;
+        let clearance = text_size * 1.25;
+        let axis_y = self.camera.world_to_screen_space(cgmath::vec3(0.0, 0.0, 0.0), size).y
+            .clamp(0.0, (size.height as f32 - clearance - text_size).max(0.0));
+        let categories = self.category_labels.iter().map(|label| text::PlacedAnnotation {
+            text: Cow::Borrowed(label.text.as_str()),
+            point: cgmath::vec2(self.camera.world_to_screen_space(cgmath::vec3(label.x, 0.0, 0.0), size).x, axis_y),
+            clearance,
+            anchor: Anchor::Below,
+            offset: cgmath::vec2(0.0, 0.0),
+        });
+
+        self.grid_text.set_point_labels(labels.chain(categories));
+    }
+
+    /// Lays out the axis labels, titles and legend text for drawing. Call after update.
+    pub fn grid_prepare(
+        &mut self,
+        device: &wgpu::Device, 
+        queue: &wgpu::Queue,
+    ) {
+        let _span = tracing::debug_span!("text_prepare").entered();
+        self.grid_text.prepare(
+            device, 
+            queue,
+            self.viewport.size, 
+            &self.camera, 
+            &self.grid_pipeline,
+            self.theme.text,
+        );
+    }
+    
+    /// Draws the graph into its viewport of render_pass, whose target is target_size. Only the
+    /// part of the viewport within the target is drawn, and nothing if none of it is.
+    pub fn render<'render_pass>(
+        &'render_pass self,
+        render_pass: &mut wgpu::RenderPass<'render_pass>,
+        target_size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let _span = tracing::debug_span!("render_pass").entered();
+
+        let Some((scissor_x, scissor_y, scissor_width, scissor_height)) = scissor_rect(self.viewport, target_size) else {
+            return Ok(());
+        };
+        let Viewport { x, y, size } = self.viewport;
+        render_pass.set_viewport(x, y, size.width as f32, size.height as f32, 0.0, 1.0);
+        render_pass.set_scissor_rect(scissor_x, scissor_y, scissor_width, scissor_height);
+
+        let timestamp = |render_pass: &mut wgpu::RenderPass, scope| {
+            if let Some(profiler) = &self.profiler {
+                profiler.timestamp(render_pass, scope);
+            }
+        };
+        timestamp(render_pass, None);
+
+        // grid rendering
+        render_pass.set_bind_group(0, &self.relative_camera_bind_group, &[]);
+        render_pass.set_pipeline(&self.grid_pipeline.render_pipeline);
+        render_pass.set_vertex_buffer(0, self.grid_pipeline.vertical_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.grid_pipeline.vertical_instance_buffer.slice());
+        render_pass.draw(0..4, 0..self.grid_pipeline.vertical_instances.len() as _);
+        render_pass.set_vertex_buffer(0, self.grid_pipeline.horizontal_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.grid_pipeline.horizontal_instance_buffer.slice());
+        render_pass.draw(0..4, 0..self.grid_pipeline.horizontal_instances.len() as _);
+        timestamp(render_pass, Some(0));
+
+        // equation rendering 
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_pipeline(&self.equation_pipeline.render_pipeline);
+        // shaded regions go beneath the lines
+        if let Some(shading) = &self.equation_pipeline.shading {
+            render_pass.set_bind_group(1, &shading.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, shading.vertex_buffer.slice());
+            render_pass.set_index_buffer(shading.index_buffer.slice(), wgpu::IndexFormat::Uint16);
+            render_pass.draw_indexed(0..shading.indices.len() as u32, 0, 0..1);
+        }
+        for line in self.equation_pipeline.lines.values().filter(|line| line.visible) {
+            render_pass.set_bind_group(1, &line.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, line.vertex_buffer.slice());
+            render_pass.set_index_buffer(line.index_buffer.slice(), wgpu::IndexFormat::Uint16);
+            render_pass.draw_indexed(0..line.indices.len() as u32, 0, 0..1);
+        }
+        for polyline in &self.equation_pipeline.polylines {
+            let line = &polyline.line;
+            render_pass.set_bind_group(1, &line.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, line.vertex_buffer.slice());
+            render_pass.set_index_buffer(line.index_buffer.slice(), wgpu::IndexFormat::Uint16);
+            render_pass.draw_indexed(0..line.indices.len() as u32, 0, 0..1);
+        }
+        timestamp(render_pass, Some(1));
+        // point rendering
+        render_pass.set_pipeline(&self.point_pipeline.render_pipeline);
+        render_pass.set_vertex_buffer(0, self.point_pipeline.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.point_pipeline.instance_buffer.slice());
+        render_pass.set_index_buffer(self.point_pipeline.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        for (indices, instances) in self.point_pipeline.draws() {
+            render_pass.draw_indexed(indices, 0, instances);
+        }
+        timestamp(render_pass, Some(2));
+
+        // the legend's frame goes under its names, so it is timed along with the text
+        render_pass.set_bind_group(0, &self.legend_pipeline.screen_bind_group, &[]);
+        render_pass.set_pipeline(&self.legend_pipeline.render_pipeline);
+        render_pass.set_vertex_buffer(0, self.legend_pipeline.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.legend_pipeline.instance_buffer.slice());
+        render_pass.draw(0..4, 0..self.legend_pipeline.instances.len() as _);
+        
+        self.grid_text.render(render_pass);
+        timestamp(render_pass, Some(3));
+
+        Ok(())
+    }
+
+    /// Draws the graph into a new texture of size and reads it back as RGBA pixels, row by row
+    /// from the top. target must be single-sampled and RGBA or BGRA.
+    ///
+    /// The viewport is set to the whole texture, so the host should set it back before drawing
+    /// the next frame. Lines still being tessellated at size are waited for, for up to five
+    /// seconds, so hosts drawing frames should use try_capture instead.
+    pub fn capture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &RenderTargetConfig,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<Vec<u8>, EngineError> {
+        capture::capture(self, device, queue, target, size)
+    }
+
+    /// Like capture, but returns None straight away if lines are still being tessellated at size.
+    /// The host should keep the viewport as it is and call this again on a later frame, since
+    /// drawing at another size in between starts the tessellation over.
+    pub fn try_capture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &RenderTargetConfig,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<Option<Vec<u8>>, EngineError> {
+        capture::try_capture(self, device, queue, target, size)
+    }
+
+    /// Draws the graph into viewport of view, whose texture is target_size, in a render pass of
+    /// its own on encoder, for hosts compositing it into a frame they draw themselves. Updates the graph and lays out its text
+    /// first, so nothing else needs calling beforehand.
+    ///
+    /// What is already in view is kept rather than cleared, so the host draws any background,
+    /// such as a rectangle of the theme's clear colour, before encoding this.
+    pub fn render_to_view(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        target_size: dpi::PhysicalSize<u32>,
+        viewport: Viewport,
+    ) -> Result<(), wgpu::SurfaceError> {
+        self.set_viewport(viewport);
+        self.update(device, queue);
+        self.grid_prepare(device, queue);
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Graph Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            self.render(&mut render_pass, target_size)?;
+        }
+        self.resolve_profiler(encoder);
+        Ok(())
+    }
+
+    /// Queues the timestamps written by render for reading back. Call after the render pass ends.
+    pub fn resolve_profiler(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(profiler) = &mut self.profiler {
+            profiler.resolve(encoder);
+        }
+    }
+
+    /// Reads back timestamps from earlier frames. Call after the frame has been submitted.
+    pub fn read_profiler(&mut self, device: &wgpu::Device) {
+        if let Some(profiler) = &mut self.profiler {
+            profiler.read(device);
+        }
+    }
+
+    /// The most recent GPU time taken by each part of the render pass, or nothing if profiling
+    /// is off or unsupported.
+    pub fn gpu_timings(&self) -> &[(&'static str, std::time::Duration)] {
+        self.profiler.as_ref().map_or(&[], |profiler| profiler.timings())
+    }
+
+    /// Saves compiled pipelines to the configured cache file.
+    pub fn save_pipeline_cache(&self) -> std::io::Result<()> {
+        self.pipeline_cache.save()
+    }
+
+    pub fn trim_atlas(&mut self) {
+        self.grid_text.trim();
+    }
+    
+    /// Adds a line with coeffs, from x^0 up, returning the id to refer to it by. Set its equation
+    /// with update_line.
+    pub fn add_line(&mut self, device: &wgpu::Device, coeffs: Vec<f32>, color: geometry::Color<f32>) -> LineId {
+        self.equation_pipeline.add_line(device, coeffs, color)
+    }
+
+    /// Returns the equation and style of a line, or None if there is no such line.
+    pub fn line(&self, id: LineId) -> Option<LineInfo<'_>> {
+        self.equation_pipeline.line(id)
+    }
+
+    /// Replaces the equation of a line. If it can't be parsed the line is cleared and
+    /// EngineError::Parse says why.
+    pub fn update_line(&mut self, id: LineId, equation: &str) -> Result<(), EngineError> {
+        let result = self.equation_pipeline.update_line(id, equation);
+        if !matches!(result, Err(EngineError::UnknownLine(_))) {
+            self.hooks.emit(EngineEvent::EquationChanged { id });
+        }
+        result
+    }
+
+    pub fn remove_line(&mut self, id: LineId) -> Result<(), EngineError> {
+        if self.selected == Some(PickResult::Line(id)) {
+            self.set_selected(None);
+        }
+        self.equation_pipeline.remove_line(id)
+    }
+
+    /// Returns whether the line is still being tessellated in the background, in which case the
+    /// previous geometry is drawn.
+    pub fn is_line_pending(&self, id: LineId) -> bool {
+        self.equation_pipeline.is_line_pending(id)
+    }
+
+    /// Returns the value of a line's equation at x, with the current parameter values.
+    pub fn evaluate(&self, id: LineId, x: f32) -> Option<f32> {
+        self.equation_pipeline.evaluate(id, x)
+    }
+
+    /// Returns n evenly spaced (x, y) samples of a line's equation from x_min to x_max inclusive,
+    /// with the current parameter values, for hosts doing their own analysis or charting. Empty
+    /// if there is no such line or its equation is empty.
+    pub fn sample(&self, id: LineId, x_min: f64, x_max: f64, n: usize) -> Vec<(f64, f64)> {
+        self.equation_pipeline.sample(id, x_min, x_max, n)
+    }
+
+    /// Returns the coefficients of a line's equation with the current parameter values, from x^0
+    /// up, or None if there is no such line.
+    pub fn line_coeffs(&self, id: LineId) -> Option<&[f32]> {
+        self.equation_pipeline.line_coeffs(id)
+    }
+
+    /// Converts a point on the graph to physical pixels relative to the render target, which may
+    /// be outside the viewport.
+    pub fn world_to_screen(&self, world: cgmath::Vector2<f32>) -> dpi::PhysicalPosition<f32> {
+        let screen = self.camera.world_to_screen_space(world.extend(0.0), self.viewport.size);
+        dpi::PhysicalPosition::new(screen.x + self.viewport.x, screen.y + self.viewport.y)
+    }
+
+    pub fn set_line_visible(&mut self, id: LineId, visible: bool) -> Result<(), EngineError> {
+        self.equation_pipeline.set_line_visible(id, visible)
+    }
+
+    pub fn set_line_color(&mut self, queue: &wgpu::Queue, id: LineId, color: geometry::Color<f32>) -> Result<(), EngineError> {
+        self.equation_pipeline.set_line_color(queue, id, color)
+    }
+
+    /// Sets the value of a parameter used in equations, replotting the lines which use it.
+    pub fn set_parameter(&mut self, name: char, value: f32) {
+        for id in self.equation_pipeline.set_parameter(name, value) {
+            self.hooks.emit(EngineEvent::EquationChanged { id });
+        }
+        if let Some(mapping) = self.grid_mapping.clone().filter(|mapping| mapping.function.contains(name)) {
+            // cleared so the unchanged mapping is drawn again, and one which failed to parse is
+            // already reported
+            self.grid_mapping = None;
+            let _ = self.set_grid_mapping(Some(mapping));
+        }
+    }
+
+    /// Draws a vector field as streamlines across the view, traced again as the view moves, or
+    /// stops drawing one.
+    pub fn set_vector_field(&mut self, field: Option<VectorField>) {
+        if field.is_none() {
+            self.point_pipeline.set_streamlines(Vec::new());
+        }
+        self.vector_field = field.map(|field| TracedField { field, traced: None, moved: None });
+    }
+
+    /// Shades a region given in polar form and draws its boundary, or stops shading one. The
+    /// boundary is sampled once here rather than whenever the view moves.
+    pub fn set_polar_region(&mut self, region: Option<PolarRegion>) {
+        let region = region.map(polar::SampledRegion::new);
+        self.point_pipeline.set_region_boundary(region.as_ref().map(polar::SampledRegion::boundary).unwrap_or_default());
+        if region.is_none() {
+            self.equation_pipeline.shading = None;
+        }
+        self.polar_region = region.map(|region| (region, None));
+    }
+
+    pub fn grid_mapping(&self) -> Option<&GridMapping> {
+        self.grid_mapping.as_ref()
+    }
+
+    /// Draws the image of the grid over a region of the complex plane under a function, or stops
+    /// drawing it. Nothing is drawn if the function fails to parse.
+    pub fn set_grid_mapping(&mut self, mapping: Option<GridMapping>) -> Result<(), EngineError> {
+        if mapping == self.grid_mapping {
+            return Ok(());
+        }
+        let parsed = mapping.as_ref().map(|mapping| self.equation_pipeline.parse(&mapping.function));
+        let lines = match (&mapping, &parsed) {
+            (Some(mapping), Some(Ok(coeffs))) => mapping::mapped_grid(mapping, coeffs),
+            _ => Vec::new(),
+        };
+        self.point_pipeline.set_mapping_lines(lines);
+        self.grid_mapping = mapping;
+        match parsed {
+            Some(Err(error)) => Err(EngineError::Parse(error)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn set_line_style(&mut self, queue: &wgpu::Queue, id: LineId, style: LineStyle) -> Result<(), EngineError> {
+        self.equation_pipeline.set_line_style(queue, id, style)
+    }
+
+    /// Zooms in by steps, or out if steps is negative, about the centre of the view.
+    pub fn zoom_steps(&mut self, steps: f32) {
+        self.camera_controller.zoom_steps(steps);
+    }
+
+    /// Eases the view back to the origin at the starting zoom.
+    pub fn reset_view(&mut self) {
+        self.camera_controller.reset(&self.camera);
+    }
+
+    /// Returns the region of the graph currently in view.
+    pub fn visible_bounds(&self) -> Bounds {
+        self.camera.visible_bounds()
+    }
+
+    /// Moves the camera so that all of bounds is in view.
+    pub fn fit_bounds(&mut self, bounds: Bounds) {
+        self.camera.fit_bounds(bounds);
+    }
+
+    /// Returns every point on the graph, in order.
+    pub fn points(&self) -> &[Point] {
+        self.point_pipeline.points()
+    }
+
+    /// Replaces every point on the graph, dropping any point being dragged or selected. Nothing
+    /// changes if there are more points than can be drawn.
+    pub fn set_points(&mut self, points: &[Point]) -> Result<(), EngineError> {
+        self.point_pipeline.set_points(points)?;
+        self.dragged_point = None;
+        self.selected_points.clear();
+        self.set_selected(None);
+        Ok(())
+    }
+
+    /// Removes the point at index, shifting the indices of later points down by one.
+    pub fn remove_point(&mut self, index: usize) -> Result<(), EngineError> {
+        self.point_pipeline.remove_point(index)?;
+        self.dragged_point = None;
+        self.selected_points = shift_after_removal(&self.selected_points, index);
+        match self.selected {
+            Some(PickResult::Point(selected)) if selected == index => self.set_selected(None),
+            Some(PickResult::Point(selected)) if selected > index => self.set_selected(Some(PickResult::Point(selected - 1))),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Removes every selected point, returning how many were removed.
+    pub fn remove_selected_points(&mut self) -> usize {
+        // from the last down, so removing one doesn't shift the indices of those still to go
+        let selected = std::mem::take(&mut self.selected_points);
+        selected.into_iter().rev().filter(|&index| self.remove_point(index).is_ok()).count()
+    }
+
+    /// Returns the coordinates of point as "(x, y)", to as many digits as a pixel on the screen
+    /// can tell apart.
+    pub fn point_coordinates(&self, point: &Point) -> String {
+        coordinates(point, self.camera.world_units_per_pixel(self.viewport.size))
+    }
+
+    /// Sets or removes the annotation of the point at index.
+    pub fn set_point_label(&mut self, index: usize, label: Option<Annotation>) -> Result<(), EngineError> {
+        self.point_pipeline.set_label(index, label)
+    }
+
+    /// Sets the data series drawn alongside the points. Their points can't be picked or dragged.
+    /// If there are more points than can be drawn, none of the series are, and the same series
+    /// aren't tried again.
+    pub fn set_series(&mut self, series: &[Series]) -> Result<(), EngineError> {
+        if series == self.series {
+            return Ok(());
+        }
+        self.series = series.to_vec();
+        let shapes = series::shapes(series);
+        let drawn = self.point_pipeline.set_series_shapes(shapes.points, shapes.runs);
+        #[cfg(feature = "text")]
+        {
+            self.category_labels = if drawn.is_ok() { shapes.labels } else { Vec::new() };
+        }
+        drawn
+    }
+
+    /// Removes every point on the graph.
+    pub fn clear_points(&mut self) {
+        // no points always fit
+        let _ = self.set_points(&[]);
+    }
+
+    /// Adds a point drawn in its own colour and radius, with its label beside it if it has one,
+    /// unless there are already as many points as can be drawn.
+    pub fn add_point(&mut self, queue: &wgpu::Queue, point: Point) -> Result<(), EngineError> {
+        let position = point.position;
+        self.point_pipeline.add_point(queue, point)?;
+        let index = self.point_pipeline.points().len() - 1;
+        self.hooks.emit(EngineEvent::PointAdded { index, x: position.x, y: position.y });
+        Ok(())
+    }
+}
+
+/// Formats the position of point as "(x, y)" to the precision of world_per_pixel.
+fn coordinates(point: &Point, world_per_pixel: f32) -> String {
+    format!(
+        "({}, {})",
+        format_number(point.position.x, world_per_pixel),
+        format_number(point.position.y, world_per_pixel),
+    )
+}
+
+/// Returns indices into a list after the item at removed is taken out of it, dropping removed
+/// and shifting those after it down by one.
+fn shift_after_removal(indices: &BTreeSet<usize>, removed: usize) -> BTreeSet<usize> {
+    indices.iter()
+        .filter(|&&index| index != removed)
+        .map(|&index| if index > removed { index - 1 } else { index })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_shifts_past_removed_points() {
+        let selected = BTreeSet::from([1, 3, 4]);
+
+        assert_eq!(shift_after_removal(&selected, 3), BTreeSet::from([1, 3]));
+        assert_eq!(shift_after_removal(&selected, 0), BTreeSet::from([0, 2, 3]));
+        assert_eq!(shift_after_removal(&selected, 5), selected);
+    }
+
+    #[test]
+    fn scissor_rects_stay_within_the_target() {
+        let target = dpi::PhysicalSize::new(800, 600);
+        let viewport = |x, y, width, height| Viewport { x, y, size: dpi::PhysicalSize::new(width, height) };
+
+        assert_eq!(scissor_rect(viewport(100.0, 50.0, 200, 100), target), Some((100, 50, 200, 100)));
+        // a panel partly off the right and top of the window
+        assert_eq!(scissor_rect(viewport(700.0, -20.0, 200, 100), target), Some((700, 0, 100, 80)));
+        assert_eq!(scissor_rect(viewport(900.0, 0.0, 200, 100), target), None);
+        assert_eq!(scissor_rect(viewport(-300.0, 0.0, 200, 100), target), None);
+    }
+}
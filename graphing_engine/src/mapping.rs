@@ -0,0 +1,110 @@
+//! The image of the Cartesian grid under a complex function, for showing how w = f(z) stretches
+//! and turns the plane.
+
+use crate::camera::Bounds;
+use crate::geometry::{Color, Run};
+
+/// Points along each mapped grid line. Lines bend under the mapping, so they are sampled far
+/// more finely than the grid's spacing.
+const SAMPLES_PER_LINE: usize = 256;
+
+/// The grid over a region of the z plane drawn where w = f(z) takes it, where f is a polynomial
+/// in the calculator's syntax with x standing for z and real coefficients.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridMapping {
+    pub function: String,
+    /// How far the grid has moved from where it is, at 0, to its image under f, at 1. Stepping
+    /// this animates the mapping.
+    pub progress: f32,
+    /// The region of the z plane whose grid is mapped.
+    pub bounds: Bounds,
+    /// Number of spaces the region is divided into along each axis.
+    pub divisions: u32,
+    /// Colour of the images of lines of constant real part.
+    pub real_color: Color<f32>,
+    /// Colour of the images of lines of constant imaginary part.
+    pub imaginary_color: Color<f32>,
+}
+
+impl Default for GridMapping {
+    fn default() -> Self {
+        Self {
+            function: "x^2".to_owned(),
+            progress: 1.0,
+            bounds: Bounds { x_min: -2.0, x_max: 2.0, y_min: -2.0, y_max: 2.0 },
+            divisions: 16,
+            real_color: Color { r: 0.85, g: 0.35, b: 0.2, a: 1.0 },
+            imaginary_color: Color { r: 0.2, g: 0.45, b: 0.85, a: 1.0 },
+        }
+    }
+}
+
+/// Returns the value at z of the polynomial with real coefficients from z^0 up, with complex
+/// numbers as (re, im).
+fn complex_polynomial(z: cgmath::Vector2<f32>, coeffs: &[f32]) -> cgmath::Vector2<f32> {
+    coeffs.iter().rev().fold(cgmath::vec2(0.0, 0.0), |w, &coeff| {
+        cgmath::vec2(w.x * z.x - w.y * z.y + coeff, w.x * z.y + w.y * z.x)
+    })
+}
+
+/// Returns where z is drawn part way from itself to f(z).
+fn map_point(z: cgmath::Vector2<f32>, coeffs: &[f32], progress: f32) -> cgmath::Vector2<f32> {
+    z + (complex_polynomial(z, coeffs) - z) * progress
+}
+
+/// Returns the mapped grid lines of mapping, with f given by coeffs from z^0 up.
+pub fn mapped_grid(mapping: &GridMapping, coeffs: &[f32]) -> Vec<Run> {
+    let Bounds { x_min, x_max, y_min, y_max } = mapping.bounds;
+    let divisions = mapping.divisions.max(1);
+    let line = |start: cgmath::Vector2<f32>, end: cgmath::Vector2<f32>, color| Run {
+        points: (0..=SAMPLES_PER_LINE)
+            .map(|i| start + (end - start) * (i as f32 / SAMPLES_PER_LINE as f32))
+            .map(|z| map_point(z, coeffs, mapping.progress))
+            .collect(),
+        color,
+        width: None,
+    };
+
+    let fraction = |i: u32| i as f32 / divisions as f32;
+    let constant_real = (0..=divisions).map(|i| {
+        let x = x_min + (x_max - x_min) * fraction(i);
+        line(cgmath::vec2(x, y_min), cgmath::vec2(x, y_max), mapping.real_color)
+    });
+    let constant_imaginary = (0..=divisions).map(|i| {
+        let y = y_min + (y_max - y_min) * fraction(i);
+        line(cgmath::vec2(x_min, y), cgmath::vec2(x_max, y), mapping.imaginary_color)
+    });
+    constant_real.chain(constant_imaginary).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn squaring_doubles_angles() {
+        // (1 + i)^2 = 2i
+        let w = complex_polynomial(cgmath::vec2(1.0, 1.0), &[0.0, 0.0, 1.0]);
+
+        assert_relative_eq!(w.x, 0.0);
+        assert_relative_eq!(w.y, 2.0);
+    }
+
+    #[test]
+    fn progress_moves_the_grid_from_itself_to_its_image() {
+        let mapping = GridMapping { divisions: 2, ..Default::default() };
+        let square = [0.0, 0.0, 1.0];
+
+        let identity = mapped_grid(&GridMapping { progress: 0.0, ..mapping.clone() }, &square);
+        let mapped = mapped_grid(&mapping, &square);
+
+        assert_eq!(identity.len(), 6);
+        // the first line is x = -2, starting at -2 - 2i, whose square is 8i
+        assert_relative_eq!(identity[0].points[0].x, -2.0);
+        assert_relative_eq!(identity[0].points[0].y, -2.0);
+        assert_relative_eq!(mapped[0].points[0].x, 0.0);
+        assert_relative_eq!(mapped[0].points[0].y, 8.0);
+    }
+}
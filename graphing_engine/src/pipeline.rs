@@ -0,0 +1,2101 @@
+use wgpu::{self, util::DeviceExt, include_wgsl};
+use cgmath::prelude::*;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::buffer::DynamicBuffer;
+use crate::EngineError;
+use crate::cache::PipelineCache;
+use crate::camera;
+use crate::geometry::*;
+use crate::tessellation::{polynomial_equation, sample_evenly, Tessellation};
+#[cfg(feature = "text")]
+use crate::format::format_decimal;
+use crate::legend;
+use crate::theme::{GridStyle, Theme};
+use crate::RenderTargetConfig;
+
+/// Extra half width in physical pixels given to strokes so the shader has room to fade the edge.
+const LINE_FRINGE: f32 = 1.0;
+/// Width in logical pixels of the lines joining connected points.
+const CONNECTION_WIDTH: f32 = 2.0;
+/// How long a frame waits for lines to be tessellated, all together, before carrying on with the
+/// previous geometry and picking up the results on a later frame.
+const TESSELLATION_BUDGET: Duration = Duration::from_millis(4);
+/// Extra distance in logical pixels around a point which still counts as hitting it.
+pub const POINT_HIT_SLOP: f32 = 4.0;
+
+/// Returns the index of the position closest to target, if any are within their tolerance. Each
+/// position is paired with how close target has to be to it.
+pub fn nearest_within(
+    positions: impl Iterator<Item = (cgmath::Vector2<f32>, f32)>,
+    target: cgmath::Vector2<f32>,
+) -> Option<usize> {
+    positions
+        .map(|(position, tolerance)| ((position - target).magnitude(), tolerance))
+        .enumerate()
+        .filter(|(_, (distance, tolerance))| distance <= tolerance)
+        .map(|(index, (distance, _))| (index, distance))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+/// Minor lines are invisible when they are this far apart, relative to the expected spacing of
+/// labelled lines, and fully shown by the time they are MINOR_FADE_END apart.
+const MINOR_FADE_START: f32 = 1.0 / 5.0;
+const MINOR_FADE_END: f32 = 8.0 / 25.0;
+
+/// Length of the strokes of axis arrowheads in logical pixels.
+const ARROW_SIZE: f32 = 10.0;
+/// Gap in logical pixels between the tips of axis arrows and the edge of the view.
+const ARROW_MARGIN: f32 = 2.0;
+
+/// A number drawn beside a labelled gridline.
+#[cfg(feature = "text")]
+pub struct GridLabel {
+    /// Where the line crosses the plane, as an offset from the point under the eye.
+    pub offset: cgmath::Vector3<f32>,
+    pub text: String,
+}
+
+/// Where the axis end, origin and quadrant labels go, as offsets from the point under the eye.
+/// Each is None while it is turned off or out of view.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AxisMarkers {
+    pub x_end: Option<cgmath::Vector3<f32>>,
+    pub y_end: Option<cgmath::Vector3<f32>>,
+    pub origin: Option<cgmath::Vector3<f32>>,
+    /// The middle of the part of each quadrant in view, from I to IV.
+    pub quadrants: [Option<cgmath::Vector3<f32>>; 4],
+}
+
+/// Spacing between labelled gridlines of mantissa * 10^exponent, kept exact so labels can be
+/// worked out without floating point error creeping in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct TickStep {
+    mantissa: i64,
+    exponent: i32,
+}
+
+impl TickStep {
+    fn value(self) -> f64 {
+        self.mantissa as f64 * 10_f64.powi(self.exponent)
+    }
+}
+
+/// Returns the spacing between labelled gridlines at an eye height and density, which steps
+/// through 1, 2 and 5 times a power of ten, and how many parts minor lines divide each space into.
+fn grid_steps(zoom: f32, density: f32) -> (TickStep, i32) {
+    let min_major = zoom / density;
+    let exponent = min_major.log10().floor() as i32;
+
+    [(1, 5), (2, 4), (5, 5)]
+        .into_iter()
+        .map(|(mantissa, subdivisions)| (TickStep { mantissa, exponent }, subdivisions))
+        .find(|(step, _)| step.value() as f32 >= min_major)
+        .unwrap_or((TickStep { mantissa: 1, exponent: exponent + 1 }, 5))
+}
+
+/// How far apart gridlines are in world units.
+struct Spacing {
+    /// Between labelled lines, in multiples of the unit.
+    #[cfg(feature = "text")]
+    step: TickStep,
+    /// Between labelled lines.
+    major: f64,
+    /// How many parts minor lines divide the space between labelled lines into.
+    subdivisions: i64,
+    /// How many parts they would divide it into if the style didn't set it.
+    natural_subdivisions: i32,
+}
+
+impl Spacing {
+    /// Returns the spacing at an eye height, in multiples of π if pi is set.
+    fn new(zoom: f32, style: &GridStyle, pi: bool) -> Self {
+        let unit = if pi { std::f64::consts::PI } else { 1.0 };
+        let (step, natural_subdivisions) = grid_steps((zoom as f64 / unit) as f32, style.major_density.max(1.0));
+        Self {
+            #[cfg(feature = "text")]
+            step,
+            major: step.value() * unit,
+            subdivisions: style.minor_subdivisions.map_or(natural_subdivisions as i64, |n| n.max(1) as i64),
+            natural_subdivisions,
+        }
+    }
+
+    fn minor(&self) -> f64 {
+        self.major / self.subdivisions as f64
+    }
+}
+
+/// Returns how visible minor lines spaced minor world units apart are at an eye height, so they
+/// fade in as zooming spreads them out rather than popping in when the spacing steps down.
+fn minor_fade(minor: f32, zoom: f32, density: f32) -> f32 {
+    let t = ((minor * density / zoom - MINOR_FADE_START) / (MINOR_FADE_END - MINOR_FADE_START)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Formats the value of the index-th labelled gridline when they are spaced major_units
+/// multiples of π apart, as a fraction of π such as "3π/2".
+#[cfg(feature = "text")]
+fn format_pi_tick(index: i64, major_units: f64) -> String {
+    let (mut numerator, mut denominator) = if major_units < 1.0 {
+        (index, (1.0 / major_units).round() as i64)
+    } else {
+        (index * major_units.round() as i64, 1)
+    };
+
+    let (mut a, mut b) = (numerator.abs(), denominator);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a > 1 {
+        numerator /= a;
+        denominator /= a;
+    }
+
+    let sign = if numerator < 0 { "-" } else { "" };
+    let coefficient = match numerator.abs() {
+        0 => return "0".to_owned(),
+        1 => String::new(),
+        n => n.to_string(),
+    };
+    match denominator {
+        1 => format!("{sign}{coefficient}π"),
+        d => format!("{sign}{coefficient}π/{d}"),
+    }
+}
+
+/// Returns the width in world units of a line width logical pixels wide, which is never thinner
+/// than a physical pixel so lines can't drop out between pixel centres.
+fn stroke_width(width: f32, scale_factor: f32, world_per_pixel: f32) -> f32 {
+    (width * scale_factor).max(1.0) * world_per_pixel
+}
+
+/// Returns the tips of arrows on the positive ends of the x and y axes, as offsets from the point
+/// under the eye, for a view reaching half_extent world units from its centre to each edge. An axis
+/// out of view has no arrow.
+fn axis_ends(
+    eye: cgmath::Point3<f32>,
+    half_extent: cgmath::Vector2<f32>,
+    margin: f32,
+) -> (Option<cgmath::Vector3<f32>>, Option<cgmath::Vector3<f32>>) {
+    let x_end = (eye.y.abs() < half_extent.y).then(|| cgmath::vec3(half_extent.x - margin, -eye.y, 0.0));
+    let y_end = (eye.x.abs() < half_extent.x).then(|| cgmath::vec3(-eye.x, half_extent.y - margin, 0.0));
+    (x_end, y_end)
+}
+
+/// Returns the middle of the part of each quadrant in view, from I to IV, with the view reaching
+/// half_extent either side of the eye. Quadrants out of view are None.
+fn quadrant_centres(eye: cgmath::Point3<f32>, half_extent: cgmath::Vector2<f32>) -> [Option<cgmath::Vector3<f32>>; 4] {
+    // the middle of the part of the view on the positive or negative side of an axis at -eye
+    let middle = |positive: bool, eye: f32, half_extent: f32| {
+        let (start, end) = if positive {
+            ((-eye).max(-half_extent), half_extent)
+        } else {
+            (-half_extent, (-eye).min(half_extent))
+        };
+        (start < end).then(|| (start + end) / 2.0)
+    };
+    // numbered anticlockwise from the top right
+    [(true, true), (false, true), (false, false), (true, false)].map(|(right, above)| {
+        Some(cgmath::vec3(middle(right, eye.x, half_extent.x)?, middle(above, eye.y, half_extent.y)?, 0.0))
+    })
+}
+
+/// Returns the two strokes of an arrowhead with its tip at tip, pointing direction radians
+/// anticlockwise from the x axis. Strokes are drawn with the vertical gridline, which reaches
+/// line_limit either side of its centre.
+fn arrowhead(
+    tip: cgmath::Vector3<f32>,
+    direction: f32,
+    length: f32,
+    width: f32,
+    line_limit: f32,
+    color: Color<f32>,
+) -> [Instance; 2] {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_6, PI};
+
+    [-1.0, 1.0].map(|side| {
+        // strokes run back from the tip at 30 degrees either side of the shaft
+        let angle = direction + PI + side * FRAC_PI_6;
+        let back = cgmath::vec3(angle.cos(), angle.sin(), 0.0);
+        Instance {
+            position: tip + back * (length / 2.0),
+            // the vertical line runs along y, a quarter turn anticlockwise of the stroke's angle
+            rotation: cgmath::Quaternion::from_angle_z(cgmath::Rad(angle - FRAC_PI_2)),
+            scale: cgmath::vec3(width, length / (2.0 * line_limit), 1.0),
+            color,
+        }
+    })
+}
+
+/// Returns where the point under the eye is along the direction lines are spaced in, and the
+/// indices of the minor lines either side of it, enough to cover the widest allowed aspect ratio.
+fn line_indices(camera: &camera::Camera, vertical: bool, style: &GridStyle, spacing: &Spacing) -> (f64, std::ops::Range<i64>) {
+    let half_count = (1.5 * style.major_density.max(1.0) * spacing.subdivisions as f32).ceil() as i64;
+    let centre = if vertical { camera.eye.x } else { camera.eye.y } as f64;
+    let offset = (centre / spacing.minor()) as i64;
+    (centre, (offset - half_count)..(offset + half_count))
+}
+
+/// Returns the lines of the grid in one direction, drawn in style with world_per_pixel world
+/// units to a physical pixel and scale_factor physical pixels to a logical one.
+///
+/// Lines are placed relative to the point under the eye, and worked out in f64 before that, so
+/// the grid keeps its precision however far it is from the origin. Minor lines fade in at the
+/// same zoom levels whatever the number of subdivisions. With pi set, labelled lines fall on
+/// multiples of fractions of π.
+fn get_instances(
+    camera: &camera::Camera,
+    vertical: bool,
+    style: &GridStyle,
+    world_per_pixel: f32,
+    scale_factor: f32,
+    pi: bool,
+) -> Vec<Instance> {
+    let density = style.major_density.max(1.0);
+    let spacing = Spacing::new(camera.eye.z, style, pi);
+    let Spacing { major, subdivisions, natural_subdivisions, .. } = spacing;
+    let minor = spacing.minor();
+    let minor_color = Color {
+        a: style.minor_color.a * minor_fade((major / natural_subdivisions as f64) as f32, camera.eye.z, density),
+        ..style.minor_color
+    };
+    // lines are unit squares stretched along their length by the vertex buffer, and across it here
+    let thickness = |width: f32| {
+        let width = stroke_width(width, scale_factor, world_per_pixel);
+        if vertical {
+            cgmath::vec3(width, 1.0, 1.0)
+        } else {
+            cgmath::vec3(1.0, width, 1.0)
+        }
+    };
+
+    let mut instances: Vec<Instance> = Vec::new();
+    let (centre, indices) = line_indices(camera, vertical, style, &spacing);
+
+    for i in indices {
+        let is_major = i % subdivisions == 0;
+        // major lines are placed from whole multiples of the major step to avoid adding up error
+        let value = if is_major {
+            (i / subdivisions) as f64 * major
+        } else {
+            i as f64 * minor
+        };
+        let along = (value - centre) as f32;
+        let position = if vertical {
+            cgmath::vec3(along, 0.0, 0.0)
+        } else {
+            cgmath::vec3(0.0, along, 0.0)
+        };
+        let rotation = cgmath::Quaternion::from_axis_angle(cgmath::Vector3::unit_z(), cgmath::Deg(0.0));
+
+        let (color, width) = match i {
+            0 => (style.axis_color, style.axis_width),
+            _ if is_major => (style.major_color, style.major_width),
+            _ => (minor_color, style.minor_width),
+        };
+
+        if color.a == 0.0 {
+            continue;
+        }
+
+        instances.push(Instance {
+            position,
+            rotation,
+            scale: thickness(width),
+            color,
+        });
+    }
+    instances
+}
+
+/// Returns the labels of the major lines get_instances draws in the same direction, placed
+/// relative to the point under the eye as they are. The axis itself isn't labelled.
+#[cfg(feature = "text")]
+fn get_labels(camera: &camera::Camera, vertical: bool, style: &GridStyle, pi: bool) -> Vec<GridLabel> {
+    if style.major_color.a == 0.0 {
+        return Vec::new();
+    }
+    let spacing = Spacing::new(camera.eye.z, style, pi);
+    let Spacing { step, major, subdivisions, .. } = spacing;
+    let (centre, indices) = line_indices(camera, vertical, style, &spacing);
+
+    indices
+        .filter(|i| *i != 0 && i % subdivisions == 0)
+        .map(|i| {
+            // labels come from the tick index rather than value, which can be a hair off
+            let index = i / subdivisions;
+            let along = (index as f64 * major - centre) as f32;
+            let text = if pi {
+                format_pi_tick(index, step.value())
+            } else {
+                format_decimal(index * step.mantissa, step.exponent)
+            };
+            let offset = if vertical {
+                cgmath::vec3(along, 0.0, 0.0)
+            } else {
+                cgmath::vec3(0.0, along, 0.0)
+            };
+            GridLabel { offset, text }
+        })
+        .collect()
+}
+
+pub struct GridPipeline {
+    pub render_pipeline: Rc<wgpu::RenderPipeline>,
+    pub horizontal_buffer: wgpu::Buffer,
+    pub vertical_buffer: wgpu::Buffer,
+    pub vertical_instance_buffer: DynamicBuffer,
+    pub horizontal_instance_buffer: DynamicBuffer,
+    pub vertical_instances: Vec<Instance>,
+    pub horizontal_instances: Vec<Instance>,
+    #[cfg(feature = "text")]
+    pub vertical_labels: Vec<GridLabel>,
+    #[cfg(feature = "text")]
+    pub horizontal_labels: Vec<GridLabel>,
+    pub markers: AxisMarkers,
+    /// Space and label the x axis in fractions of π, for trigonometric functions.
+    pub pi_ticks: bool,
+    /// Spacing of the minor gridlines along x and y as last drawn, where points snap to.
+    pub snap_step: cgmath::Vector2<f32>,
+}
+
+impl GridPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        cache: &mut PipelineCache,
+        pipeline_layout: &wgpu::PipelineLayout,
+        target: &RenderTargetConfig,
+    ) -> Self {
+        let render_pipeline = cache.render_pipeline(
+            device, 
+            pipeline_layout, 
+            target,
+            &[Vertex::desc(), InstanceRaw::desc()],
+            include_wgsl!("shader.wgsl"),
+            wgpu::PrimitiveTopology::TriangleStrip,
+        );
+        
+        let horizontal_buffer = device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Horizontal Grid Buffer"),
+                size: 48,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }
+        );
+
+        let vertical_buffer = device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Vertical Grid Buffer"),
+                size: 48,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }
+        );
+
+        let vertical_instance_buffer = DynamicBuffer::new(
+            device,
+            "Vertical Grid Instance Buffer",
+            wgpu::BufferUsages::VERTEX,
+            12800,
+        );
+
+        let horizontal_instance_buffer = DynamicBuffer::new(
+            device,
+            "Horizontal Grid Instance Buffer",
+            wgpu::BufferUsages::VERTEX,
+            12800,
+        );
+
+        let horizontal_instances = vec![];
+        let vertical_instances = vec![];
+
+        Self {
+            render_pipeline,
+            horizontal_buffer,
+            vertical_buffer,
+            vertical_instance_buffer,
+            horizontal_instance_buffer,
+            horizontal_instances,
+            vertical_instances,
+            #[cfg(feature = "text")]
+            vertical_labels: Vec::new(),
+            #[cfg(feature = "text")]
+            horizontal_labels: Vec::new(),
+            markers: AxisMarkers::default(),
+            pi_ticks: false,
+            snap_step: cgmath::vec2(1.0, 1.0),
+        }
+    }
+    
+    pub fn update_grid(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        camera: &camera::Camera,
+        style: &GridStyle,
+        size: dpi::PhysicalSize<u32>,
+        scale_factor: f32,
+    ) -> Result<(), EngineError> {
+        let world_per_pixel = camera.world_units_per_pixel(size);
+        self.snap_step = cgmath::vec2(
+            Spacing::new(camera.eye.z, style, self.pi_ticks).minor() as f32,
+            Spacing::new(camera.eye.z, style, false).minor() as f32,
+        );
+        self.vertical_instances = get_instances(camera, true, style, world_per_pixel, scale_factor, self.pi_ticks);
+        self.horizontal_instances = get_instances(camera, false, style, world_per_pixel, scale_factor, false);
+        #[cfg(feature = "text")]
+        {
+            self.vertical_labels = get_labels(camera, true, style, self.pi_ticks);
+            self.horizontal_labels = get_labels(camera, false, style, false);
+        }
+
+        let line_limit = camera.eye.z * 2.0;
+        let world_per_point = world_per_pixel * scale_factor;
+        let half_extent = cgmath::vec2(size.width as f32, size.height as f32) * world_per_pixel / 2.0;
+        let (x_end, y_end) = axis_ends(camera.eye, half_extent, ARROW_MARGIN * world_per_point);
+        if style.axis_arrows {
+            let width = stroke_width(style.axis_width, scale_factor, world_per_pixel);
+            for (tip, direction) in [(x_end, 0.0), (y_end, std::f32::consts::FRAC_PI_2)] {
+                if let Some(tip) = tip {
+                    let strokes = arrowhead(tip, direction, ARROW_SIZE * world_per_point, width, line_limit, style.axis_color);
+                    self.vertical_instances.extend(strokes);
+                }
+            }
+        }
+        let origin_visible = camera.eye.x.abs() < half_extent.x && camera.eye.y.abs() < half_extent.y;
+        self.markers = AxisMarkers {
+            x_end: x_end.filter(|_| style.axis_arrows),
+            y_end: y_end.filter(|_| style.axis_arrows),
+            origin: (style.origin_label && origin_visible).then(|| cgmath::vec3(-camera.eye.x, -camera.eye.y, 0.0)),
+            quadrants: if style.quadrant_labels { quadrant_centres(camera.eye, half_extent) } else { Default::default() },
+        };
+
+        self.set_buffers(device, queue, line_limit)
+    }
+
+    /// Writes the grid lines to the GPU, clearing them if there are too many to.
+    fn set_buffers(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, line_limit: f32) -> Result<(), EngineError> {
+
+        // unit wide strips, which each instance scales to its line width
+        let line_horizontal: &[Vertex] = &[
+            Vertex { position: [-line_limit, -0.5, 0.0] },
+            Vertex { position: [line_limit, -0.5, 0.0] },
+            Vertex { position: [-line_limit, 0.5, 0.0] },
+            Vertex { position: [line_limit, 0.5, 0.0] },
+        ];
+
+        let line_vertical: &[Vertex] = &[
+            Vertex { position: [-0.5, -line_limit, 0.0] },
+            Vertex { position: [0.5, -line_limit, 0.0] },
+            Vertex { position: [-0.5, line_limit, 0.0] },
+            Vertex { position: [0.5, line_limit, 0.0] },
+        ];
+
+        let vertical_instance_data = self.vertical_instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
+        let horizontal_instance_data = self.horizontal_instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
+
+        queue.write_buffer(&self.horizontal_buffer, 0, bytemuck::cast_slice(line_horizontal));
+        queue.write_buffer(&self.vertical_buffer, 0, bytemuck::cast_slice(line_vertical));
+        let written = self.horizontal_instance_buffer.write(device, queue, bytemuck::cast_slice(&horizontal_instance_data))
+            .and_then(|_| self.vertical_instance_buffer.write(device, queue, bytemuck::cast_slice(&vertical_instance_data)));
+        if written.is_err() {
+            self.horizontal_instances.clear();
+            self.vertical_instances.clear();
+        }
+        written.map(|_| ())
+    }
+}
+
+/// Why an equation could not be parsed, and which part of it is at fault.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte range of the offending text in the equation as written.
+    pub span: std::ops::Range<usize>,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Splits the coefficient of a term into its number and the parameters multiplying it.
+fn split_coefficient(coefficient: &str) -> (&str, &str) {
+    // plain numbers are tried first so exponents such as 1e5 aren't read as parameters
+    let split = match coefficient.parse::<f32>() {
+        Ok(_) => coefficient.len(),
+        Err(_) => coefficient.find(|c: char| c.is_alphabetic()).unwrap_or(coefficient.len()),
+    };
+    coefficient.split_at(split)
+}
+
+/// Matches one term of an equation without whitespace, with its sign. Numbers with an exponent,
+/// such as 1e-3, are matched whole first so their sign doesn't start a new term.
+const TERM: &str = r"[+-]?(?:[0-9.]+[eE][+-]?[0-9]+|[^+-])+";
+
+/// Returns the free parameters of an equation, which are the letters in its coefficients.
+pub fn equation_parameters(equation: &str) -> BTreeSet<char> {
+    let re = Regex::new(TERM).unwrap();
+    let split_eqn = equation.split_whitespace().collect::<String>();
+
+    re.find_iter(&split_eqn)
+        .flat_map(|m| split_coefficient(m.as_str().split('x').next().unwrap()).1.chars())
+        .filter(char::is_ascii_alphabetic)
+        .collect()
+}
+
+/// Forms equations can be written in, each with an example, for the help. Keep in step with
+/// parse_equation, whose tests check every example parses.
+pub const SYNTAX: [(&str, &str); 5] = [
+    ("3x^2 - 2x + 1", "Polynomials in x, with ^ for powers"),
+    ("x^3 + x", "Coefficients of 1 can be left out"),
+    ("2.5x - 1e-3", "Decimal and scientific coefficients"),
+    ("ax^2 + bx + c", "Single letter parameters, set with sliders"),
+    ("2ab x", "Parameters next to each other multiply"),
+];
+
+/// Returns coefficients for Tessellation::polynomial if successful. 
+///
+/// Takes a string which represents a polynomial equation, using ^ to represent exponent.
+/// Coefficients can be followed by single letter parameters, such as 2ab for 2 * a * b, which
+/// take their values from parameters or 1.0 if they are missing.
+fn parse_equation(equation: &str, parameters: &HashMap<char, f32>) -> Result<Vec<f32>, ParseError> {
+    // TODO: possibly expensive so reuse this as explained in regex docs
+    let re = Regex::new(TERM).unwrap();
+
+    // whitespace is dropped before parsing, so keep where each remaining byte came from to report
+    // errors against the equation as written
+    let mut split_eqn = String::new();
+    let mut origins: Vec<usize> = Vec::new();
+    for (i, c) in equation.char_indices().filter(|(_, c)| !c.is_whitespace()) {
+        split_eqn.push(c);
+        origins.extend(i..i + c.len_utf8());
+    }
+    let error = |message: String, start: usize, end: usize| ParseError {
+        message,
+        span: origins[start]..origins[end.max(start + 1) - 1] + 1,
+    };
+    
+    let mut coeffs: Vec<f32> = Vec::new();
+
+    for m in re.find_iter(split_eqn.as_str()) {
+        let exp = m.as_str();
+        let parts = exp.split('x').collect::<Vec<_>>();
+
+        let key = if parts.len() > 1 {
+            let last = parts.last().unwrap();
+            let last_start = m.end() - last.len();
+
+            if last.is_empty() {
+                1
+            } else if let Some(power) = last.strip_prefix('^') {
+                power.parse::<u32>()
+                    .map_err(|_| error(format!("invalid exponent \"{power}\""), last_start, m.end()))?
+            } else {
+                return Err(error("expected ^ after x".to_owned(), last_start, m.end()));
+            }
+        } else {
+            0
+        };
+        
+        let first = parts.first().unwrap();
+        let invalid = || error(format!("invalid coefficient \"{first}\""), m.start(), m.start() + first.len());
+        let (number, names) = split_coefficient(first);
+        let mut val = if number.is_empty() || number.chars().all(|c| c == '+') {
+            1.0
+        } else if number.chars().all(|c| c == '-') {
+            -1.0
+        } else {
+            number.parse::<f32>().map_err(|_| invalid())?
+        };
+        for name in names.chars() {
+            if !name.is_ascii_alphabetic() {
+                return Err(invalid());
+            }
+            val *= parameters.get(&name).copied().unwrap_or(1.0);
+        }
+
+        match coeffs.get_mut(key as usize) {
+            Some(o) => *o += val,
+            None => {
+                coeffs.resize(key as usize, 0.0);
+                coeffs.push(val);
+            }
+        }
+    }
+
+    Ok(coeffs)
+}
+
+/// Identifies a line added with EquationPipeline::add_line. Ids are handed out in order and never
+/// reused, so an id kept after its line is removed can't refer to a later one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineId(u32);
+
+impl LineId {
+    /// Returns the id as a number, for passing to code which can't hold a LineId, such as scripts.
+    pub fn to_raw(self) -> u32 {
+        self.0
+    }
+}
+
+/// A line's equation and how it is drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineInfo<'a> {
+    /// The equation as written.
+    pub equation: &'a str,
+    /// The coefficients of the equation with the current parameter values, from x^0 up, or empty
+    /// if it failed to parse.
+    pub coeffs: &'a [f32],
+    pub color: Color<f32>,
+    pub style: LineStyle,
+    pub visible: bool,
+}
+
+/// A line through connected points, drawn like an equation.
+pub struct Polyline {
+    points: Vec<cgmath::Vector2<f32>>,
+    /// Width in world units, or None for the line's width in logical pixels.
+    width: Option<f32>,
+    pub line: Line,
+}
+
+pub struct EquationPipeline {
+    pub render_pipeline: Rc<wgpu::RenderPipeline>,
+    /// Kept in order of their ids, which is the order they were added and are drawn in.
+    pub lines: BTreeMap<LineId, Line>,
+    /// The id the next line added is given.
+    next_id: u32,
+    /// Lines joining connected points, tessellated on this thread as they are only as long as
+    /// the data.
+    pub polylines: Vec<Polyline>,
+    /// Set when the polylines change and need tessellating again.
+    polylines_dirty: bool,
+    /// A region shaded beneath the lines, if one is shown.
+    pub shading: Option<Line>,
+    line_bind_group_layout: wgpu::BindGroupLayout,
+    line_buffer_size: u64,
+    /// Scale factor the line uniforms were last written for.
+    scale_factor: f32,
+    /// Values of the parameters used in equations.
+    parameters: HashMap<char, f32>,
+    /// Sends lines to the tessellation worker.
+    jobs: mpsc::Sender<TessellationJob>,
+    /// Tessellations from the worker, or None for a line whose tessellation panicked.
+    results: mpsc::Receiver<(LineId, Option<Tessellation>)>,
+    /// Lines sent to the worker whose tessellation hasn't been taken yet. Each has at most one
+    /// job queued, so the worker never falls behind on views which have since been left.
+    pending: BTreeSet<LineId>,
+    /// The visible bounds and world units per pixel the lines were last tessellated for.
+    last_view: Option<(camera::Bounds, f32)>,
+    /// Failures since the host last took them.
+    errors: Vec<EngineError>,
+}
+
+impl EquationPipeline {
+    pub fn new(device: &wgpu::Device,
+        cache: &mut PipelineCache,
+        pipeline_layout: &wgpu::PipelineLayout,
+        line_bind_group_layout: wgpu::BindGroupLayout, 
+        target: &RenderTargetConfig,
+        line_buffer_size: u64,
+    ) -> Self {
+        let render_pipeline = cache.render_pipeline(
+            device, 
+            pipeline_layout, 
+            target, 
+            &[StrokeVertex::desc()],
+            include_wgsl!("eqn_shader.wgsl"),
+            wgpu::PrimitiveTopology::TriangleList,
+        );
+        
+        let lines = BTreeMap::new();
+        let (jobs, results) = spawn_tessellation_worker();
+
+        Self {
+            render_pipeline,
+            lines,
+            next_id: 0,
+            polylines: Vec::new(),
+            polylines_dirty: false,
+            shading: None,
+            line_bind_group_layout,
+            line_buffer_size,
+            scale_factor: 1.0,
+            parameters: HashMap::new(),
+            jobs,
+            results,
+            pending: BTreeSet::new(),
+            errors: Vec::new(),
+            last_view: None,
+        }
+    }
+
+    /// Adds a line with coeffs, returning the id to refer to it by.
+    pub fn add_line(&mut self, device: &wgpu::Device, coeffs: Vec<f32>, color: Color<f32>) -> LineId {
+        let id = LineId(self.next_id);
+        self.next_id += 1;
+        let line = Line::new(device, coeffs, self.line_buffer_size, color, self.scale_factor, &self.line_bind_group_layout);
+        self.lines.insert(id, line);
+        id
+    }
+
+    /// Returns the equation and style of a line, or None if there is no such line.
+    pub fn line(&self, id: LineId) -> Option<LineInfo<'_>> {
+        self.lines.get(&id).map(|line| LineInfo {
+            equation: &line.equation,
+            coeffs: &line.coeffs,
+            color: line.color,
+            style: line.style,
+            visible: line.visible,
+        })
+    }
+
+    /// Replaces the lines joining connected points with runs of points in their colours, reusing
+    /// the GPU resources of the previous lines.
+    pub fn set_polylines(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, runs: Vec<Run>) {
+        self.polylines.truncate(runs.len());
+        for (i, Run { points, color, width }) in runs.into_iter().enumerate() {
+            match self.polylines.get_mut(i) {
+                Some(polyline) => {
+                    polyline.points = points;
+                    polyline.width = width;
+                    if polyline.line.color != color {
+                        polyline.line.color = color;
+                        polyline.line.write_uniform(queue, self.scale_factor);
+                    }
+                }
+                None => {
+                    let mut line = Line::new(device, Vec::new(), self.line_buffer_size, color, self.scale_factor, &self.line_bind_group_layout);
+                    line.style.width = CONNECTION_WIDTH;
+                    line.write_uniform(queue, self.scale_factor);
+                    self.polylines.push(Polyline { points, width, line });
+                }
+            }
+        }
+        self.polylines_dirty = true;
+    }
+
+    /// Replaces the shaded region with a tessellation filled in a colour, reusing the GPU
+    /// resources of the previous region.
+    pub fn set_shading(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, shading: Option<(Tessellation, Color<f32>)>) {
+        let Some((tessellation, color)) = shading else {
+            self.shading = None;
+            return;
+        };
+        let line = self.shading.get_or_insert_with(|| {
+            Line::new(device, Vec::new(), self.line_buffer_size, color, self.scale_factor, &self.line_bind_group_layout)
+        });
+        line.color = color;
+        line.write_uniform(queue, self.scale_factor);
+        line.set_tessellation(tessellation);
+        if let Err(error) = line.update_buffers(device, queue) {
+            self.errors.push(error);
+        }
+    }
+
+    pub fn remove_line(&mut self, id: LineId) -> Result<(), EngineError> {
+        self.pending.remove(&id);
+        self.lines.remove(&id).map(|_| ()).ok_or(EngineError::UnknownLine(id))
+    }
+
+    pub fn is_line_pending(&self, id: LineId) -> bool {
+        self.pending.contains(&id)
+    }
+
+    /// Returns the value of a line's equation at x, or None if there is no such line or its
+    /// equation is empty or failed to parse.
+    pub fn evaluate(&self, id: LineId, x: f32) -> Option<f32> {
+        self.lines.get(&id)
+            .filter(|line| !line.coeffs.is_empty())
+            .map(|line| polynomial_equation(x, &line.coeffs))
+    }
+
+    /// Returns n evenly spaced samples of a line's equation from x_min to x_max, or nothing if
+    /// there is no such line or its equation is empty.
+    pub fn sample(&self, id: LineId, x_min: f64, x_max: f64, n: usize) -> Vec<(f64, f64)> {
+        self.lines.get(&id)
+            .filter(|line| !line.coeffs.is_empty())
+            .map(|line| sample_evenly(&line.coeffs, x_min, x_max, n))
+            .unwrap_or_default()
+    }
+
+    /// Returns the coefficients of a line's equation with the current parameter values, from x^0
+    /// up.
+    pub fn line_coeffs(&self, id: LineId) -> Option<&[f32]> {
+        self.lines.get(&id).map(|line| line.coeffs.as_slice())
+    }
+
+    /// Returns the failures since this was last called, oldest first.
+    pub fn take_errors(&mut self) -> Vec<EngineError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Returns true if any line is waiting to be tessellated or for its tessellation to finish.
+    pub fn needs_update(&self) -> bool {
+        !self.pending.is_empty() || self.lines.values().any(|line| line.dirty)
+    }
+
+    fn line_mut(&mut self, id: LineId) -> Result<&mut Line, EngineError> {
+        self.lines.get_mut(&id).ok_or(EngineError::UnknownLine(id))
+    }
+
+    pub fn set_line_visible(&mut self, id: LineId, visible: bool) -> Result<(), EngineError> {
+        self.line_mut(id)?.visible = visible;
+        Ok(())
+    }
+
+    pub fn set_line_color(&mut self, queue: &wgpu::Queue, id: LineId, color: Color<f32>) -> Result<(), EngineError> {
+        let scale_factor = self.scale_factor;
+        let line = self.line_mut(id)?;
+        line.color = color;
+        line.write_uniform(queue, scale_factor);
+        Ok(())
+    }
+
+    pub fn set_line_style(&mut self, queue: &wgpu::Queue, id: LineId, style: LineStyle) -> Result<(), EngineError> {
+        let scale_factor = self.scale_factor;
+        let line = self.line_mut(id)?;
+        // only the width changes the geometry
+        if style.width != line.style.width {
+            line.dirty = true;
+        }
+        line.style = style;
+        line.write_uniform(queue, scale_factor);
+        Ok(())
+    }
+
+    /// Parses an equation with the current parameter values, returning its coefficients from x^0
+    /// up.
+    pub fn parse(&self, equation: &str) -> Result<Vec<f32>, ParseError> {
+        parse_equation(equation, &self.parameters)
+    }
+
+    /// Replaces the equation of a line, clearing it if the equation can't be parsed.
+    pub fn update_line(&mut self, id: LineId, equation: &str) -> Result<(), EngineError> {
+        let parsed = parse_equation(equation, &self.parameters);
+        let line = self.line_mut(id)?;
+        line.equation = equation.to_owned();
+        line.coeffs = parsed.clone().unwrap_or_default();
+        line.dirty = true;
+        parsed.map(|_| ()).map_err(EngineError::Parse)
+    }
+
+    /// Sets the value of a parameter, updating the lines which use it. Returns the ids of the
+    /// lines whose coefficients changed.
+    pub fn set_parameter(&mut self, name: char, value: f32) -> Vec<LineId> {
+        if self.parameters.insert(name, value) == Some(value) {
+            return Vec::new();
+        }
+        let mut changed = Vec::new();
+        for (id, line) in self.lines.iter_mut().filter(|(_, line)| line.equation.contains(name)) {
+            // lines which failed to parse are already cleared and stay that way
+            if let Ok(coeffs) = parse_equation(&line.equation, &self.parameters) {
+                line.coeffs = coeffs;
+                line.dirty = true;
+                changed.push(*id);
+            }
+        }
+        changed
+    }
+
+    /// Returns the point on any visible curve closest to p, if one passes within radius of it.
+    pub fn nearest_point(&self, p: cgmath::Vector2<f32>, radius: f32, world_per_pixel: f32) -> Option<cgmath::Vector2<f32>> {
+        self.lines.values()
+            .filter_map(|line| line.nearest_point(p, radius, world_per_pixel))
+            .min_by(|a, b| (a - p).magnitude2().total_cmp(&(b - p).magnitude2()))
+    }
+
+    /// Returns the id of the line closest to p, if any pass within radius world units.
+    pub fn line_at(&self, p: cgmath::Vector2<f32>, radius: f32, world_per_pixel: f32) -> Option<LineId> {
+        self.lines.iter()
+            .filter_map(|(id, line)| line.distance_to(p, radius, world_per_pixel).map(|distance| (*id, distance)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    pub fn update_equations(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        camera: &camera::Camera,
+        size: dpi::PhysicalSize<u32>,
+        scale_factor: f32,
+    ) {
+        let world_per_pixel = camera.world_units_per_pixel(size);
+        let bounds = camera.visible_bounds();
+
+        if scale_factor != self.scale_factor {
+            self.scale_factor = scale_factor;
+            for line in self.lines.values_mut() {
+                line.write_uniform(queue, scale_factor);
+                line.dirty = true;
+            }
+            for polyline in &self.polylines {
+                polyline.line.write_uniform(queue, scale_factor);
+            }
+            self.polylines_dirty = true;
+        }
+
+        let view = Some((bounds, world_per_pixel));
+        if view != self.last_view {
+            self.last_view = view;
+            for line in self.lines.values_mut() {
+                line.dirty = true;
+            }
+            self.polylines_dirty = true;
+        }
+
+        if self.polylines_dirty {
+            self.polylines_dirty = false;
+            for polyline in &mut self.polylines {
+                let width = match polyline.width {
+                    Some(width) => width / 2.0 + LINE_FRINGE * world_per_pixel,
+                    None => (polyline.line.style.width / 2.0 * scale_factor + LINE_FRINGE) * world_per_pixel,
+                };
+                polyline.line.set_tessellation(Tessellation::polyline(&polyline.points, world_per_pixel, width));
+                if let Err(error) = polyline.line.update_buffers(device, queue) {
+                    self.errors.push(error);
+                }
+            }
+        }
+
+        for (id, line) in &mut self.lines {
+            // a line already queued keeps its previous job rather than queueing more work behind
+            // it, and stays dirty to be queued again once that job is taken
+            if !line.dirty || self.pending.contains(id) {
+                continue;
+            }
+            // cleared before the job runs so that edits made meanwhile queue another one
+            line.dirty = false;
+            let job = TessellationJob {
+                id: *id,
+                coeffs: line.coeffs.clone(),
+                bounds,
+                world_per_pixel,
+                width: (line.style.width / 2.0 * scale_factor + LINE_FRINGE) * world_per_pixel,
+            };
+            if self.jobs.send(job).is_ok() {
+                self.pending.insert(*id);
+            } else {
+                self.errors.push(EngineError::Tessellation { equation: line.equation.clone() });
+            }
+        }
+
+        // one deadline for the whole frame, however many lines are waiting
+        let deadline = Instant::now() + TESSELLATION_BUDGET;
+        while !self.pending.is_empty() {
+            let (id, tessellation) = match self.results.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(result) => result,
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    log::error!("the tessellation worker stopped");
+                    let lines = &self.lines;
+                    self.errors.extend(std::mem::take(&mut self.pending).into_iter()
+                        .filter_map(|id| lines.get(&id))
+                        .map(|line| EngineError::Tessellation { equation: line.equation.clone() }));
+                    break;
+                }
+            };
+            // the line may have been removed while its job was running
+            if !self.pending.remove(&id) {
+                continue;
+            }
+            let Some(line) = self.lines.get_mut(&id) else {
+                continue;
+            };
+            match tessellation {
+                Some(tessellation) => {
+                    line.set_tessellation(tessellation);
+                    if let Err(error) = line.update_buffers(device, queue) {
+                        self.errors.push(error);
+                    }
+                }
+                None => {
+                    log::error!("tessellation of line {id:?} failed");
+                    self.errors.push(EngineError::Tessellation { equation: line.equation.clone() });
+                }
+            }
+        }
+    }
+
+}
+
+/// A line to tessellate on the worker, for the view it was queued in.
+struct TessellationJob {
+    id: LineId,
+    coeffs: Vec<f32>,
+    bounds: camera::Bounds,
+    world_per_pixel: f32,
+    width: f32,
+}
+
+/// Starts the thread lines are tessellated on, which runs until the returned sender is dropped
+/// with its pipeline. A job which panics is answered with None, leaving the worker running.
+fn spawn_tessellation_worker() -> (mpsc::Sender<TessellationJob>, mpsc::Receiver<(LineId, Option<Tessellation>)>) {
+    let (jobs, job_receiver) = mpsc::channel::<TessellationJob>();
+    let (result_sender, results) = mpsc::channel();
+    std::thread::spawn(move || {
+        for job in job_receiver {
+            let tessellation = std::panic::catch_unwind(|| Tessellation::polynomial(
+                &job.coeffs,
+                job.bounds.x_min,
+                job.bounds.x_max,
+                job.world_per_pixel,
+                job.width,
+            ));
+            if result_sender.send((job.id, tessellation.ok())).is_err() {
+                break;
+            }
+        }
+    });
+    (jobs, results)
+}
+
+/// Places the unit marker at a point, scaled to its radius and filled in its colour.
+fn point_instance(point: &Point) -> Instance {
+    Instance {
+        position: point.position.extend(0.0),
+        rotation: cgmath::Quaternion::from_axis_angle(cgmath::Vector3::unit_z(), cgmath::Deg(0.0)),
+        scale: cgmath::vec3(point.radius, point.radius, 1.0),
+        color: point.color,
+    }
+}
+
+/// Returns the mesh of every marker, in the order of Marker::ALL, sharing one vertex and index
+/// buffer, along with the range of indices each marker takes up.
+fn marker_meshes(radius: f32) -> (Mesh, Vec<std::ops::Range<u32>>) {
+    let mut meshes = Mesh { vertices: Vec::new(), indices: Vec::new() };
+    let mut ranges = Vec::new();
+    for marker in Marker::ALL {
+        let mesh = marker.mesh(radius);
+        let base = meshes.vertices.len() as u16;
+        let start = meshes.indices.len() as u32;
+        meshes.indices.extend(mesh.indices.iter().map(|index| index + base));
+        meshes.vertices.extend(mesh.vertices);
+        ranges.push(start..meshes.indices.len() as u32);
+    }
+    // wgpu only copies whole 4 byte words, so an odd number of u16 indices is padded with one
+    // which isn't drawn
+    if meshes.indices.len() % 2 == 1 {
+        meshes.indices.push(0);
+    }
+    (meshes, ranges)
+}
+
+/// Instances of groups of points, grouped by marker.
+struct GroupedInstances {
+    instances: Vec<Instance>,
+    /// The range of instances which have each marker, in the order of Marker::ALL.
+    ranges: Vec<std::ops::Range<u32>>,
+    /// The group and index of the point each instance is drawn for.
+    origins: Vec<(u32, u32)>,
+}
+
+/// Returns the instances of each group of points together, grouped by marker in the order of
+/// Marker::ALL. Each marker's instances are sorted by x, so those on screen can be found without
+/// looking at the rest.
+fn instances_by_marker(groups: &[&[Point]]) -> GroupedInstances {
+    let mut instances = Vec::new();
+    let mut origins = Vec::new();
+    let mut ranges = Vec::new();
+    for marker in Marker::ALL {
+        let start = instances.len();
+        let mut grouped = groups.iter().enumerate()
+            .flat_map(|(group, points)| points.iter().enumerate().map(move |(index, point)| ((group as u32, index as u32), point)))
+            .filter(|(_, point)| point.marker == marker)
+            .map(|(origin, point)| (origin, point_instance(point)))
+            .collect::<Vec<_>>();
+        grouped.sort_by(|(_, a), (_, b)| a.position.x.total_cmp(&b.position.x));
+        for (origin, instance) in grouped {
+            origins.push(origin);
+            instances.push(instance);
+        }
+        ranges.push(start as u32..instances.len() as u32);
+    }
+    GroupedInstances { instances, ranges, origins }
+}
+
+/// Moves the instance at `at` within range, a run of instances sorted by x apart from it, to
+/// where it keeps the run sorted, carrying its origin along with it. Returns where it ends up.
+fn resort_instance(instances: &mut [Instance], origins: &mut [(u32, u32)], range: std::ops::Range<usize>, mut at: usize) -> usize {
+    let x = instances[at].position.x;
+    while at > range.start && instances[at - 1].position.x > x {
+        instances.swap(at - 1, at);
+        origins.swap(at - 1, at);
+        at -= 1;
+    }
+    while at + 1 < range.end && instances[at + 1].position.x < x {
+        instances.swap(at, at + 1);
+        origins.swap(at, at + 1);
+        at += 1;
+    }
+    at
+}
+
+/// Narrows range, a run of instances sorted by x, to those between x_min and x_max.
+fn instances_between(instances: &[Instance], range: std::ops::Range<u32>, x_min: f32, x_max: f32) -> std::ops::Range<u32> {
+    let sorted = &instances[range.start as usize..range.end as usize];
+    let start = sorted.partition_point(|instance| instance.position.x < x_min);
+    let end = sorted.partition_point(|instance| instance.position.x <= x_max);
+    range.start + start as u32..range.start + end.max(start) as u32
+}
+
+pub struct PointPipeline {
+    pub render_pipeline: Rc<wgpu::RenderPipeline>,
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    /// The indices of each marker's mesh, in the order of Marker::ALL.
+    mesh_ranges: Vec<std::ops::Range<u32>>,
+    pub instance_buffer: DynamicBuffer,
+    /// Instances of the points grouped by marker, so they aren't in the same order as points.
+    pub instances: Vec<Instance>,
+    /// The instances drawn with each marker, in the order of Marker::ALL.
+    instance_ranges: Vec<std::ops::Range<u32>>,
+    /// The point each instance is drawn for, as its group, 0 for points and 1 for series points,
+    /// and its index within the group.
+    instance_origins: Vec<(u32, u32)>,
+    /// The instance each of points is drawn with, so a dragged point can be found without
+    /// regrouping the rest.
+    point_instances: Vec<u32>,
+    /// The part of each of instance_ranges which is on screen, found on each update.
+    visible_ranges: Vec<std::ops::Range<u32>>,
+    /// Radius of the largest point in logical pixels, which is how far off screen a point can be
+    /// and still be partly visible.
+    max_radius: f32,
+    points: Vec<Point>,
+    /// The points of the data series, which are drawn after points but can't be picked.
+    series_points: Vec<Point>,
+    /// Lines drawn for the data series other than those joining their points, such as box plots.
+    series_lines: Vec<Run>,
+    /// The image of the grid under a complex function, if one is shown.
+    mapping_lines: Vec<Run>,
+    /// Streamlines of a vector field, if one is shown.
+    streamlines: Vec<Run>,
+    /// The boundary of a shaded polar region, if one is shown.
+    region_boundary: Vec<Run>,
+    /// Set when the points have changed since the lines joining them were last taken.
+    connections_dirty: bool,
+    /// Set when instances have changed since they were last written to the instance buffer.
+    instances_dirty: bool,
+    /// Instances changed by moving a point since they were last written, which are all that
+    /// need writing unless instances_dirty is also set.
+    moved_instances: Option<std::ops::RangeInclusive<usize>>,
+}
+
+impl PointPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        cache: &mut PipelineCache,
+        pipeline_layout: &wgpu::PipelineLayout,
+        target: &RenderTargetConfig,
+        instance_buffer_size: u64,
+    ) -> Self {
+        let render_pipeline = cache.render_pipeline(
+            device, 
+            pipeline_layout, 
+            target, 
+            &[Vertex::desc(), InstanceRaw::desc()],
+            include_wgsl!("shader.wgsl"),
+            wgpu::PrimitiveTopology::TriangleList,
+        );
+
+        // markers one logical pixel in radius, converted to world units in update_points and
+        // scaled up to each point's radius by its instance
+        let (meshes, mesh_ranges) = marker_meshes(1.0);
+
+        let vertex_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Vertex Buffer"),
+                contents: bytemuck::cast_slice(&meshes.vertices),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+        
+        let index_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Index Buffer"),
+                contents: bytemuck::cast_slice(&meshes.indices),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let GroupedInstances { instances, ranges: instance_ranges, origins: instance_origins } = instances_by_marker(&[]);
+        
+        let instance_buffer = DynamicBuffer::new(
+            device,
+            "Points Instance Buffer",
+            wgpu::BufferUsages::VERTEX,
+            instance_buffer_size,
+        );
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+            mesh_ranges,
+            instance_buffer,
+            instances,
+            visible_ranges: instance_ranges.clone(),
+            instance_ranges,
+            instance_origins,
+            point_instances: Vec::new(),
+            max_radius: 0.0,
+            points: Vec::new(),
+            series_points: Vec::new(),
+            series_lines: Vec::new(),
+            mapping_lines: Vec::new(),
+            streamlines: Vec::new(),
+            region_boundary: Vec::new(),
+            connections_dirty: false,
+            instances_dirty: false,
+            moved_instances: None,
+        }
+    }
+
+    pub fn update_points(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        camera: &camera::Camera,
+        size: dpi::PhysicalSize<u32>,
+        scale_factor: f32,
+    ) -> Result<(), EngineError> {
+        let mut written = Ok(());
+        if let Some(moved) = self.moved_instances.take().filter(|_| !self.instances_dirty) {
+            let instance_data = self.instances[moved.clone()].iter().map(Instance::to_raw).collect::<Vec<_>>();
+            let offset = (*moved.start() * std::mem::size_of::<InstanceRaw>()) as u64;
+            if !self.instance_buffer.write_at(queue, offset, bytemuck::cast_slice(&instance_data)) {
+                self.instances_dirty = true;
+            }
+        }
+        if self.instances_dirty {
+            let instance_data = &self.instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
+            written = self.instance_buffer.write(device, queue, bytemuck::cast_slice(instance_data)).map(|_| ());
+            if written.is_err() {
+                // nothing is drawn until the points change again, rather than stale instances
+                GroupedInstances { instances: self.instances, ranges: self.instance_ranges, origins: self.instance_origins } =
+                    instances_by_marker(&[]);
+                self.point_instances.clear();
+            }
+            self.instances_dirty = false;
+        }
+
+        let world_per_pixel = camera.world_units_per_pixel(size);
+        // points are only culled along x, which is all their sorting allows, and only by their
+        // centres, so the view is widened by the largest radius
+        let bounds = camera.visible_bounds();
+        let margin = self.max_radius * scale_factor * world_per_pixel;
+        self.visible_ranges = self.instance_ranges.iter()
+            .map(|range| instances_between(&self.instances, range.clone(), bounds.x_min - margin, bounds.x_max + margin))
+            .collect();
+
+        let (meshes, _) = marker_meshes(scale_factor * world_per_pixel);
+
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&meshes.vertices));
+        queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&meshes.indices));
+        written
+    }
+
+    /// Returns the indices of each marker's mesh with the instances drawn with it, leaving out
+    /// markers with no points on screen.
+    pub fn draws(&self) -> impl Iterator<Item = (std::ops::Range<u32>, std::ops::Range<u32>)> + '_ {
+        self.mesh_ranges.iter().cloned()
+            .zip(self.visible_ranges.iter().cloned())
+            .filter(|(_, instances)| !instances.is_empty())
+    }
+
+    /// Brings point_instances up to date for the instances in range, after they have moved.
+    fn index_point_instances(&mut self, range: std::ops::Range<usize>) {
+        for (instance, &(group, index)) in self.instance_origins[range.clone()].iter().enumerate() {
+            if group == 0 {
+                self.point_instances[index as usize] = (range.start + instance) as u32;
+            }
+        }
+    }
+
+    /// Regroups the instances after the points change. They are written to the GPU on the next
+    /// update.
+    fn rebuild_instances(&mut self) {
+        GroupedInstances { instances: self.instances, ranges: self.instance_ranges, origins: self.instance_origins } =
+            instances_by_marker(&[&self.points, &self.series_points]);
+        self.point_instances = vec![0; self.points.len()];
+        self.index_point_instances(0..self.instances.len());
+        self.max_radius = self.points.iter()
+            .chain(&self.series_points)
+            .map(|point| point.radius)
+            .fold(0.0, f32::max);
+        self.instances_dirty = true;
+        self.connections_dirty = true;
+    }
+
+    /// Returns the runs of connected points if the points have changed since this was last
+    /// called.
+    pub fn take_connections(&mut self) -> Option<Vec<Run>> {
+        std::mem::take(&mut self.connections_dirty).then(|| {
+            let mut runs = connected_runs(&self.points);
+            runs.extend(connected_runs(&self.series_points));
+            runs.extend(self.series_lines.iter().cloned());
+            runs.extend(self.mapping_lines.iter().cloned());
+            runs.extend(self.streamlines.iter().cloned());
+            runs.extend(self.region_boundary.iter().cloned());
+            runs
+        })
+    }
+
+    /// Replaces the lines of a mapped grid, which are drawn with those joining points.
+    pub fn set_mapping_lines(&mut self, lines: Vec<Run>) {
+        self.mapping_lines = lines;
+        self.connections_dirty = true;
+    }
+
+    /// Replaces the streamlines of a vector field, which are drawn with the lines joining points.
+    pub fn set_streamlines(&mut self, lines: Vec<Run>) {
+        self.streamlines = lines;
+        self.connections_dirty = true;
+    }
+
+    /// Replaces the boundary of a polar region, which is drawn with the lines joining points.
+    pub fn set_region_boundary(&mut self, lines: Vec<Run>) {
+        self.region_boundary = lines;
+        self.connections_dirty = true;
+    }
+
+    /// Replaces the points and lines drawn for the data series, which are written to the GPU on
+    /// the next update. Nothing is drawn for the series if their points and the others together
+    /// are more than the instance buffer can hold.
+    pub fn set_series_shapes(&mut self, points: Vec<Point>, lines: Vec<Run>) -> Result<(), EngineError> {
+        if let Err(error) = self.check_capacity(self.points.len() + points.len()) {
+            self.series_points.clear();
+            self.series_lines.clear();
+            self.rebuild_instances();
+            return Err(error);
+        }
+        self.series_points = points;
+        self.series_lines = lines;
+        self.rebuild_instances();
+        Ok(())
+    }
+
+    /// Returns an error if count points, including those of the data series, are more than the
+    /// instance buffer can hold.
+    fn check_capacity(&self, count: usize) -> Result<(), EngineError> {
+        self.instance_buffer.check_fits((count * std::mem::size_of::<InstanceRaw>()) as u64)
+    }
+
+    /// Adds a point, unless there are already as many as the instance buffer can hold. Points
+    /// added in order of x with the last marker in use, as when streaming samples, are written
+    /// straight after the instances already on the GPU. Otherwise the instances are regrouped and
+    /// all written on the next update.
+    pub fn add_point(&mut self, queue: &wgpu::Queue, point: Point) -> Result<(), EngineError> {
+        self.check_capacity(self.points.len() + self.series_points.len() + 1)?;
+        let instance = point_instance(&point);
+        let group = Marker::ALL.iter().position(|&marker| marker == point.marker).unwrap();
+        let range = self.instance_ranges[group].clone();
+        let end = self.instances.len() as u32;
+        let appendable = !self.instances_dirty
+            && range.end == end
+            && self.instances[range.start as usize..range.end as usize].last()
+                .is_none_or(|last| last.position.x <= instance.position.x);
+
+        self.max_radius = self.max_radius.max(point.radius);
+        self.points.push(point);
+        self.connections_dirty = true;
+
+        let offset = end as u64 * std::mem::size_of::<InstanceRaw>() as u64;
+        if appendable && self.instance_buffer.write_at(queue, offset, bytemuck::cast_slice(&[instance.to_raw()])) {
+            self.instances.push(instance);
+            self.instance_origins.push((0, self.points.len() as u32 - 1));
+            self.point_instances.push(end);
+            self.instance_ranges[group].end += 1;
+            // the markers after this one have no instances, but their empty ranges follow on
+            for range in &mut self.instance_ranges[group + 1..] {
+                *range = end + 1..end + 1;
+            }
+        } else {
+            self.rebuild_instances();
+        }
+
+        Ok(())
+    }
+
+    /// Returns every point on the graph, in order.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// Replaces every point, which are written to the GPU on the next update. The points are
+    /// left as they were if there are more than the instance buffer can hold.
+    pub fn set_points(&mut self, points: &[Point]) -> Result<(), EngineError> {
+        self.check_capacity(points.len() + self.series_points.len())?;
+        self.points = points.to_vec();
+        self.rebuild_instances();
+        Ok(())
+    }
+
+    /// Removes the point at index, shifting later points down. The instance buffer is rewritten
+    /// without it on the next update.
+    pub fn remove_point(&mut self, index: usize) -> Result<(), EngineError> {
+        if index >= self.points.len() {
+            return Err(EngineError::UnknownPoint(index));
+        }
+        self.points.remove(index);
+        self.rebuild_instances();
+        Ok(())
+    }
+
+    /// Sets the annotation of the point at index, which doesn't change how the point is drawn.
+    pub fn set_label(&mut self, index: usize, label: Option<Annotation>) -> Result<(), EngineError> {
+        let point = self.points.get_mut(index).ok_or(EngineError::UnknownPoint(index))?;
+        point.label = label;
+        Ok(())
+    }
+
+    /// Returns the index of the point drawn under a screen position, if any.
+    pub fn point_at(
+        &self,
+        camera: &camera::Camera,
+        size: dpi::PhysicalSize<u32>,
+        scale_factor: f32,
+        pos: cgmath::Vector2<f32>,
+    ) -> Option<usize> {
+        let positions = self.points.iter().map(|point| {
+            let position = camera.world_to_screen_space(point.position.extend(0.0), size);
+            (position, (point.radius + POINT_HIT_SLOP) * scale_factor)
+        });
+        nearest_within(positions, pos)
+    }
+
+    /// Moves the point at index, which is written to the GPU on the next update. Only its
+    /// instance is replaced, moved along its marker's instances as far as keeps them sorted, and
+    /// only the instances it passed are written.
+    pub fn move_point(&mut self, index: usize, position: cgmath::Vector2<f32>) -> bool {
+        let Some(point) = self.points.get_mut(index) else {
+            return false;
+        };
+        point.position = position;
+        let instance = point_instance(point);
+        self.connections_dirty = true;
+        // everything is written on the next update anyway, or the instances were dropped when
+        // they couldn't be written
+        let Some(&from) = self.point_instances.get(index).filter(|_| !self.instances_dirty) else {
+            self.rebuild_instances();
+            return true;
+        };
+        let from = from as usize;
+        let range = self.instance_ranges.iter()
+            .find(|range| range.contains(&(from as u32)))
+            .map(|range| range.start as usize..range.end as usize)
+            .unwrap_or(from..from + 1);
+        self.instances[from] = instance;
+        let to = resort_instance(&mut self.instances, &mut self.instance_origins, range, from);
+        let moved = from.min(to)..=from.max(to);
+        self.index_point_instances(*moved.start()..*moved.end() + 1);
+        self.moved_instances = Some(match self.moved_instances.take() {
+            Some(before) => *before.start().min(moved.start())..=*before.end().max(moved.end()),
+            None => moved,
+        });
+        true
+    }
+}
+
+
+/// Draws the legend's frame and colour swatches as quads in screen space. Its names are drawn
+/// with the rest of the text.
+pub struct LegendPipeline {
+    pub render_pipeline: Rc<wgpu::RenderPipeline>,
+    /// A unit square, which each instance scales and moves into place.
+    pub vertex_buffer: wgpu::Buffer,
+    pub instance_buffer: DynamicBuffer,
+    pub instances: Vec<Instance>,
+    /// Maps physical pixels from the top left of the viewport to clip space.
+    screen_buffer: wgpu::Buffer,
+    pub screen_bind_group: wgpu::BindGroup,
+}
+
+impl LegendPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        cache: &mut PipelineCache,
+        pipeline_layout: &wgpu::PipelineLayout,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        target: &RenderTargetConfig,
+    ) -> Self {
+        let render_pipeline = cache.render_pipeline(
+            device,
+            pipeline_layout,
+            target,
+            &[Vertex::desc(), InstanceRaw::desc()],
+            include_wgsl!("shader.wgsl"),
+            wgpu::PrimitiveTopology::TriangleStrip,
+        );
+
+        let square: &[Vertex] = &[
+            Vertex { position: [0.0, 0.0, 0.0] },
+            Vertex { position: [1.0, 0.0, 0.0] },
+            Vertex { position: [0.0, 1.0, 0.0] },
+            Vertex { position: [1.0, 1.0, 0.0] },
+        ];
+        let vertex_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Legend Vertex Buffer"),
+                contents: bytemuck::cast_slice(square),
+                usage: wgpu::BufferUsages::VERTEX,
+            }
+        );
+
+        let instance_buffer = DynamicBuffer::new(
+            device,
+            "Legend Instance Buffer",
+            wgpu::BufferUsages::VERTEX,
+            1024,
+        );
+
+        let screen_buffer = device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Legend Screen Buffer"),
+                size: std::mem::size_of::<[[f32; 4]; 4]>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }
+        );
+        let screen_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: screen_buffer.as_entire_binding(),
+                }
+            ],
+            label: Some("Legend Screen Bind Group"),
+        });
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            instance_buffer,
+            instances: Vec::new(),
+            screen_buffer,
+            screen_bind_group,
+        }
+    }
+
+    /// Rebuilds the quads for layout, or clears them if there is no legend.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_legend(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: Option<&legend::Layout>,
+        entries: &[legend::LegendEntry],
+        theme: &Theme,
+        size: dpi::PhysicalSize<u32>,
+        scale_factor: f32,
+    ) -> Result<(), EngineError> {
+        let quad = |rect: legend::Rect, color| Instance {
+            position: rect.min.extend(0.0),
+            rotation: cgmath::Quaternion::one(),
+            scale: rect.size.extend(1.0),
+            color,
+        };
+
+        self.instances.clear();
+        if let Some(layout) = layout {
+            // the frame is drawn as a filled border colour square with the background inset over it
+            let border = scale_factor.max(1.0);
+            let inner = legend::Rect {
+                min: layout.frame.min + cgmath::vec2(border, border),
+                size: layout.frame.size - cgmath::vec2(border, border) * 2.0,
+            };
+            self.instances.push(quad(layout.frame, theme.grid.major_color));
+            self.instances.push(quad(inner, theme.background));
+            for (swatch, entry) in layout.swatches.iter().zip(entries) {
+                self.instances.push(quad(*swatch, entry.color));
+            }
+        }
+
+        let screen: [[f32; 4]; 4] = cgmath::ortho(0.0, size.width as f32, size.height as f32, 0.0, -1.0, 1.0).into();
+        queue.write_buffer(&self.screen_buffer, 0, bytemuck::cast_slice(&screen));
+        let instance_data = self.instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
+        let written = self.instance_buffer.write(device, queue, bytemuck::cast_slice(&instance_data));
+        if written.is_err() {
+            self.instances.clear();
+        }
+        written.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_instances_vertical() {
+        let x = 5.0;
+        let y = 200.0;
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (x, y, 4.0).into(),
+            target: (x, y, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let v_instances = get_instances(&camera, true, &GridStyle::default(), 0.01, 1.0, false);
+        let h_instances = get_instances(&camera, false, &GridStyle::default(), 0.01, 1.0, false);
+
+        for (v_instance, h_instance) in v_instances.iter().zip(h_instances.iter()) {
+            // they will share a common point in the center
+            if v_instance.position.x != 0.0 && v_instance.position.y != 0.0 {
+                // assert the positions are different as they should be here if vertical functions
+                assert_ne!(v_instance.position, h_instance.position);
+            }
+        }
+    }
+    #[test]
+    #[cfg(feature = "text")]
+    fn grid_stays_precise_far_from_the_origin() {
+        use approx::relative_eq;
+
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (1e7, 0.0, 5.0).into(),
+            target: (1e7, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let instances = get_instances(&camera, true, &GridStyle::default(), 0.01, 1.0, false);
+        let labels = get_labels(&camera, true, &GridStyle::default(), false);
+
+        let label = labels.iter().find(|label| label.text == "1.0000001e7").unwrap();
+        assert!(relative_eq!(label.offset.x, 1.0));
+        // minor lines keep their spacing rather than snapping to whole units, as f32 would here
+        let spacing = instances[1].position.x - instances[0].position.x;
+        assert!(relative_eq!(spacing, 0.2, max_relative = 1e-5));
+    }
+    #[test]
+    fn grid_steps_follow_1_2_5() {
+        use approx::relative_eq;
+
+        let majors = [8.0, 16.0, 40.0, 80.0].map(|zoom| grid_steps(zoom, 8.0).0.value() as f32);
+        for (major, expected) in majors.into_iter().zip([1.0, 2.0, 5.0, 10.0]) {
+            assert!(relative_eq!(major, expected, max_relative = 1e-5));
+        }
+        assert_eq!(grid_steps(16.0, 8.0), (TickStep { mantissa: 2, exponent: 0 }, 4));
+
+        // and works far beyond the range of integer eye heights
+        assert!(relative_eq!(grid_steps(8e-6, 8.0).0.value() as f32, 1e-6, max_relative = 1e-5));
+        assert!(relative_eq!(grid_steps(8e12, 8.0).0.value() as f32, 1e12, max_relative = 1e-5));
+    }
+    #[test]
+    fn minor_lines_fade_in_as_they_spread_out() {
+        assert_eq!(minor_fade(0.2, 8.0, 8.0), 0.0);
+        assert!(minor_fade(0.2, 6.0, 8.0) > 0.0 && minor_fade(0.2, 6.0, 8.0) < 1.0);
+        assert_eq!(minor_fade(0.2, 4.0, 8.0), 1.0);
+    }
+    #[test]
+    #[cfg(feature = "text")]
+    fn labels_are_on_major_lines_away_from_the_axis() {
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (0.0, 0.0, 8.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let labels = get_labels(&camera, true, &GridStyle::default(), false);
+
+        assert!(labels.iter().all(|label| label.offset.x != 0.0 && label.offset.x.fract() == 0.0));
+        assert!(labels.iter().any(|label| label.text == "3"));
+    }
+    #[test]
+    #[cfg(feature = "text")]
+    fn labels_have_no_floating_point_noise() {
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (0.0, 0.0, 0.8).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let labels = get_labels(&camera, false, &GridStyle::default(), false);
+
+        assert!(labels.iter().any(|label| label.text == "0.3"));
+        assert!(labels.iter().all(|label| label.text.len() <= 4));
+    }
+    #[test]
+    #[cfg(feature = "text")]
+    fn test_format_pi_tick() {
+        assert_eq!(format_pi_tick(0, 0.5), "0");
+        assert_eq!(format_pi_tick(1, 0.5), "π/2");
+        assert_eq!(format_pi_tick(2, 0.5), "π");
+        assert_eq!(format_pi_tick(3, 0.5), "3π/2");
+        assert_eq!(format_pi_tick(-4, 0.5), "-2π");
+        assert_eq!(format_pi_tick(-1, 2.0), "-2π");
+        assert_eq!(format_pi_tick(3, 0.2), "3π/5");
+    }
+    #[test]
+    #[cfg(feature = "text")]
+    fn pi_ticks_fall_on_multiples_of_pi() {
+        use approx::relative_eq;
+
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (0.0, 0.0, 12.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+
+        let labels = get_labels(&camera, true, &GridStyle::default(), true);
+        let label = labels.iter().find(|label| label.text == "π").unwrap();
+
+        assert!(relative_eq!(label.offset.x, std::f32::consts::PI));
+    }
+    #[test]
+    fn subdivisions_set_the_number_of_minor_lines() {
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (0.0, 0.0, 6.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+        let style = |minor_subdivisions| GridStyle { minor_subdivisions, ..GridStyle::default() };
+        let between = |instances: &[Instance]| {
+            instances.iter().filter(|instance| instance.position.x > 0.0 && instance.position.x < 1.0).count()
+        };
+
+        let instances = get_instances(&camera, true, &style(Some(10)), 0.01, 1.0, false);
+        assert_eq!(between(&instances), 9);
+
+        // a single subdivision leaves only the labelled lines
+        let instances = get_instances(&camera, true, &style(Some(1)), 0.01, 1.0, false);
+        assert_eq!(between(&instances), 0);
+    }
+
+    #[test]
+    fn line_widths_scale_across_the_line() {
+        use approx::relative_eq;
+
+        let camera = camera::Camera {
+            projection: camera::Projection::Orthographic,
+            eye: (0.0, 0.0, 6.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+        let style = GridStyle { axis_width: 3.0, minor_width: 0.25, ..GridStyle::default() };
+
+        let instances = get_instances(&camera, true, &style, 0.01, 2.0, false);
+        let axis = instances.iter().find(|instance| instance.position.x == 0.0).unwrap();
+        assert!(relative_eq!(axis.scale.x, 0.06));
+        assert!(relative_eq!(axis.scale.y, 1.0));
+
+        // thin lines are widened to a physical pixel
+        let thinnest = instances.iter().map(|instance| instance.scale.x).fold(f32::MAX, f32::min);
+        assert!(relative_eq!(thinnest, 0.01));
+
+        let instances = get_instances(&camera, false, &style, 0.01, 2.0, false);
+        let axis = instances.iter().find(|instance| instance.position.y == 0.0).unwrap();
+        assert!(relative_eq!(axis.scale.x, 1.0));
+        assert!(relative_eq!(axis.scale.y, 0.06));
+    }
+
+    #[test]
+    fn axis_ends_are_at_the_edges_of_the_view() {
+        let half_extent = cgmath::vec2(4.0, 2.0);
+
+        let (x_end, y_end) = axis_ends((1.0, 0.5, 5.0).into(), half_extent, 0.1);
+        assert_eq!(x_end, Some(cgmath::vec3(3.9, -0.5, 0.0)));
+        assert_eq!(y_end, Some(cgmath::vec3(-1.0, 1.9, 0.0)));
+
+        // the x axis is below the view, so only the y axis has an arrow
+        let (x_end, y_end) = axis_ends((0.0, 3.0, 5.0).into(), half_extent, 0.1);
+        assert_eq!(x_end, None);
+        assert!(y_end.is_some());
+    }
+    #[test]
+    fn quadrant_labels_centre_on_the_part_in_view() {
+        let half_extent = cgmath::vec2(4.0, 2.0);
+
+        let centred = quadrant_centres((0.0, 0.0, 5.0).into(), half_extent);
+        assert_eq!(centred, [(2.0, 1.0), (-2.0, 1.0), (-2.0, -1.0), (2.0, -1.0)].map(|(x, y)| Some(cgmath::vec3(x, y, 0.0))));
+
+        // the y axis is just inside the left edge, leaving a sliver of II and III
+        let [first, second, _, fourth] = quadrant_centres((3.0, 0.0, 5.0).into(), half_extent);
+        assert_eq!(first, Some(cgmath::vec3(0.5, 1.0, 0.0)));
+        assert_eq!(second, Some(cgmath::vec3(-3.5, 1.0, 0.0)));
+        assert_eq!(fourth, Some(cgmath::vec3(0.5, -1.0, 0.0)));
+
+        // with both axes off to the bottom left, only I is in view, filling it
+        let corner = quadrant_centres((10.0, 10.0, 5.0).into(), half_extent);
+        assert_eq!(corner, [Some(cgmath::vec3(0.0, 0.0, 0.0)), None, None, None]);
+    }
+    #[test]
+    fn arrowheads_point_back_from_the_tip() {
+        use approx::relative_eq;
+
+        let color = GridStyle::default().axis_color;
+        let tip = cgmath::vec3(2.0, 0.0, 0.0);
+        let strokes = arrowhead(tip, 0.0, 1.0, 0.1, 10.0, color);
+
+        for stroke in &strokes {
+            // each stroke is centred half its length behind the tip, mirrored about the shaft
+            assert!(stroke.position.x < tip.x);
+            assert!(relative_eq!((stroke.position - tip).magnitude(), 0.5, epsilon = 1e-6));
+            assert!(relative_eq!(stroke.scale.y, 0.05));
+            // and runs along the line from its centre to the tip
+            let along = stroke.rotation.rotate_vector(cgmath::Vector3::unit_y());
+            assert!(relative_eq!(along.cross(tip - stroke.position).magnitude(), 0.0, epsilon = 1e-6));
+        }
+        assert!(relative_eq!(strokes[0].position.y, -strokes[1].position.y, epsilon = 1e-6));
+    }
+    #[test]
+    fn nearest_within_picks_the_closest_in_range() {
+        let positions = [cgmath::vec2(0.0, 0.0), cgmath::vec2(10.0, 0.0), cgmath::vec2(13.0, 0.0)];
+
+        assert_eq!(nearest_within(positions.into_iter().map(|p| (p, 5.0)), cgmath::vec2(12.0, 1.0)), Some(2));
+        assert_eq!(nearest_within(positions.into_iter().map(|p| (p, 4.0)), cgmath::vec2(5.0, 0.0)), None);
+    }
+    #[test]
+    fn nearest_within_uses_each_tolerance() {
+        // a large point is hit from further away than a small one beside it
+        let positions = [(cgmath::vec2(0.0, 0.0), 2.0), (cgmath::vec2(10.0, 0.0), 8.0)];
+
+        assert_eq!(nearest_within(positions.into_iter(), cgmath::vec2(3.0, 0.0)), Some(1));
+        assert_eq!(nearest_within(positions.into_iter(), cgmath::vec2(1.0, 0.0)), Some(0));
+    }
+    #[test]
+    fn instances_are_grouped_by_marker() {
+        let points = [Marker::Cross, Marker::Circle, Marker::Cross].map(|marker| Point { marker, ..Point::new(cgmath::vec2(0.0, 0.0)) });
+
+        let GroupedInstances { instances, ranges, .. } = instances_by_marker(&[&points[..2], &points[2..]]);
+
+        assert_eq!(instances.len(), 3);
+        assert_eq!(ranges[0], 0..1);
+        assert_eq!(ranges[Marker::ALL.iter().position(|&m| m == Marker::Cross).unwrap()], 1..3);
+        assert_eq!(ranges.iter().filter(|range| range.is_empty()).count(), 3);
+    }
+    #[test]
+    fn instances_are_sorted_by_x_within_markers() {
+        let points = [3.0, -1.0, 2.0].map(|x| Point::new(cgmath::vec2(x, 0.0)));
+
+        let instances = instances_by_marker(&[&points]).instances;
+
+        assert_eq!(instances.iter().map(|instance| instance.position.x).collect::<Vec<_>>(), [-1.0, 2.0, 3.0]);
+    }
+    #[test]
+    fn moved_instances_keep_their_marker_sorted() {
+        let points = [0.0, 1.0, 2.0, 3.0].map(|x| Point::new(cgmath::vec2(x, 0.0)));
+        let GroupedInstances { mut instances, mut origins, .. } = instances_by_marker(&[&points]);
+
+        instances[0].position.x = 2.5;
+        assert_eq!(resort_instance(&mut instances, &mut origins, 0..4, 0), 2);
+        assert_eq!(instances.iter().map(|instance| instance.position.x).collect::<Vec<_>>(), [1.0, 2.0, 2.5, 3.0]);
+        assert_eq!(origins, [(0, 1), (0, 2), (0, 0), (0, 3)]);
+
+        // staying between its neighbours leaves everything where it was
+        instances[2].position.x = 2.2;
+        assert_eq!(resort_instance(&mut instances, &mut origins, 0..4, 2), 2);
+        instances[3].position.x = -1.0;
+        assert_eq!(resort_instance(&mut instances, &mut origins, 0..4, 3), 0);
+        assert_eq!(origins, [(0, 3), (0, 1), (0, 2), (0, 0)]);
+    }
+    #[test]
+    fn instances_between_culls_by_x() {
+        let points = [0.0, 1.0, 2.0, 3.0, 4.0].map(|x| Point::new(cgmath::vec2(x, 0.0)));
+        let instances = instances_by_marker(&[&points]).instances;
+
+        assert_eq!(instances_between(&instances, 0..5, 0.5, 3.0), 1..4);
+        assert_eq!(instances_between(&instances, 1..3, -10.0, 10.0), 1..3);
+        assert!(instances_between(&instances, 0..5, 5.0, 6.0).is_empty());
+        assert!(instances_between(&instances, 0..5, -2.0, -1.0).is_empty());
+    }
+    #[test]
+    fn marker_meshes_share_buffers() {
+        let (meshes, ranges) = marker_meshes(1.0);
+
+        assert_eq!(ranges.len(), Marker::ALL.len());
+        assert_eq!(meshes.indices.len() % 2, 0);
+        assert!(meshes.indices.iter().all(|&i| (i as usize) < meshes.vertices.len()));
+        // each range indexes only the vertices of its own marker
+        let square = &meshes.indices[ranges[1].start as usize..ranges[1].end as usize];
+        assert_eq!(square.iter().min(), Some(&(Marker::Circle.mesh(1.0).vertices.len() as u16)));
+    }
+    #[test]
+    fn point_instances_are_scaled_to_the_radius() {
+        let point = Point { radius: 6.0, ..Point::new(cgmath::vec2(1.0, 2.0)) };
+
+        let instance = point_instance(&point);
+
+        assert_eq!(instance.position, cgmath::vec3(1.0, 2.0, 0.0));
+        assert_eq!(instance.scale, cgmath::vec3(6.0, 6.0, 1.0));
+    }
+    #[test]
+    fn test_parse_equation_standard() {
+        let equation = "3x^3-4x^2-3x+5";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [5.0, -3.0, -4.0, 3.0]);
+    }
+    #[test]
+    fn test_parse_equation_spacing() {
+        let equation = " 3 x^3 -    4x^2 - 3x+    5 ";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [5.0, -3.0, -4.0, 3.0]);
+    }
+    #[test]
+    fn test_parse_equation_multiple_same_exp() {
+        let equation = " 3x^3 + 3x^3 - 2x^3  -4x^2-3x+5";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [5.0, -3.0, -4.0, 4.0]);
+    }
+    #[test]
+    fn test_parse_equation_missing_terms() {
+        let equation = "3x^3";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [0.0, 0.0, 0.0, 3.0]);
+    }
+    #[test]
+    fn test_parse_equation_one_x() {
+        let equation = "x^3";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [0.0, 0.0, 0.0, 1.0]);
+    }
+    #[test]
+    fn test_parse_equation_neg_plus_one_x() {
+        let equation = "-x^3";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [0.0, 0.0, 0.0, -1.0]);
+
+        let equation = "+x^3";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert_eq!(coeffs, [0.0, 0.0, 0.0, 1.0]);
+    }
+    #[test]
+    fn test_parse_equation_empty() {
+        let equation = "";
+        let coeffs = parse_equation(equation, &HashMap::new()).unwrap();
+
+        assert!(coeffs.is_empty());
+    }
+    #[test]
+    fn test_parse_equation_invalid() {
+        let equation = "this is not a valid equation!";
+        let coeffs = parse_equation(equation, &HashMap::new());
+
+        assert!(coeffs.is_err());
+    }
+    #[test]
+    fn syntax_examples_parse() {
+        let expected: [&[f32]; 5] = [
+            &[1.0, -2.0, 3.0],
+            &[0.0, 1.0, 0.0, 1.0],
+            &[-1e-3, 2.5],
+            &[1.0, 1.0, 1.0],
+            &[0.0, 2.0],
+        ];
+        for ((example, _), coeffs) in SYNTAX.iter().zip(expected) {
+            assert_eq!(parse_equation(example, &HashMap::new()).unwrap(), coeffs, "{example}");
+        }
+    }
+    #[test]
+    fn test_parse_equation_exponent_signs() {
+        assert_eq!(parse_equation("1e-3 - 2E+2x", &HashMap::new()).unwrap(), [1e-3, -200.0]);
+        assert!(equation_parameters("2.5x - 1e-3").is_empty());
+
+        // a sign after a parameter e still starts a term
+        let parameters = HashMap::from([('e', 2.0)]);
+        assert_eq!(parse_equation("3e-x", &parameters).unwrap(), [6.0, -1.0]);
+    }
+    #[test]
+    fn test_parse_equation_parameters() {
+        let parameters = HashMap::from([('a', 2.0), ('b', -3.0)]);
+
+        let coeffs = parse_equation("ax^2 - 2bx + ab", &parameters).unwrap();
+        assert_eq!(coeffs, [-6.0, 6.0, 2.0]);
+
+        // parameters without a value count as 1
+        let coeffs = parse_equation("3cx", &parameters).unwrap();
+        assert_eq!(coeffs, [0.0, 3.0]);
+
+        assert_eq!(equation_parameters("ax^2 - 2bx + ab"), BTreeSet::from(['a', 'b']));
+        assert!(equation_parameters("1e5x").is_empty());
+    }
+    #[test]
+    fn parse_errors_point_at_the_equation_as_written() {
+        let error = parse_equation("3x^2 + 2.5.1x", &HashMap::new()).unwrap_err();
+        assert_eq!(error.span, 5..12);
+        assert_eq!(error.message, "invalid coefficient \"+2.5.1\"");
+
+        let error = parse_equation("x ^ 2 - x^ y", &HashMap::new()).unwrap_err();
+        assert_eq!(&"x ^ 2 - x^ y"[error.span], "^ y");
+
+        let error = parse_equation("4x2", &HashMap::new()).unwrap_err();
+        assert_eq!(error.span, 2..3);
+    }
+
+    #[test]
+    fn one_worker_tessellates_every_line_in_turn() {
+        let (jobs, results) = spawn_tessellation_worker();
+        for id in 0..3 {
+            jobs.send(TessellationJob {
+                id: LineId(id),
+                coeffs: vec![0.0, 1.0],
+                bounds: camera::Bounds { x_min: -1.0, x_max: 1.0, y_min: -1.0, y_max: 1.0 },
+                world_per_pixel: 0.01,
+                width: 0.02,
+            }).unwrap();
+        }
+
+        for id in 0..3 {
+            let (done, tessellation) = results.recv().unwrap();
+            assert_eq!(done, LineId(id));
+            assert!(tessellation.is_some_and(|tessellation| !tessellation.indices.is_empty()));
+        }
+        // the worker stops with its pipeline
+        drop(jobs);
+        assert!(results.recv().is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Regions given in polar form, such as r < 1 + cos θ, shaded either between the origin and their
+//! boundary r = f(θ) or from the boundary outwards.
+
+use cgmath::InnerSpace;
+
+use crate::camera::Bounds;
+use crate::geometry::{Color, Run, StrokeVertex};
+use crate::tessellation::Tessellation;
+
+/// Angles the boundary is sampled at over a whole turn. Each pair of neighbouring samples gives
+/// one quad of the shading, which keeps its vertices well within reach of u16 indices.
+const SAMPLES: usize = 1024;
+
+/// Which side of its boundary a region lies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolarSide {
+    /// Points nearer the origin than the boundary, as in r < f(θ).
+    Inside,
+    /// Points farther from the origin than the boundary, as in r > f(θ).
+    Outside,
+}
+
+/// A region whose boundary is r = f(θ) for θ from 0 to 2π. Distances from the origin are never
+/// negative, so where f(θ) is, the region is empty inside the boundary and everything outside it.
+pub struct PolarRegion {
+    /// The boundary's distance from the origin at an angle in radians, or None where it isn't
+    /// defined.
+    pub radius: Box<dyn Fn(f32) -> Option<f32>>,
+    pub side: PolarSide,
+    /// Colour the region is shaded in, which should be translucent for the graph to show through.
+    pub fill: Color<f32>,
+    /// Colour of the boundary.
+    pub color: Color<f32>,
+}
+
+fn polar(theta: f32, r: f32) -> cgmath::Vector2<f32> {
+    cgmath::vec2(r * theta.cos(), r * theta.sin())
+}
+
+/// A region with its boundary sampled, which is kept as the view moves and only the shading
+/// outside the boundary needs to reach further.
+pub struct SampledRegion {
+    pub region: PolarRegion,
+    /// Each sampled angle with the boundary's distance there, if it is defined and finite.
+    samples: Vec<(f32, Option<f32>)>,
+}
+
+impl SampledRegion {
+    pub fn new(region: PolarRegion) -> Self {
+        let samples = (0..=SAMPLES)
+            .map(|i| {
+                let theta = std::f32::consts::TAU * i as f32 / SAMPLES as f32;
+                (theta, (region.radius)(theta).filter(|r| r.is_finite()))
+            })
+            .collect();
+        Self { region, samples }
+    }
+
+    /// Returns the boundary as runs of points, broken where it is undefined or negative.
+    pub fn boundary(&self) -> Vec<Run> {
+        let mut runs = Vec::new();
+        let mut points = Vec::new();
+        for &(theta, r) in &self.samples {
+            if let Some(r) = r.filter(|r| *r >= 0.0) {
+                points.push(polar(theta, r));
+                continue;
+            }
+            let run = std::mem::take(&mut points);
+            if run.len() > 1 {
+                runs.push(run);
+            }
+        }
+        if points.len() > 1 {
+            runs.push(points);
+        }
+        runs.into_iter()
+            .map(|points| Run { points, color: self.region.color, width: None })
+            .collect()
+    }
+
+    /// Returns the shading of the region as quads between neighbouring samples, each filled
+    /// wholly as the stroke shader only fades vertices off the middle of a line. Shading outside
+    /// the boundary reaches twice as far as the corner of bounds farthest from the origin, so it
+    /// covers the view however coarse the quads' outer edges are.
+    pub fn shading(&self, bounds: &Bounds) -> Tessellation {
+        let far = [bounds.x_min, bounds.x_max].into_iter()
+            .flat_map(|x| [bounds.y_min, bounds.y_max].map(|y| cgmath::vec2(x, y).magnitude()))
+            .fold(0.0, f32::max) * 2.0;
+        let vertex = |point: cgmath::Vector2<f32>| StrokeVertex { position: [point.x, point.y, 0.0], side: 0.0, distance: 0.0 };
+
+        let mut tessellation = Tessellation::default();
+        for pair in self.samples.windows(2) {
+            let ((theta0, Some(r0)), (theta1, Some(r1))) = (pair[0], pair[1]) else {
+                continue;
+            };
+            let (r0, r1) = (r0.max(0.0), r1.max(0.0));
+            let ((inner0, outer0), (inner1, outer1)) = match self.region.side {
+                PolarSide::Inside => ((0.0, r0), (0.0, r1)),
+                PolarSide::Outside => ((r0, far.max(r0)), (r1, far.max(r1))),
+            };
+
+            // wound anticlockwise, as the back faces are culled
+            let base = tessellation.vertices.len() as u16;
+            tessellation.vertices.extend([
+                polar(theta0, inner0),
+                polar(theta0, outer0),
+                polar(theta1, inner1),
+                polar(theta1, outer1),
+            ].map(vertex));
+            tessellation.indices.extend([base, base + 1, base + 3, base, base + 3, base + 2]);
+        }
+        tessellation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn region(radius: impl Fn(f32) -> Option<f32> + 'static, side: PolarSide) -> SampledRegion {
+        let color = Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+        SampledRegion::new(PolarRegion { radius: Box::new(radius), side, fill: color, color })
+    }
+
+    /// Returns the area covered by a tessellation's triangles, which is negative where they are
+    /// wound clockwise.
+    fn area(tessellation: &Tessellation) -> f32 {
+        tessellation.indices.chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| {
+                    let [x, y, _] = tessellation.vertices[triangle[i] as usize].position;
+                    cgmath::vec2(x, y)
+                });
+                ((b - a).x * (c - a).y - (b - a).y * (c - a).x) / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn the_inside_of_a_circle_is_a_disc() {
+        let bounds = Bounds { x_min: -1.0, x_max: 1.0, y_min: -1.0, y_max: 1.0 };
+        let shading = region(|_| Some(2.0), PolarSide::Inside).shading(&bounds);
+
+        assert_relative_eq!(area(&shading), std::f32::consts::PI * 4.0, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn the_outside_of_a_circle_covers_the_view_around_it() {
+        let bounds = Bounds { x_min: -3.0, x_max: 4.0, y_min: -3.0, y_max: 4.0 };
+        let shading = region(|_| Some(1.0), PolarSide::Outside).shading(&bounds);
+
+        // an annulus out to twice the distance to the corner (4, 4)
+        let far = 2.0 * 32_f32.sqrt();
+        assert_relative_eq!(area(&shading), std::f32::consts::PI * (far * far - 1.0), max_relative = 1e-3);
+    }
+
+    #[test]
+    fn boundaries_break_where_they_are_undefined_or_negative() {
+        // a cardioid's boundary is whole, ending where it starts
+        let cardioid = region(|theta| Some(1.0 + theta.cos()), PolarSide::Inside).boundary();
+        assert_eq!(cardioid.len(), 1);
+        assert_relative_eq!(cardioid[0].points[0].x, 2.0);
+
+        // cos 2θ is negative on two quarters of the turn, leaving three runs, the first and last
+        // meeting at θ = 0
+        let rose = region(|theta| Some((2.0 * theta).cos()), PolarSide::Inside);
+        assert_eq!(rose.boundary().len(), 3);
+
+        let half = region(|theta| (theta < std::f32::consts::PI).then_some(1.0), PolarSide::Inside);
+        assert_eq!(half.boundary().len(), 1);
+        assert!(half.boundary()[0].points.iter().all(|point| point.y >= -1e-6));
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The parts of a frame which are timed, in the order they are drawn.
+pub const SCOPES: [&str; 4] = ["grid", "equations", "points", "text"];
+
+/// Where the readback buffer is in its resolve, copy and map cycle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Readback {
+    Idle,
+    Copied,
+    Mapping,
+}
+
+/// Measures how long each part of the engine's render pass takes on the GPU using timestamp
+/// queries written between the draws.
+///
+/// Results are read back a frame or two late, and frames are skipped while a readback is in
+/// flight, so the timings shown are always slightly stale.
+pub struct GpuProfiler {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    readback: Readback,
+    mapped: Arc<AtomicBool>,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+    timings: Vec<(&'static str, Duration)>,
+}
+
+/// Converts the raw timestamps written at the start of the pass and after each scope into
+/// durations.
+fn durations(timestamps: &[u64], period: f32) -> Vec<(&'static str, Duration)> {
+    SCOPES.iter()
+        .zip(timestamps.windows(2))
+        .map(|(scope, pair)| {
+            // timestamps may go backwards on some drivers, so saturate rather than wrap
+            let ticks = pair[1].saturating_sub(pair[0]);
+            (*scope, Duration::from_nanos((ticks as f64 * period as f64) as u64))
+        })
+        .collect()
+}
+
+impl GpuProfiler {
+    /// Creates a profiler, or returns None if the device can't write timestamps inside passes.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        let features = wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES;
+        if !device.features().contains(features) {
+            log::warn!("GPU profiling requested but timestamp queries are not supported");
+            return None;
+        }
+
+        let count = SCOPES.len() as u32 + 1;
+        let size = count as u64 * wgpu::QUERY_SIZE as u64;
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Profiler Query Set"),
+            ty: wgpu::QueryType::Timestamp,
+            count,
+        });
+
+        let resolve_buffer = device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Profiler Resolve Buffer"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }
+        );
+
+        let readback_buffer = device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Profiler Readback Buffer"),
+                size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }
+        );
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            readback: Readback::Idle,
+            mapped: Arc::new(AtomicBool::new(false)),
+            period: queue.get_timestamp_period(),
+            timings: Vec::new(),
+        })
+    }
+
+    /// Writes the timestamp marking the end of scope, or the start of the pass for None.
+    pub fn timestamp(&self, render_pass: &mut wgpu::RenderPass, scope: Option<usize>) {
+        let index = scope.map_or(0, |scope| scope as u32 + 1);
+        render_pass.write_timestamp(&self.query_set, index);
+    }
+
+    /// Copies this frame's timestamps for reading back, unless a previous readback is in flight.
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if self.readback != Readback::Idle {
+            return;
+        }
+
+        let count = SCOPES.len() as u32 + 1;
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, self.readback_buffer.size());
+        self.readback = Readback::Copied;
+    }
+
+    /// Starts mapping timestamps copied by a submitted frame and collects any that have arrived.
+    pub fn read(&mut self, device: &wgpu::Device) {
+        match self.readback {
+            Readback::Idle => {}
+            Readback::Copied => {
+                let mapped = self.mapped.clone();
+                self.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                    if result.is_ok() {
+                        mapped.store(true, Ordering::Release);
+                    }
+                });
+                self.readback = Readback::Mapping;
+            }
+            Readback::Mapping => {
+                device.poll(wgpu::Maintain::Poll);
+                if self.mapped.swap(false, Ordering::Acquire) {
+                    {
+                        let data = self.readback_buffer.slice(..).get_mapped_range();
+                        self.timings = durations(bytemuck::cast_slice(&data), self.period);
+                    }
+                    self.readback_buffer.unmap();
+                    self.readback = Readback::Idle;
+                }
+            }
+        }
+    }
+
+    pub fn timings(&self) -> &[(&'static str, Duration)] {
+        &self.timings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durations() {
+        let timings = durations(&[100, 150, 400, 400, 390], 2.0);
+
+        assert_eq!(timings, [
+            ("grid", Duration::from_nanos(100)),
+            ("equations", Duration::from_nanos(500)),
+            ("points", Duration::ZERO),
+            ("text", Duration::ZERO),
+        ]);
+    }
+}
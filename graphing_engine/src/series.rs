@@ -0,0 +1,200 @@
+//! Named sets of points, such as columns of imported data, which are drawn and listed together.
+
+use serde::{Deserialize, Serialize};
+
+use crate::box_plot::BoxStats;
+use crate::geometry::{connected_runs, Color, Marker, Point, Run};
+
+/// Width of a bar in world units, leaving a gap between bars one apart.
+const BAR_WIDTH: f32 = 0.8;
+
+/// How a series is drawn.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Chart {
+    /// Each point with the series' marker.
+    #[default]
+    Points,
+    /// The y values summarised as a box and whiskers.
+    BoxPlot,
+    /// A bar from the x axis up or down to each point.
+    Bars,
+    /// A line through the points in order, without markers, as for a sampled curve.
+    Line,
+}
+
+impl Chart {
+    pub const ALL: [Chart; 4] = [Chart::Points, Chart::BoxPlot, Chart::Bars, Chart::Line];
+}
+
+/// Points sharing a colour and marker, shown or hidden and listed in the legend as one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    pub name: String,
+    pub color: Color<f32>,
+    pub marker: Marker,
+    /// Join the points with lines in order, as for a time series.
+    pub connected: bool,
+    pub visible: bool,
+    #[serde(default)]
+    pub chart: Chart,
+    pub points: Vec<cgmath::Vector2<f32>>,
+    /// The name of each point's category, labelled along the x axis below it. Empty where the x
+    /// values are numbers.
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+/// Text labelling a category along the x axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryLabel {
+    pub x: f32,
+    pub text: String,
+}
+
+/// What is drawn for the data series.
+#[derive(Default)]
+pub struct Shapes {
+    pub points: Vec<Point>,
+    pub runs: Vec<Run>,
+    pub labels: Vec<CategoryLabel>,
+}
+
+impl Series {
+    /// Returns the points to draw in the series' style, or none while it is hidden or drawn as a
+    /// chart.
+    pub fn drawn_points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.points.iter()
+            .filter(|_| self.visible && self.chart == Chart::Points)
+            .enumerate()
+            .map(|(i, &position)| Point {
+                color: self.color,
+                marker: self.marker,
+                // the first point starts the series rather than joining on to the one before
+                connected: self.connected && i > 0,
+                ..Point::new(position)
+            })
+    }
+}
+
+/// Returns the points, lines and labels drawing every series. Series drawn as box plots are
+/// placed at x = 1, 2 and so on in the order they are listed, keeping their places while hidden,
+/// and labelled with their names.
+pub fn shapes(series: &[Series]) -> Shapes {
+    let mut shapes = Shapes {
+        points: series.iter().flat_map(Series::drawn_points).collect(),
+        ..Shapes::default()
+    };
+
+    let boxes = series.iter().filter(|series| series.chart == Chart::BoxPlot).enumerate();
+    for (category, series) in boxes.filter(|(_, series)| series.visible) {
+        let Some(stats) = BoxStats::new(series.points.iter().map(|point| point.y)) else {
+            continue;
+        };
+        let x = (category + 1) as f32;
+        let (lines, outliers) = stats.shapes(x, series.color, series.marker);
+        shapes.runs.extend(lines);
+        shapes.points.extend(outliers);
+        shapes.labels.push(CategoryLabel { x, text: series.name.trim().to_owned() });
+    }
+
+    for series in series.iter().filter(|series| series.visible && series.chart == Chart::Bars) {
+        shapes.runs.extend(series.points.iter().map(|point| Run {
+            points: vec![cgmath::vec2(point.x, 0.0), *point],
+            color: series.color,
+            width: Some(BAR_WIDTH),
+        }));
+    }
+
+    for series in series.iter().filter(|series| series.visible && series.chart == Chart::Line) {
+        let points = series.points.iter()
+            .map(|&position| Point { color: series.color, connected: true, ..Point::new(position) })
+            .collect::<Vec<_>>();
+        shapes.runs.extend(connected_runs(&points));
+    }
+
+    // categories are labelled once each, however many series share them
+    let categorised = series.iter().filter(|series| series.visible && series.chart != Chart::BoxPlot);
+    for series in categorised {
+        for (point, text) in series.points.iter().zip(&series.categories) {
+            if !shapes.labels.iter().any(|label| label.x == point.x) {
+                shapes.labels.push(CategoryLabel { x: point.x, text: text.clone() });
+            }
+        }
+    }
+
+    shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(visible: bool) -> Series {
+        Series {
+            name: "height".to_owned(),
+            color: Color { r: 1.0, g: 0.0, b: 0.0, a: 1.0 },
+            marker: Marker::Square,
+            connected: true,
+            visible,
+            chart: Chart::Points,
+            points: vec![cgmath::vec2(0.0, 1.0), cgmath::vec2(1.0, 2.0)],
+            categories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn points_take_the_style_of_the_series() {
+        let points = series(true).drawn_points().collect::<Vec<_>>();
+
+        assert_eq!(points.len(), 2);
+        assert!(points.iter().all(|point| point.marker == Marker::Square && point.color.r == 1.0));
+        assert_eq!(points.iter().map(|point| point.connected).collect::<Vec<_>>(), [false, true]);
+    }
+
+    #[test]
+    fn hidden_series_draw_nothing() {
+        assert_eq!(series(false).drawn_points().count(), 0);
+    }
+
+    #[test]
+    fn box_plots_take_the_next_category() {
+        let boxed = |visible| Series { chart: Chart::BoxPlot, ..series(visible) };
+
+        let shapes = shapes(&[boxed(false), series(true), boxed(true)]);
+
+        // only the plain series' points are drawn, and the hidden box keeps its place at x = 1
+        assert_eq!(shapes.points.len(), 2);
+        assert!(!shapes.runs.is_empty());
+        assert!(shapes.runs.iter().flat_map(|run| &run.points).all(|point| (point.x - 2.0).abs() < 0.5));
+        assert_eq!(shapes.labels, [CategoryLabel { x: 2.0, text: "height".to_owned() }]);
+    }
+
+    #[test]
+    fn lines_draw_no_markers() {
+        let line = Series { chart: Chart::Line, ..series(true) };
+
+        let shapes = shapes(&[line]);
+
+        assert!(shapes.points.is_empty());
+        assert_eq!(shapes.runs.len(), 1);
+        assert_eq!(shapes.runs[0].points.len(), 2);
+    }
+
+    #[test]
+    fn bars_rise_from_the_axis_with_their_categories_below() {
+        let bars = Series {
+            chart: Chart::Bars,
+            points: vec![cgmath::vec2(1.0, 3.0), cgmath::vec2(2.0, -1.0)],
+            categories: vec!["apples".to_owned(), "pears".to_owned()],
+            ..series(true)
+        };
+
+        let shapes = shapes(&[bars]);
+
+        assert!(shapes.points.is_empty());
+        assert_eq!(shapes.runs.len(), 2);
+        assert_eq!(shapes.runs[1].points, [cgmath::vec2(2.0, 0.0), cgmath::vec2(2.0, -1.0)]);
+        assert_eq!(shapes.runs[0].width, Some(BAR_WIDTH));
+        assert_eq!(shapes.labels.iter().map(|label| label.text.as_str()).collect::<Vec<_>>(), ["apples", "pears"]);
+    }
+}
@@ -0,0 +1,54 @@
+//! Snapping dragged points to where gridlines cross or on to curves.
+
+use serde::{Deserialize, Serialize};
+
+/// Distance in logical pixels within which points snap by default.
+pub const DEFAULT_SNAP_RADIUS: f32 = 10.0;
+
+/// What dragged points are drawn to.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapTarget {
+    #[default]
+    Off,
+    /// Where the minor gridlines cross.
+    Grid,
+    /// The nearest point on a visible curve.
+    Curve,
+}
+
+/// How dragged points snap.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snap {
+    pub target: SnapTarget,
+    /// How close in logical pixels a point has to be to its target to snap to it.
+    pub radius: f32,
+}
+
+impl Default for Snap {
+    fn default() -> Self {
+        Self {
+            target: SnapTarget::Off,
+            radius: DEFAULT_SNAP_RADIUS,
+        }
+    }
+}
+
+/// Returns the crossing of gridlines spaced step apart along each axis closest to p.
+pub fn grid_crossing(p: cgmath::Vector2<f32>, step: cgmath::Vector2<f32>) -> cgmath::Vector2<f32> {
+    cgmath::vec2((p.x / step.x).round() * step.x, (p.y / step.y).round() * step.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossings_are_nearest_on_each_axis() {
+        use approx::relative_eq;
+
+        let crossing = grid_crossing(cgmath::vec2(0.74, -1.35), cgmath::vec2(0.5, 0.2));
+
+        assert!(relative_eq!(crossing.x, 0.5));
+        assert!(relative_eq!(crossing.y, -1.4));
+    }
+}
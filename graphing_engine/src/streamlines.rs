@@ -0,0 +1,188 @@
+//! Streamlines of a vector field, traced across the view and spaced evenly apart by seeding them
+//! only where no other line passes close by.
+
+use std::collections::HashMap;
+
+use cgmath::InnerSpace;
+
+use crate::camera::Bounds;
+use crate::geometry::Color;
+
+/// Steps taken along a streamline for each separation between lines.
+const STEPS_PER_SEPARATION: f32 = 4.0;
+/// Most steps in each direction from a seed, which stops lines circling a closed orbit forever.
+const MAX_STEPS: usize = 1000;
+/// Fraction of the separation a line may come to another before it stops, so lines can draw
+/// together where the field converges without merging.
+const STOP_FRACTION: f32 = 0.5;
+
+/// A vector field drawn as streamlines across the view, traced again whenever the view changes.
+pub struct VectorField {
+    /// The field's value at a point, or None where it isn't defined.
+    pub field: Box<dyn Fn(cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>>>,
+    /// Distance between neighbouring streamlines in logical pixels.
+    pub separation: f32,
+    pub color: Color<f32>,
+}
+
+/// Points of the streamlines traced so far, bucketed by cells as wide as the separation, so
+/// only neighbouring cells need searching for points nearby.
+struct Occupancy {
+    cell: f32,
+    cells: HashMap<(i32, i32), Vec<cgmath::Vector2<f32>>>,
+}
+
+impl Occupancy {
+    fn key(&self, point: cgmath::Vector2<f32>) -> (i32, i32) {
+        ((point.x / self.cell).floor() as i32, (point.y / self.cell).floor() as i32)
+    }
+
+    /// Returns whether a traced point is within distance of point, which must be at most the
+    /// cell size.
+    fn is_near(&self, point: cgmath::Vector2<f32>, distance: f32) -> bool {
+        let (x, y) = self.key(point);
+        (x - 1..=x + 1)
+            .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .any(|other| (*other - point).magnitude2() < distance * distance)
+    }
+
+    fn add(&mut self, points: &[cgmath::Vector2<f32>]) {
+        for &point in points {
+            self.cells.entry(self.key(point)).or_default().push(point);
+        }
+    }
+}
+
+fn contains(bounds: &Bounds, point: cgmath::Vector2<f32>) -> bool {
+    (bounds.x_min..=bounds.x_max).contains(&point.x) && (bounds.y_min..=bounds.y_max).contains(&point.y)
+}
+
+/// Returns the direction of the field at point, or None where it is undefined or vanishes.
+fn direction(field: &dyn Fn(cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>>, point: cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>> {
+    field(point)
+        .filter(|value| value.x.is_finite() && value.y.is_finite() && value.magnitude2() > f32::EPSILON)
+        .map(|value| value.normalize())
+}
+
+/// Follows the field from seed, backwards if step is negative, until the line leaves bounds,
+/// reaches a point where the field vanishes, comes too close to another line or closes on
+/// itself. Returns the points after the seed, and whether the line closed.
+fn follow(
+    field: &dyn Fn(cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>>,
+    seed: cgmath::Vector2<f32>,
+    step: f32,
+    bounds: &Bounds,
+    occupancy: &Occupancy,
+    stop_distance: f32,
+) -> (Vec<cgmath::Vector2<f32>>, bool) {
+    let mut points = Vec::new();
+    let mut point = seed;
+    for i in 0..MAX_STEPS {
+        // the midpoint method, with directions only so the steps are evenly spaced
+        let Some(start) = direction(field, point) else { break };
+        let Some(middle) = direction(field, point + start * step / 2.0) else { break };
+        point += middle * step;
+        if i > 2 && (point - seed).magnitude() < step.abs() {
+            return (points, true);
+        }
+        if !contains(bounds, point) || occupancy.is_near(point, stop_distance) {
+            break;
+        }
+        points.push(point);
+    }
+    (points, false)
+}
+
+/// Returns streamlines of field across bounds, about separation apart in world units.
+///
+/// Seeds are tried on a grid of the separation, and a line is only started from one which isn't
+/// already near a line, then traced both ways from it.
+pub fn trace(
+    field: &dyn Fn(cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>>,
+    bounds: Bounds,
+    separation: f32,
+) -> Vec<Vec<cgmath::Vector2<f32>>> {
+    let width = bounds.x_max - bounds.x_min;
+    let height = bounds.y_max - bounds.y_min;
+    if !(separation > 0.0 && width > 0.0 && height > 0.0) {
+        return Vec::new();
+    }
+    let step = separation / STEPS_PER_SEPARATION;
+    let columns = (width / separation).ceil() as usize;
+    let rows = (height / separation).ceil() as usize;
+
+    let mut occupancy = Occupancy { cell: separation, cells: HashMap::new() };
+    let mut lines = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            let seed = cgmath::vec2(
+                bounds.x_min + (column as f32 + 0.5) * separation,
+                bounds.y_min + (row as f32 + 0.5) * separation,
+            );
+            if occupancy.is_near(seed, separation) || direction(field, seed).is_none() {
+                continue;
+            }
+            let stop_distance = separation * STOP_FRACTION;
+            let (mut forwards, closed) = follow(field, seed, step, &bounds, &occupancy, stop_distance);
+            // a closed orbit is already whole, and is drawn back to where it started
+            let backwards = if closed {
+                forwards.push(seed);
+                Vec::new()
+            } else {
+                follow(field, seed, -step, &bounds, &occupancy, stop_distance).0
+            };
+            let line = backwards.into_iter().rev()
+                .chain(std::iter::once(seed))
+                .chain(forwards)
+                .collect::<Vec<_>>();
+            if line.len() > 1 {
+                occupancy.add(&line);
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Bounds = Bounds { x_min: -1.0, x_max: 1.0, y_min: -1.0, y_max: 1.0 };
+
+    #[test]
+    fn uniform_fields_give_evenly_spaced_straight_lines() {
+        let lines = trace(&|_| Some(cgmath::vec2(1.0, 0.0)), BOUNDS, 0.25);
+
+        // one line along each row of seeds, as every later seed on the row is on it
+        assert_eq!(lines.len(), 8);
+        for line in &lines {
+            assert!(line.iter().all(|point| point.y == line[0].y));
+            assert!(line.windows(2).all(|pair| pair[1].x > pair[0].x));
+        }
+    }
+
+    #[test]
+    fn lines_stop_where_the_field_vanishes() {
+        let lines = trace(&|point| (point.x > 0.0).then_some(cgmath::vec2(0.0, 1.0)), BOUNDS, 0.5);
+
+        assert!(lines.iter().flatten().all(|point| point.x > 0.0));
+    }
+
+    #[test]
+    fn lines_keep_apart() {
+        // circles about the origin
+        let lines = trace(&|point| Some(cgmath::vec2(-point.y, point.x)), BOUNDS, 0.2);
+
+        for (i, line) in lines.iter().enumerate() {
+            for other in &lines[i + 1..] {
+                let closest = line.iter()
+                    .flat_map(|a| other.iter().map(move |b| (*a - *b).magnitude()))
+                    .fold(f32::INFINITY, f32::min);
+                assert!(closest >= 0.2 * STOP_FRACTION * 0.9, "lines {closest} apart");
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Stroke geometry for curves and polylines, built on the CPU without any GPU resources so it can
+//! run on another thread and be benchmarked on its own.
+
+use crate::geometry::StrokeVertex;
+
+/// Returns two vertices a certain distance from a point that can be used to form a line.
+///
+/// Takes four inputs: the first point, the second point, the width of the square, and a bool
+/// specifying whether initial or not. initial refers to whether the square points of p1 are wanted
+/// or not. This is useful for beginning a line with the initial start point.
+///
+/// This function always needs the two points the line segment will be between, but only returns
+/// two of the four vertices needed to avoid repeated vertices on lines.
+fn square_points(
+    p1: cgmath::Vector2<f32>,
+    p2: cgmath::Vector2<f32>,
+    width: f32,
+    distance: f32,
+    initial: bool,
+) -> Vec<StrokeVertex> {
+    let theta = f32::atan2(p1.x - p2.x, p1.y - p2.y);
+    let delta_x = f32::cos(theta) * width;
+    let delta_y = f32::sin(theta) * width;
+
+    if initial {
+        vec![
+            StrokeVertex { position: [p1.x + delta_x, p1.y - delta_y, 0.0], side: 1.0, distance },
+            StrokeVertex { position: [p1.x - delta_x, p1.y + delta_y, 0.0], side: -1.0, distance },
+        ]
+    } else {
+        vec![
+            StrokeVertex { position: [p2.x + delta_x, p2.y - delta_y, 0.0], side: 1.0, distance },
+            StrokeVertex { position: [p2.x - delta_x, p2.y + delta_y, 0.0], side: -1.0, distance },
+        ]
+    }
+}
+
+/// Returns the corresponding y value to the x value for a polynomial equation.
+///
+/// Takes x as an input, as well as a list of coefficients ordered from the smallest order to the
+/// largest, including x^0.
+pub fn polynomial_equation(x: f32, coeffs: &[f32]) -> f32 {
+    coeffs.iter().enumerate()
+        .map(|(i, coeff)| coeff * x.powi(i as i32))
+        .sum::<f32>()
+}
+
+/// Returns n evenly spaced samples of a polynomial from x_min to x_max inclusive, evaluated in
+/// double precision. A single sample is taken at x_min.
+pub fn sample_evenly(coeffs: &[f32], x_min: f64, x_max: f64, n: usize) -> Vec<(f64, f64)> {
+    let step = if n > 1 { (x_max - x_min) / (n - 1) as f64 } else { 0.0 };
+    (0..n)
+        .map(|i| {
+            let x = x_min + step * i as f64;
+            let y = coeffs.iter().rev().fold(0.0, |y, &coeff| y * x + coeff as f64);
+            (x, y)
+        })
+        .collect()
+}
+
+/// Number of samples taken per pixel across the visible x range.
+const SAMPLES_PER_PIXEL: f32 = 2.0;
+/// Segments shorter than this many pixels are merged into the following segment.
+const MIN_SEGMENT_PIXELS: f32 = 1.0;
+/// Upper bound on samples so the stroke vertices can always be indexed with u16.
+pub(crate) const MAX_SAMPLES: usize = (u16::MAX as usize - 2) / 2;
+
+/// Returns the points of a polyline following a polynomial between x_min and x_max.
+///
+/// The polynomial is sampled a fixed number of times per pixel, and samples closer than
+/// MIN_SEGMENT_PIXELS to the previous point are dropped, so the number of points depends on the
+/// length of the curve on screen rather than the zoom level.
+pub fn sample_polynomial(coeffs: &[f32], x_min: f32, x_max: f32, world_per_pixel: f32) -> Vec<cgmath::Vector2<f32>> {
+    use cgmath::InnerSpace;
+
+    let samples = (((x_max - x_min) / world_per_pixel * SAMPLES_PER_PIXEL).ceil() as usize).clamp(1, MAX_SAMPLES);
+    let step = (x_max - x_min) / samples as f32;
+    let min_length = MIN_SEGMENT_PIXELS * world_per_pixel;
+
+    let mut points: Vec<cgmath::Vector2<f32>> = Vec::new();
+    for i in 0..=samples {
+        let x = x_min + i as f32 * step;
+        let point = cgmath::vec2(x, polynomial_equation(x, coeffs));
+
+        // always keep the final sample so the curve reaches the edge of the view
+        let too_short = points.last().is_some_and(|last| (point - last).magnitude() < min_length);
+        if i == samples || !too_short {
+            points.push(point);
+        }
+    }
+    points
+}
+
+/// Stroke geometry for a line: two vertices either side of each point and two triangles joining
+/// each pair of points.
+#[derive(Default)]
+pub struct Tessellation {
+    pub vertices: Vec<StrokeVertex>,
+    pub indices: Vec<u16>,
+}
+
+impl Tessellation {
+    /// Strokes a polynomial between x_min and x_max, sampled finely enough for world_per_pixel.
+    /// No coefficients give an empty tessellation.
+    pub fn polynomial(coeffs: &[f32], x_min: f32, x_max: f32, world_per_pixel: f32, width: f32) -> Self {
+        let _span = tracing::debug_span!("tessellate_polynomial", degree = coeffs.len().saturating_sub(1)).entered();
+        if coeffs.is_empty() {
+            return Self::default();
+        }
+
+        Self::polyline(&sample_polynomial(coeffs, x_min, x_max, world_per_pixel), world_per_pixel, width)
+    }
+
+    /// Strokes a polyline through points in order, width world units either side of it.
+    pub fn polyline(points: &[cgmath::Vector2<f32>], world_per_pixel: f32, width: f32) -> Self {
+        use cgmath::InnerSpace;
+
+        let _span = tracing::trace_span!("tessellate_polyline", points = points.len()).entered();
+
+        let mut tessellation = Self::default();
+        let mut distance = 0.0;
+
+        for (i, pair) in points.windows(2).enumerate() {
+            let (p1, p2) = (pair[0], pair[1]);
+
+            if i == 0 {
+                tessellation.vertices.append(&mut square_points(p1, p2, width, distance, true));
+            }
+
+            distance += (p2 - p1).magnitude() / world_per_pixel;
+            tessellation.next(i as u16 * 2, p1, p2, width, distance);
+        }
+        tessellation
+    }
+
+    fn next(&mut self, offset: u16, p1: cgmath::Vector2<f32>, p2: cgmath::Vector2<f32>, width: f32, distance: f32) {
+        self.vertices.append(&mut square_points(p1, p2, width, distance, false));
+        self.indices.append(&mut [
+            offset, offset+1, offset+3,
+            offset+2, offset, offset+3,
+        ].to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_are_evenly_spaced_and_include_both_ends() {
+        let samples = sample_evenly(&[-1.0, 0.0, 1.0], -2.0, 2.0, 5);
+
+        assert_eq!(samples, vec![(-2.0, 3.0), (-1.0, 0.0), (0.0, -1.0), (1.0, 0.0), (2.0, 3.0)]);
+        assert_eq!(sample_evenly(&[1.0, 1.0], 3.0, 5.0, 1), vec![(3.0, 4.0)]);
+        assert!(sample_evenly(&[1.0], 0.0, 1.0, 0).is_empty());
+    }
+
+    #[test]
+    fn stroke_distance_follows_the_curve() {
+        use approx::relative_eq;
+
+        // y = x from 0 to 3 is 3√2 long, which at half a world unit per pixel is 6√2 pixels
+        let tessellation = Tessellation::polynomial(&[0.0, 1.0], 0.0, 3.0, 0.5, 0.1);
+        let distances = tessellation.vertices.iter().map(|vertex| vertex.distance).collect::<Vec<_>>();
+
+        assert_eq!(distances[0], 0.0);
+        assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(relative_eq!(*distances.last().unwrap(), 6.0 * 2_f32.sqrt(), max_relative = 1e-4));
+    }
+
+    #[test]
+    fn test_polynomial_equation() {
+        let coeffs = &[];
+        assert_eq!(polynomial_equation(2.0, coeffs), 0.0);
+        let coeffs = &[-1.0, 3.0, 4.0, 1.0];
+        assert_eq!(polynomial_equation(2.0, coeffs), 29.0);
+        let coeffs = &[0.0, 1.0];
+        assert_eq!(polynomial_equation(2.0, coeffs), 2.0);
+    }
+
+    #[test]
+    fn sample_polynomial_bounded_at_any_zoom() {
+        // a 1000 pixel wide view of y = x at very different zoom levels
+        for world_per_pixel in [0.001, 1.0, 1000.0] {
+            let half_width = 500.0 * world_per_pixel;
+            let points = sample_polynomial(&[0.0, 1.0], -half_width, half_width, world_per_pixel);
+
+            // the line is about 1414 pixels long on screen
+            assert!(points.len() > 2);
+            assert!(points.len() <= 1416);
+            assert_eq!(points.last().unwrap().x, half_width);
+        }
+    }
+
+    #[test]
+    fn sample_polynomial_merges_short_segments() {
+        // a constant sampled every half pixel only keeps a point every pixel
+        let points = sample_polynomial(&[3.0], 0.0, 100.0, 1.0);
+
+        assert_eq!(points.len(), 101);
+        assert!(points.iter().all(|point| point.y == 3.0));
+    }
+
+    #[test]
+    fn polylines_stroke_every_segment() {
+        let points = [cgmath::vec2(0.0, 0.0), cgmath::vec2(1.0, 0.0), cgmath::vec2(1.0, 1.0)];
+
+        let tessellation = Tessellation::polyline(&points, 0.1, 0.05);
+
+        // two vertices per point and two triangles per segment
+        assert_eq!(tessellation.vertices.len(), 6);
+        assert_eq!(tessellation.indices.len(), 12);
+        assert!(Tessellation::polyline(&points[..1], 0.1, 0.05).indices.is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(feature = "text")]
+use std::borrow::Cow;
+
+#[cfg(feature = "text")]
+use crate::geometry::Anchor;
+
+#[cfg(not(feature = "text"))]
+mod disabled;
+#[cfg(feature = "text")]
+mod glyphs;
+
+#[cfg(not(feature = "text"))]
+pub use disabled::GridText;
+#[cfg(feature = "text")]
+pub use glyphs::GridText;
+
+/// A point's annotation to draw, in physical pixels.
+#[cfg(feature = "text")]
+pub struct PlacedAnnotation<'a> {
+    pub text: Cow<'a, str>,
+    /// The centre of the point on screen.
+    pub point: cgmath::Vector2<f32>,
+    /// How far the text keeps from the centre of the point along each axis.
+    pub clearance: f32,
+    pub anchor: Anchor,
+    pub offset: cgmath::Vector2<f32>,
+}
+
+/// Titles drawn around the edges of the graph. Empty titles are left out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Titles {
+    /// Drawn centred along the top edge.
+    pub title: String,
+    /// Drawn centred along the bottom edge.
+    pub x_axis: String,
+    /// Drawn centred along the left edge.
+    pub y_axis: String,
+}
+
+/// Default label font size in logical pixels, scaled by the window scale factor.
+pub const TEXT_SIZE: f32 = 14.0;
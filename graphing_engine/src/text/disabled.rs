@@ -0,0 +1,60 @@
+use crate::camera;
+use crate::geometry::Color;
+use crate::pipeline::GridPipeline;
+use crate::RenderTargetConfig;
+
+use super::{Titles, TEXT_SIZE};
+
+/// Stands in for the text renderer when the engine is built without the `text` feature. Nothing
+/// is drawn, but the text size is kept so the legend's frame is still laid out.
+pub struct GridText {
+    pub text_size: f32,
+    /// Label font size in logical pixels, before the scale factor is applied.
+    font_size: f32,
+    scale_factor: f32,
+    pub legend_origin: Option<cgmath::Vector2<f32>>,
+}
+
+impl GridText {
+    pub fn new(_device: &wgpu::Device, _queue: &wgpu::Queue, _target: &RenderTargetConfig, scale_factor: f32) -> Self {
+        Self {
+            text_size: TEXT_SIZE * scale_factor,
+            font_size: TEXT_SIZE,
+            scale_factor,
+            legend_origin: None,
+        }
+    }
+
+    pub fn prepare(&mut self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        _size: dpi::PhysicalSize<u32>,
+        _camera: &camera::Camera,
+        _grid: &GridPipeline,
+        _color: Color<f32>,
+    ) {}
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+        self.text_size = self.font_size * self.scale_factor;
+    }
+
+    pub fn set_font_size(&mut self, font_size: f32) {
+        self.font_size = font_size;
+        self.text_size = self.font_size * self.scale_factor;
+    }
+
+    pub fn set_legend_names<'a>(&mut self, _names: impl Iterator<Item = &'a str>) {}
+
+    pub fn legend_width(&self) -> f32 {
+        0.0
+    }
+
+    pub fn set_titles(&mut self, _titles: &Titles) {}
+
+    pub fn resize(&mut self, _queue: &wgpu::Queue, _size: dpi::PhysicalSize<u32>) {}
+
+    pub fn render<'pass>(&'pass self, _render_pass: &mut wgpu::RenderPass<'pass>) {}
+
+    pub fn trim(&mut self) {}
+}
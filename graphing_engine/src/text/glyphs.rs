@@ -0,0 +1,466 @@
+use crate::camera;
+use crate::geometry::{Anchor, Color};
+use crate::pipeline::GridPipeline;
+use crate::RenderTargetConfig;
+
+use super::{PlacedAnnotation, Titles, TEXT_SIZE};
+
+/// Draws the axis labels, titles, legend names and point annotations with glyphon.
+pub struct GridText {
+    pub font_system: glyphon::FontSystem,
+    pub swash_cache: glyphon::SwashCache,
+    pub viewport: glyphon::Viewport,
+    pub atlas: glyphon::TextAtlas,
+    pub text_renderer: glyphon::TextRenderer,
+    pub x_text_buffer: glyphon::Buffer,
+    pub y_text_buffer: glyphon::Buffer,
+    pub text_size: f32,
+    pub spacing: f32,
+    /// Label font size in logical pixels, before the scale factor is applied.
+    font_size: f32,
+    scale_factor: f32,
+    title_buffer: glyphon::Buffer,
+    x_title_buffer: glyphon::Buffer,
+    y_title_buffer: glyphon::Buffer,
+    x_end_buffer: glyphon::Buffer,
+    y_end_buffer: glyphon::Buffer,
+    origin_buffer: glyphon::Buffer,
+    /// The numbers of the quadrants, from I to IV.
+    quadrant_buffers: [glyphon::Buffer; 4],
+    /// The legend's names, one per line.
+    legend_buffer: glyphon::Buffer,
+    /// Top left of the legend's names, or None if there is no legend.
+    pub legend_origin: Option<cgmath::Vector2<f32>>,
+    point_labels: Vec<PointLabel>,
+}
+
+/// The label of a point, laid out in its own buffer.
+struct PointLabel {
+    text: String,
+    buffer: glyphon::Buffer,
+    /// Top left of the text in physical pixels.
+    pos: cgmath::Vector2<f32>,
+}
+
+/// Size of the plot title relative to the labels. Axis titles are the same size as the labels.
+const TITLE_SCALE: f32 = 1.25;
+
+/// Returns where to put something extent pixels long starting at pos, so that it stays within
+/// a viewport of length size.
+fn clamp_to_edges(pos: f32, extent: f32, size: f32) -> f32 {
+    pos.clamp(0.0, (size - extent).max(0.0))
+}
+
+/// Converts a colour to the 8 bit form glyphon draws text in.
+fn glyph_color(color: Color<f32>) -> glyphon::Color {
+    let [r, g, b, a] = color.to_raw().map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    glyphon::Color::rgba(r, g, b, a)
+}
+
+/// Returns the top left of text of size placed on the anchor's side of point, clearance away
+/// from it. Text is centred on the point along any axis the anchor doesn't lean along.
+fn anchored_top_left(
+    anchor: Anchor,
+    point: cgmath::Vector2<f32>,
+    clearance: f32,
+    size: cgmath::Vector2<f32>,
+) -> cgmath::Vector2<f32> {
+    let direction = anchor.direction();
+    cgmath::vec2(
+        point.x + direction.x * clearance - size.x * (1.0 - direction.x) / 2.0,
+        point.y + direction.y * clearance - size.y * (1.0 - direction.y) / 2.0,
+    )
+}
+
+/// Returns the width of the widest line laid out in buffer.
+fn buffer_width(buffer: &glyphon::Buffer) -> f32 {
+    buffer.layout_runs().map(|run| run.line_w).fold(0.0, f32::max)
+}
+
+impl GridText {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, target: &RenderTargetConfig, scale_factor: f32) -> Self {
+        let mut font_system = glyphon::FontSystem::new();
+        let swash_cache = glyphon::SwashCache::new();
+        let cache = glyphon::Cache::new(device);
+        let viewport = glyphon::Viewport::new(device, &cache);
+
+        let mut atlas = glyphon::TextAtlas::new(device, queue, &cache, target.format);
+        let text_renderer = glyphon::TextRenderer::new(&mut atlas, device, target.multisample_state(), None);
+        let text_size = TEXT_SIZE * scale_factor;
+        let spacing = text_size;
+        let mut x_text_buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(text_size, spacing));
+        let mut y_text_buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(text_size, spacing));
+
+        // the buffers hold one label per line, so they are left unbounded rather than sized to the window
+        x_text_buffer.set_size(&mut font_system, None, None);
+        y_text_buffer.set_size(&mut font_system, None, None);
+
+        x_text_buffer.shape_until_scroll(&mut font_system, false);
+        y_text_buffer.shape_until_scroll(&mut font_system, false);
+
+        let title_size = text_size * TITLE_SCALE;
+        let mut title_buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(title_size, title_size));
+        let mut x_title_buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(text_size, spacing));
+        let mut y_title_buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(text_size, spacing));
+        for buffer in [&mut title_buffer, &mut x_title_buffer, &mut y_title_buffer] {
+            buffer.set_size(&mut font_system, None, None);
+        }
+
+        let attrs = glyphon::Attrs::new().family(glyphon::Family::Monospace);
+        let mut marker_buffer = |text| {
+            let mut buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(text_size, spacing));
+            buffer.set_size(&mut font_system, None, None);
+            buffer.set_text(&mut font_system, text, attrs, glyphon::Shaping::Advanced);
+            buffer
+        };
+        let [x_end_buffer, y_end_buffer, origin_buffer] = ["x", "y", "0"].map(&mut marker_buffer);
+        let quadrant_buffers = ["I", "II", "III", "IV"].map(&mut marker_buffer);
+        let mut legend_buffer = glyphon::Buffer::new(&mut font_system, glyphon::Metrics::new(text_size, spacing));
+        legend_buffer.set_size(&mut font_system, None, None);
+
+        Self {
+            font_system,
+            swash_cache,
+            viewport,
+            atlas,
+            text_renderer,
+            x_text_buffer,
+            y_text_buffer,
+            text_size,
+            spacing,
+            font_size: TEXT_SIZE,
+            scale_factor,
+            title_buffer,
+            x_title_buffer,
+            y_title_buffer,
+            x_end_buffer,
+            y_end_buffer,
+            origin_buffer,
+            quadrant_buffers,
+            legend_buffer,
+            legend_origin: None,
+            point_labels: Vec::new(),
+        }
+    }
+
+    pub fn prepare(&mut self,
+        device: &wgpu::Device, 
+        queue: &wgpu::Queue, 
+        size: dpi::PhysicalSize<u32>, 
+        camera: &camera::Camera, 
+        grid: &GridPipeline,
+        color: Color<f32>,
+    ) {
+        let GridPipeline { vertical_labels, horizontal_labels, markers, .. } = grid;
+        let color = glyph_color(color);
+
+        let mut y_text: String = "".to_owned();
+        for label in horizontal_labels {
+            y_text.push_str(&label.text);
+            y_text.push('\n');
+        }
+        let mut x_text: String = "".to_owned();
+        for label in vertical_labels {
+            x_text.push_str(&label.text);
+            x_text.push('\n');
+        }
+
+        let attrs = glyphon::Attrs::new()
+            .family(glyphon::Family::Monospace);
+
+        self.x_text_buffer.set_text(&mut self.font_system, x_text.as_str(), attrs, glyphon::Shaping::Advanced);
+        self.y_text_buffer.set_text(&mut self.font_system, y_text.as_str(), attrs, glyphon::Shaping::Advanced);
+
+        // labels follow the axes, but stay pinned to the nearest edge once an axis leaves the view
+        let origin = camera.world_to_screen_space(cgmath::vec3(0.0, 0.0, 0.0), size);
+        let axis_pos = cgmath::vec2(
+            clamp_to_edges(origin.x, buffer_width(&self.y_text_buffer), size.width as f32),
+            clamp_to_edges(origin.y, self.text_size, size.height as f32),
+        );
+        let position_offset = self.text_size / 2.0;
+
+        let mut text_areas: Vec<glyphon::TextArea> = vec![];
+        for (i, instance) in vertical_labels.iter().enumerate() {
+            let text_pos = camera.offset_to_screen_space(instance.offset, size);
+
+            let bound_offset = i as f32 * self.spacing;
+
+            let text_area = glyphon::TextArea {
+                buffer: &self.x_text_buffer,
+                left: text_pos.x - position_offset,
+                top:  axis_pos.y - bound_offset,
+                scale: 1.0,
+                bounds: glyphon::TextBounds {
+                    left: (text_pos.x - position_offset) as i32,
+                    top: axis_pos.y as i32,
+                    right: size.width as i32,
+                    bottom: (axis_pos.y + self.text_size) as i32,
+                },
+                default_color: color,
+                custom_glyphs: &[],
+            };
+            text_areas.push(text_area);
+        }
+        for (i, instance) in horizontal_labels.iter().enumerate() {
+            let text_pos = camera.offset_to_screen_space(instance.offset, size);
+
+            let bound_offset = i as f32 * self.spacing;
+
+            let text_area = glyphon::TextArea {
+                buffer: &self.y_text_buffer,
+                left: axis_pos.x,
+                top: text_pos.y - bound_offset - position_offset,
+                scale: 1.0,
+                bounds: glyphon::TextBounds {
+                    left: axis_pos.x as i32,
+                    top: (text_pos.y - position_offset) as i32,
+                    right: size.width as i32,
+                    bottom: (text_pos.y + self.text_size - position_offset) as i32,
+                },
+                default_color: color,
+                custom_glyphs: &[],
+            };
+            text_areas.push(text_area);
+
+            // avoid doubling up the origin label
+            // origin label disabled so code disabled, remove above text_areas.push if using
+            /*
+            if instance.position.y != 0.0 {
+                text_areas.push(text_area);
+            }
+            */
+        }
+
+        // titles sit a margin in from the edges of the viewport, centred along them
+        let margin = self.text_size / 2.0;
+        let (width, height) = (size.width as f32, size.height as f32);
+        let bounds = glyphon::TextBounds {
+            left: 0,
+            top: 0,
+            right: size.width as i32,
+            bottom: size.height as i32,
+        };
+        let titles = [
+            (&self.title_buffer, (width - buffer_width(&self.title_buffer)) / 2.0, margin),
+            (&self.x_title_buffer, (width - buffer_width(&self.x_title_buffer)) / 2.0, height - margin - self.spacing),
+            (&self.y_title_buffer, margin, (height - self.spacing) / 2.0),
+        ];
+        for (buffer, left, top) in titles {
+            text_areas.push(glyphon::TextArea {
+                buffer,
+                left,
+                top,
+                scale: 1.0,
+                bounds,
+                default_color: color,
+                custom_glyphs: &[],
+            });
+        }
+
+        // markers end just left of their point. x sits above the end of its axis, clear of the tick
+        // labels below it, and y and the origin sit left of the y axis, clear of the tick labels
+        // to its right
+        let gap = self.text_size / 4.0;
+        let markers = [
+            (&self.x_end_buffer, markers.x_end, cgmath::vec2(0.0, -self.text_size - gap)),
+            (&self.y_end_buffer, markers.y_end, cgmath::vec2(-gap, 0.0)),
+            (&self.origin_buffer, markers.origin, cgmath::vec2(-gap, gap)),
+        ];
+        for (buffer, offset, shift) in markers {
+            let Some(offset) = offset else { continue };
+            let pos = camera.offset_to_screen_space(offset, size);
+            let (left, top) = (pos.x - buffer_width(buffer) + shift.x, pos.y + shift.y);
+            text_areas.push(glyphon::TextArea {
+                buffer,
+                left,
+                top,
+                scale: 1.0,
+                bounds,
+                default_color: color,
+                custom_glyphs: &[],
+            });
+        }
+
+        // quadrant numbers are centred on the part of their quadrant in view
+        for (buffer, offset) in self.quadrant_buffers.iter().zip(grid.markers.quadrants) {
+            let Some(offset) = offset else { continue };
+            let pos = camera.offset_to_screen_space(offset, size);
+            text_areas.push(glyphon::TextArea {
+                buffer,
+                left: pos.x - buffer_width(buffer) / 2.0,
+                top: pos.y - self.text_size / 2.0,
+                scale: 1.0,
+                bounds,
+                default_color: color,
+                custom_glyphs: &[],
+            });
+        }
+
+        for label in &self.point_labels {
+            text_areas.push(glyphon::TextArea {
+                buffer: &label.buffer,
+                left: label.pos.x,
+                top: label.pos.y,
+                scale: 1.0,
+                bounds,
+                default_color: color,
+                custom_glyphs: &[],
+            });
+        }
+
+        if let Some(origin) = self.legend_origin {
+            text_areas.push(glyphon::TextArea {
+                buffer: &self.legend_buffer,
+                left: origin.x,
+                top: origin.y,
+                scale: 1.0,
+                bounds,
+                default_color: color,
+                custom_glyphs: &[],
+            });
+        }
+
+        self.text_renderer.prepare(
+            device,
+            queue,
+            &mut self.font_system,
+            &mut self.atlas,
+            &self.viewport,
+            text_areas,
+            &mut self.swash_cache,
+        )
+        .unwrap();
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+        self.update_metrics();
+    }
+
+    /// Sets the label font size in logical pixels. Titles are sized relative to it.
+    pub fn set_font_size(&mut self, font_size: f32) {
+        if font_size == self.font_size {
+            return;
+        }
+        self.font_size = font_size;
+        self.update_metrics();
+    }
+
+    /// Resizes the text in every buffer to the font size and scale factor, which reflows it.
+    fn update_metrics(&mut self) {
+        self.text_size = self.font_size * self.scale_factor;
+        self.spacing = self.text_size;
+
+        let metrics = glyphon::Metrics::new(self.text_size, self.spacing);
+        self.x_text_buffer.set_metrics(&mut self.font_system, metrics);
+        self.y_text_buffer.set_metrics(&mut self.font_system, metrics);
+        self.x_title_buffer.set_metrics(&mut self.font_system, metrics);
+        self.y_title_buffer.set_metrics(&mut self.font_system, metrics);
+        self.x_end_buffer.set_metrics(&mut self.font_system, metrics);
+        self.y_end_buffer.set_metrics(&mut self.font_system, metrics);
+        self.origin_buffer.set_metrics(&mut self.font_system, metrics);
+        for buffer in &mut self.quadrant_buffers {
+            buffer.set_metrics(&mut self.font_system, metrics);
+        }
+        self.legend_buffer.set_metrics(&mut self.font_system, metrics);
+        for label in &mut self.point_labels {
+            label.buffer.set_metrics(&mut self.font_system, metrics);
+        }
+
+        let title_size = self.text_size * TITLE_SCALE;
+        self.title_buffer.set_metrics(&mut self.font_system, glyphon::Metrics::new(title_size, title_size));
+    }
+
+    /// Sets the names listed in the legend, in order.
+    pub fn set_legend_names<'a>(&mut self, names: impl Iterator<Item = &'a str>) {
+        let text = names.collect::<Vec<_>>().join("\n");
+        self.legend_buffer.set_text(&mut self.font_system, &text, glyphon::Attrs::new(), glyphon::Shaping::Advanced);
+    }
+
+    /// Sets the point annotations to draw. Text is only reshaped for those which have changed.
+    pub fn set_point_labels<'a>(&mut self, labels: impl Iterator<Item = PlacedAnnotation<'a>>) {
+        let metrics = glyphon::Metrics::new(self.text_size, self.spacing);
+        let mut count = 0;
+        for (i, placed) in labels.enumerate() {
+            let text = placed.text.as_ref();
+            if i == self.point_labels.len() {
+                let mut buffer = glyphon::Buffer::new(&mut self.font_system, metrics);
+                buffer.set_size(&mut self.font_system, None, None);
+                self.point_labels.push(PointLabel { text: String::new(), buffer, pos: placed.point });
+            }
+            let label = &mut self.point_labels[i];
+            if label.text != text {
+                label.text = text.to_owned();
+                label.buffer.set_text(&mut self.font_system, text, glyphon::Attrs::new(), glyphon::Shaping::Advanced);
+            }
+            let size = cgmath::vec2(buffer_width(&label.buffer), self.text_size);
+            label.pos = anchored_top_left(placed.anchor, placed.point, placed.clearance, size) + placed.offset;
+            count = i + 1;
+        }
+        self.point_labels.truncate(count);
+    }
+
+    /// Returns the width of the longest name in the legend, in physical pixels.
+    pub fn legend_width(&self) -> f32 {
+        buffer_width(&self.legend_buffer)
+    }
+
+    pub fn set_titles(&mut self, titles: &Titles) {
+        let attrs = glyphon::Attrs::new();
+        for (buffer, text) in [
+            (&mut self.title_buffer, &titles.title),
+            (&mut self.x_title_buffer, &titles.x_axis),
+            (&mut self.y_title_buffer, &titles.y_axis),
+        ] {
+            buffer.set_text(&mut self.font_system, text, attrs, glyphon::Shaping::Advanced);
+        }
+    }
+
+    /// Sets the size in physical pixels of the viewport text is drawn into.
+    pub fn resize(&mut self, queue: &wgpu::Queue, size: dpi::PhysicalSize<u32>) {
+        self.viewport.update(queue, glyphon::Resolution { width: size.width, height: size.height });
+    }
+
+    /// Draws the text laid out by prepare.
+    pub fn render<'pass>(&'pass self, render_pass: &mut wgpu::RenderPass<'pass>) {
+        self.text_renderer.render(&self.atlas, &self.viewport, render_pass).unwrap();
+    }
+
+    /// Frees glyphs which weren't drawn in the last frame from the atlas.
+    pub fn trim(&mut self) {
+        self.atlas.trim();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_to_edges() {
+        assert_eq!(clamp_to_edges(50.0, 10.0, 100.0), 50.0);
+        assert_eq!(clamp_to_edges(-30.0, 10.0, 100.0), 0.0);
+        assert_eq!(clamp_to_edges(95.0, 10.0, 100.0), 90.0);
+        // content wider than the viewport sticks to the start
+        assert_eq!(clamp_to_edges(5.0, 200.0, 100.0), 0.0);
+    }
+
+    #[test]
+    fn test_anchored_top_left() {
+        let point = cgmath::vec2(100.0, 50.0);
+        let size = cgmath::vec2(40.0, 10.0);
+
+        assert_eq!(anchored_top_left(Anchor::TopRight, point, 4.0, size), cgmath::vec2(104.0, 36.0));
+        assert_eq!(anchored_top_left(Anchor::BottomLeft, point, 4.0, size), cgmath::vec2(56.0, 54.0));
+        // centred along the axis the anchor doesn't lean along
+        assert_eq!(anchored_top_left(Anchor::Above, point, 4.0, size), cgmath::vec2(80.0, 36.0));
+        assert_eq!(anchored_top_left(Anchor::Right, point, 4.0, size), cgmath::vec2(104.0, 45.0));
+    }
+
+    #[test]
+    fn test_glyph_color() {
+        assert_eq!(glyph_color(Color { r: 1.0, g: 0.5, b: 0.0, a: 1.0 }), glyphon::Color::rgba(255, 128, 0, 255));
+        // out of range channels are clamped rather than wrapping
+        assert_eq!(glyph_color(Color { r: 2.0, g: -1.0, b: 0.0, a: 0.0 }), glyphon::Color::rgba(255, 0, 0, 0));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::geometry::Color;
+
+/// Colours used to draw the graph.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Colour the graph is cleared to before drawing.
+    pub background: Color<f32>,
+    /// Colour of the axis labels and titles.
+    pub text: Color<f32>,
+    pub grid: GridStyle,
+}
+
+/// How the axes and gridlines are drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GridStyle {
+    pub axis_color: Color<f32>,
+    /// Colour of the labelled gridlines.
+    pub major_color: Color<f32>,
+    /// Colour of the gridlines between labelled ones, whose alpha is reduced further while
+    /// they fade in.
+    pub minor_color: Color<f32>,
+    /// Line widths in logical pixels. Lines are never drawn thinner than a physical pixel.
+    pub axis_width: f32,
+    pub major_width: f32,
+    pub minor_width: f32,
+    /// Labelled gridlines aimed for per unit of camera distance from the graph, which is about
+    /// how many fit in the height of the view.
+    pub major_density: f32,
+    /// Number of parts minor lines divide the space between labelled lines into, or None to
+    /// pick one to suit the spacing.
+    pub minor_subdivisions: Option<u32>,
+    /// Draw arrowheads on the positive ends of the axes, labelled x and y.
+    pub axis_arrows: bool,
+    /// Label the origin with a 0.
+    pub origin_label: bool,
+    /// Label each quadrant in view with its number, from I to IV.
+    pub quadrant_labels: bool,
+}
+
+impl Default for GridStyle {
+    fn default() -> Self {
+        Self {
+            axis_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+            major_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.7 },
+            minor_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.4 },
+            axis_width: 1.0,
+            major_width: 1.0,
+            minor_width: 1.0,
+            major_density: 8.0,
+            minor_subdivisions: None,
+            axis_arrows: false,
+            origin_label: false,
+            quadrant_labels: false,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 },
+            text: Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+            grid: GridStyle::default(),
+        }
+    }
+}
+
+impl Theme {
+    /// Light lines and text on a dark background.
+    pub fn dark() -> Self {
+        Self {
+            background: Color { r: 0.11, g: 0.11, b: 0.12, a: 1.0 },
+            text: Color { r: 0.9, g: 0.9, b: 0.9, a: 1.0 },
+            grid: GridStyle {
+                axis_color: Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 },
+                major_color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.6 },
+                minor_color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.3 },
+                ..GridStyle::default()
+            },
+        }
+    }
+
+    /// The default theme with a fully transparent background, for drawing over other windows.
+    pub fn overlay() -> Self {
+        Self::default().transparent()
+    }
+
+    /// Returns this theme with a fully transparent background, for drawing over other windows.
+    pub fn transparent(self) -> Self {
+        Self {
+            background: Color { a: 0.0, ..self.background },
+            ..self
+        }
+    }
+
+    /// The background as a premultiplied clear colour.
+    pub fn clear_color(&self) -> wgpu::Color {
+        let Color { r, g, b, a } = self.background;
+        wgpu::Color {
+            r: (r * a) as f64,
+            g: (g * a) as f64,
+            b: (b * a) as f64,
+            a: a as f64,
+        }
+    }
+}
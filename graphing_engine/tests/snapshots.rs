@@ -0,0 +1,174 @@
+//! Golden image tests, drawing plots offscreen and comparing them with the images in
+//! `tests/snapshots`, so changes to grid spacing, stroke tessellation or label placement show up
+//! in `cargo test` without a display.
+//!
+//! Images are only written with `UPDATE_SNAPSHOTS=1 cargo test -p graphing-engine --test
+//! snapshots`, for new snapshots or after an intended change to the rendering; look over them
+//! before committing them. Otherwise a missing image fails the test. Without a graphics adapter the comparisons
+//! are skipped. Labels are drawn in the system's fonts, so images recorded on one machine may not
+//! match on another with different fonts installed.
+
+use std::path::{Path, PathBuf};
+
+use graphing_engine::{Annotation, Bounds, Plot, Point};
+
+/// Largest difference in any channel for pixels to count as the same, for the small differences
+/// in rasterisation and blending between drivers.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Largest fraction of pixels which may differ before the images count as different.
+const MAX_DIFFERING: f64 = 0.002;
+
+/// How two images of the same size differ.
+#[derive(Debug, PartialEq)]
+struct Difference {
+    differing: usize,
+    /// The largest difference in any channel of any pixel.
+    max_channel: u8,
+}
+
+/// Compares two sets of RGBA pixels, counting the pixels where a channel differs by more than
+/// the tolerance.
+fn compare(expected: &[u8], actual: &[u8], tolerance: u8) -> Difference {
+    let mut difference = Difference { differing: 0, max_channel: 0 };
+    for (expected, actual) in expected.chunks_exact(4).zip(actual.chunks_exact(4)) {
+        let channel = expected.iter().zip(actual).map(|(e, a)| e.abs_diff(*a)).max().unwrap_or(0);
+        difference.max_channel = difference.max_channel.max(channel);
+        if channel > tolerance {
+            difference.differing += 1;
+        }
+    }
+    difference
+}
+
+/// Returns an image of the pixels which differ beyond the tolerance in red, over a faded copy of
+/// the expected image.
+fn diff_image(expected: &[u8], actual: &[u8], tolerance: u8) -> Vec<u8> {
+    expected.chunks_exact(4).zip(actual.chunks_exact(4))
+        .flat_map(|(expected, actual)| {
+            if expected.iter().zip(actual).any(|(e, a)| e.abs_diff(*a) > tolerance) {
+                [255, 0, 0, 255]
+            } else {
+                let faded = |channel: u8| 255 - (255 - channel) / 4;
+                [faded(expected[0]), faded(expected[1]), faded(expected[2]), 255]
+            }
+        })
+        .collect()
+}
+
+fn read_png(path: &Path) -> (u32, u32, Vec<u8>) {
+    let decoder = png::Decoder::new(std::fs::File::open(path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba, "{} isn't RGBA", path.display());
+    pixels.truncate(info.buffer_size());
+    (info.width, info.height, pixels)
+}
+
+fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) {
+    let mut encoder = png::Encoder::new(std::fs::File::create(path).unwrap(), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().unwrap().write_image_data(pixels).unwrap();
+}
+
+fn has_adapter() -> bool {
+    pollster::block_on(wgpu::Instance::default().request_adapter(&wgpu::RequestAdapterOptions::default())).is_some()
+}
+
+/// Draws plot and compares it with the golden image called name, or records it if
+/// UPDATE_SNAPSHOTS is set. On a mismatch the drawn image and a diff are written next to the test
+/// binaries.
+fn assert_snapshot(name: &str, plot: &Plot) {
+    if !has_adapter() {
+        eprintln!("skipping snapshot {name}: no graphics adapter was found");
+        return;
+    }
+    let actual = plot.to_rgba().unwrap();
+    let (width, height) = (plot.size.width, plot.size.height);
+
+    let golden = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots").join(format!("{name}.png"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(golden.parent().unwrap()).unwrap();
+        write_png(&golden, width, height, &actual);
+        eprintln!("recorded snapshot {}", golden.display());
+        return;
+    }
+    assert!(
+        golden.exists(),
+        "snapshot {name} has no golden image at {}; record it with UPDATE_SNAPSHOTS=1",
+        golden.display(),
+    );
+
+    let (expected_width, expected_height, expected) = read_png(&golden);
+    assert_eq!((expected_width, expected_height), (width, height), "snapshot {name} changed size");
+    let difference = compare(&expected, &actual, CHANNEL_TOLERANCE);
+    if difference.differing as f64 > MAX_DIFFERING * (width * height) as f64 {
+        let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+        let actual_path = out.join(format!("{name}.actual.png"));
+        let diff_path = out.join(format!("{name}.diff.png"));
+        write_png(&actual_path, width, height, &actual);
+        write_png(&diff_path, width, height, &diff_image(&expected, &actual, CHANNEL_TOLERANCE));
+        panic!(
+            "snapshot {name} differs in {} pixels, by up to {} in a channel; see {} and {}",
+            difference.differing,
+            difference.max_channel,
+            actual_path.display(),
+            diff_path.display(),
+        );
+    }
+}
+
+#[test]
+fn grid_spacing() {
+    assert_snapshot("grid_default", &Plot::default());
+    // zoomed far in and out, where the spacing of the grid lines changes by powers of ten
+    assert_snapshot("grid_zoomed_in", &Plot {
+        bounds: Bounds { x_min: 0.0, x_max: 0.03, y_min: -0.01, y_max: 0.01 },
+        ..Default::default()
+    });
+    assert_snapshot("grid_zoomed_out", &Plot {
+        bounds: Bounds { x_min: -4000.0, x_max: 6000.0, y_min: -3000.0, y_max: 3000.0 },
+        ..Default::default()
+    });
+}
+
+#[test]
+fn stroke_tessellation() {
+    assert_snapshot("strokes", &Plot {
+        equations: vec![
+            "2x + 1".to_owned(),
+            "x^2 - 4".to_owned(),
+            "x^3 - 3x".to_owned(),
+            "x^7 - 14x^5 + 49x^3 - 36x".to_owned(),
+        ],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn label_placement() {
+    let labelled = |x, y, label| Point {
+        label: Some(label),
+        ..Point::new(cgmath::vec2(x, y))
+    };
+    assert_snapshot("labels", &Plot {
+        equations: vec!["x^2 - 1".to_owned()],
+        points: vec![
+            labelled(-1.0, 0.0, Annotation::new("root")),
+            labelled(0.0, -1.0, Annotation::coordinates()),
+            labelled(4.5, 4.5, Annotation::new("near the corner")),
+        ],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn differences_within_the_tolerance_are_ignored() {
+    let expected = [10, 20, 30, 255, 200, 200, 200, 255];
+    let actual = [12, 18, 30, 255, 200, 100, 200, 255];
+
+    assert_eq!(compare(&expected, &actual, 4), Difference { differing: 1, max_channel: 100 });
+    assert_eq!(compare(&expected, &expected, 0), Difference { differing: 0, max_channel: 0 });
+    assert_eq!(diff_image(&expected, &actual, 4)[4..], [255, 0, 0, 255]);
+}
@@ -0,0 +1,16 @@
+use winit::event_loop::EventLoop;
+use winit::platform::android::activity::AndroidApp;
+use winit::platform::android::EventLoopBuilderExtAndroid;
+
+use crate::{Options, UserEvent};
+
+/// Entry point called by the native activity. Android starts apps without arguments, so the
+/// default options are used.
+#[no_mangle]
+fn android_main(app: AndroidApp) {
+    let event_loop = EventLoop::<UserEvent>::with_user_event()
+        .with_android_app(app)
+        .build()
+        .unwrap();
+    crate::run_app(event_loop, Options::default());
+}
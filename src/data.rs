@@ -0,0 +1,300 @@
+//! Tables of numbers read from CSV files, to plot as series of points, and points written back
+//! out as CSV.
+
+pub mod fit;
+
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+
+/// A row which couldn't be turned into a point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineError {
+    /// Line of the file the row came from, counting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Points read from two columns of a table, named after the y column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Samples {
+    pub name: String,
+    pub points: Vec<cgmath::Vector2<f32>>,
+    /// The category of each point, when the x column holds names rather than numbers.
+    pub categories: Vec<String>,
+    /// Rows which were skipped, in order.
+    pub errors: Vec<LineError>,
+}
+
+/// Columns of numbers read from CSV text.
+pub struct Table {
+    pub headers: Vec<String>,
+    /// The text of each cell, by column then row.
+    columns: Vec<Vec<String>>,
+    /// The line of the file each row came from, counting from 1.
+    lines: Vec<usize>,
+}
+
+/// Picks the delimiter from the first line, ignoring quoted cells. Semicolons and tabs are used
+/// where a comma is the decimal separator, so they take precedence over commas.
+fn delimiter(first: &str) -> char {
+    // every other piece between quotes is inside a quoted cell
+    let unquoted = first.split('"').step_by(2).collect::<String>();
+    if unquoted.contains(';') {
+        ';'
+    } else if unquoted.contains('\t') {
+        '\t'
+    } else {
+        ','
+    }
+}
+
+/// Splits a line into trimmed cells at delimiter, dropping the quotes around cells and keeping
+/// delimiters inside them.
+fn cells(line: &str, delimiter: char) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == delimiter && !quoted {
+            cells.push(&line[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    cells.push(&line[start..]);
+    cells.into_iter().map(|cell| cell.trim().trim_matches('"').trim()).collect()
+}
+
+/// Reads a number, accepting a comma as the decimal separator when it is the only one, as in
+/// 2,5.
+fn parse_number(cell: &str) -> Option<f32> {
+    cell.parse().ok().or_else(|| {
+        let decimal_comma = cell.matches(',').count() == 1 && !cell.contains('.');
+        decimal_comma.then(|| cell.replace(',', ".").parse().ok()).flatten()
+    })
+}
+
+/// Quotes cell if it holds a delimiter, quote or line break, doubling any quotes inside it.
+/// Semicolons and tabs are quoted as well as commas, as either in the header would otherwise be
+/// taken for the delimiter when the file is read back.
+fn quoted(cell: &str) -> String {
+    if cell.contains([',', ';', '\t', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+/// Writes points as comma separated x and y columns under a header row. Where categories are
+/// given they are written in place of the x values, as for bars.
+pub fn to_csv(x: &str, y: &str, points: &[cgmath::Vector2<f32>], categories: &[String]) -> String {
+    let mut text = format!("{},{}\n", quoted(x), quoted(y));
+    for (i, point) in points.iter().enumerate() {
+        let x = categories.get(i).map_or_else(|| point.x.to_string(), |category| quoted(category));
+        text.push_str(&format!("{x},{}\n", point.y));
+    }
+    text
+}
+
+impl Table {
+    /// Reads delimited columns from text. The first line is taken as the column names if any of
+    /// it isn't numeric. Fails if there are fewer than two columns.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+        let (_, first) = lines.peek().ok_or_else(|| anyhow!("the file is empty"))?;
+
+        let delimiter = delimiter(first);
+        let first = cells(first, delimiter);
+        if first.len() < 2 {
+            return Err(anyhow!("at least two columns are needed to plot"));
+        }
+
+        let headers: Vec<String> = if first.iter().any(|cell| parse_number(cell).is_none()) {
+            lines.next();
+            first.iter().map(|cell| cell.to_string()).collect()
+        } else {
+            (1..=first.len()).map(|i| format!("Column {i}")).collect()
+        };
+
+        let mut columns = vec![Vec::new(); headers.len()];
+        let mut line_numbers = Vec::new();
+        for (i, line) in lines {
+            let mut row = cells(line, delimiter).into_iter();
+            for column in &mut columns {
+                column.push(row.next().unwrap_or_default().to_owned());
+            }
+            line_numbers.push(i + 1);
+        }
+
+        Ok(Self {
+            headers,
+            columns,
+            lines: line_numbers,
+        })
+    }
+
+    /// Returns the number in a cell, or a message saying what is wrong with it.
+    fn number(&self, column: usize, row: usize) -> Result<f32, String> {
+        let text = &self.columns[column][row];
+        let header = &self.headers[column];
+        if text.is_empty() {
+            Err(format!("no value for {header}"))
+        } else {
+            parse_number(text).ok_or_else(|| format!("\"{text}\" for {header} is not a number"))
+        }
+    }
+
+    /// Returns a point for each row with numbers in both the x and y columns, and an error for
+    /// each row without.
+    pub fn samples(&self, x: usize, y: usize) -> Samples {
+        let mut points = Vec::new();
+        let mut errors = Vec::new();
+        for (row, &line) in self.lines.iter().enumerate() {
+            match self.number(x, row).and_then(|x| Ok(cgmath::vec2(x, self.number(y, row)?))) {
+                Ok(point) => points.push(point),
+                Err(message) => errors.push(LineError { line, message }),
+            }
+        }
+
+        Samples {
+            name: self.headers[y].clone(),
+            points,
+            categories: Vec::new(),
+            errors,
+        }
+    }
+
+    /// Returns a point for each row with a number in the y column, taking the text in the label
+    /// column as its category and placing the categories at x = 1, 2 and so on. Rows without a
+    /// number or a label are skipped with an error.
+    pub fn categories(&self, label: usize, y: usize) -> Samples {
+        let mut points = Vec::new();
+        let mut categories = Vec::new();
+        let mut errors = Vec::new();
+        for (row, &line) in self.lines.iter().enumerate() {
+            let text = &self.columns[label][row];
+            let value = if text.is_empty() {
+                Err(format!("no value for {}", self.headers[label]))
+            } else {
+                self.number(y, row)
+            };
+            match value {
+                Ok(value) => {
+                    points.push(cgmath::vec2((points.len() + 1) as f32, value));
+                    categories.push(text.clone());
+                }
+                Err(message) => errors.push(LineError { line, message }),
+            }
+        }
+
+        Samples {
+            name: self.headers[y].clone(),
+            points,
+            categories,
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_headers() {
+        let table = Table::parse("t, \"height\", speed\n0, 1.5, 2\n1, 2.5, x\n\n").unwrap();
+
+        assert_eq!(table.headers, ["t", "height", "speed"]);
+        let samples = table.samples(0, 1);
+        assert_eq!(samples.name, "height");
+        assert_eq!(samples.points, [cgmath::vec2(0.0, 1.5), cgmath::vec2(1.0, 2.5)]);
+    }
+
+    #[test]
+    fn test_parse_without_headers() {
+        let table = Table::parse("1,2\n3,\n5,6").unwrap();
+
+        assert_eq!(table.headers, ["Column 1", "Column 2"]);
+        // rows missing a value are skipped
+        assert_eq!(table.samples(0, 1).points, [cgmath::vec2(1.0, 2.0), cgmath::vec2(5.0, 6.0)]);
+        assert_eq!(table.samples(1, 0).points, [cgmath::vec2(2.0, 1.0), cgmath::vec2(6.0, 5.0)]);
+    }
+
+    #[test]
+    fn test_parse_needs_two_columns() {
+        assert!(Table::parse("").is_err());
+        assert!(Table::parse("1\n2\n").is_err());
+    }
+
+    #[test]
+    fn decimal_commas_are_read() {
+        let table = Table::parse("x;y\n1,5;2\n-0,25;3,75").unwrap();
+        assert_eq!(table.samples(0, 1).points, [cgmath::vec2(1.5, 2.0), cgmath::vec2(-0.25, 3.75)]);
+
+        // quoted cells can hold the delimiter
+        let table = Table::parse("\"1,5\",2\n3,\"4,5\"").unwrap();
+        assert_eq!(table.headers, ["Column 1", "Column 2"]);
+        assert_eq!(table.samples(0, 1).points, [cgmath::vec2(1.5, 2.0), cgmath::vec2(3.0, 4.5)]);
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let table = Table::parse("x\ty\n1\t2\n\n3\tn/a\n4").unwrap();
+
+        let samples = table.samples(0, 1);
+
+        assert_eq!(samples.points, [cgmath::vec2(1.0, 2.0)]);
+        assert_eq!(samples.errors, [
+            LineError { line: 4, message: "\"n/a\" for y is not a number".to_owned() },
+            LineError { line: 5, message: "no value for y".to_owned() },
+        ]);
+        assert_eq!(samples.errors[1].to_string(), "line 5: no value for y");
+    }
+
+    #[test]
+    fn categories_are_placed_one_apart() {
+        let table = Table::parse("fruit,sold\napples,12\n,3\npears,x\nplums,\"4,5\"").unwrap();
+
+        let samples = table.categories(0, 1);
+
+        assert_eq!(samples.points, [cgmath::vec2(1.0, 12.0), cgmath::vec2(2.0, 4.5)]);
+        assert_eq!(samples.categories, ["apples", "plums"]);
+        assert_eq!(samples.errors.iter().map(|error| error.line).collect::<Vec<_>>(), [3, 4]);
+    }
+
+    #[test]
+    fn written_points_read_back() {
+        let points = [cgmath::vec2(-1.5, 0.1), cgmath::vec2(2.0, 1e-7)];
+
+        let table = Table::parse(&to_csv("x", "height, m", &points, &[])).unwrap();
+
+        assert_eq!(table.headers, ["x", "height, m"]);
+        assert_eq!(table.samples(0, 1).points, points);
+
+        // names holding the other delimiters don't change the one the file is read with
+        for name in ["temp; °C", "temp\t°C"] {
+            let table = Table::parse(&to_csv("x", name, &points, &[])).unwrap();
+
+            assert_eq!(table.headers, ["x", name]);
+            assert_eq!(table.samples(0, 1).points, points);
+        }
+    }
+
+    #[test]
+    fn categories_are_written_as_x() {
+        let text = to_csv("fruit", "sold", &[cgmath::vec2(1.0, 12.0), cgmath::vec2(2.0, 4.5)], &["apples".to_owned(), "say \"hi\"".to_owned()]);
+
+        assert_eq!(text, "fruit,sold\napples,12\n\"say \"\"hi\"\"\",4.5\n");
+    }
+}
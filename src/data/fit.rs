@@ -0,0 +1,254 @@
+//! Least squares fits of curves to series of points.
+//!
+//! Exponential and power curves are fitted as straight lines through the logarithms of the
+//! values, which weights the points differently to fitting the curve itself but needs no
+//! iteration.
+
+use anyhow::{anyhow, Result};
+
+/// Number of points sampled along curves which can't be written as equations.
+const CURVE_SAMPLES: usize = 200;
+/// Significant figures coefficients are written to in formulas.
+const FORMULA_DIGITS: i32 = 4;
+
+/// A kind of curve to fit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Model {
+    /// y = mx + c
+    Linear,
+    /// y = ax^2 + bx + c
+    Quadratic,
+    /// y = ae^(bx)
+    Exponential,
+    /// y = ax^b
+    Power,
+}
+
+impl Model {
+    pub const ALL: [Model; 4] = [Model::Linear, Model::Quadratic, Model::Exponential, Model::Power];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Model::Linear => "Linear",
+            Model::Quadratic => "Quadratic",
+            Model::Exponential => "Exponential",
+            Model::Power => "Power",
+        }
+    }
+}
+
+/// A fitted curve.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fit {
+    /// Coefficients from the constant term up.
+    Polynomial(Vec<f32>),
+    /// y = ae^(bx)
+    Exponential { a: f32, b: f32 },
+    /// y = ax^b
+    Power { a: f32, b: f32 },
+}
+
+/// Solves the square system matrix * x = rhs by Gaussian elimination with partial pivoting,
+/// returning None if it is singular.
+fn solve<const N: usize>(mut matrix: [[f64; N]; N], mut rhs: [f64; N]) -> Option<[f64; N]> {
+    for column in 0..N {
+        let pivot = (column..N).max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+        if matrix[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+
+        let pivot_row = matrix[column];
+        for row in column + 1..N {
+            let factor = matrix[row][column] / pivot_row[column];
+            for (cell, pivot) in matrix[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                *cell -= factor * pivot;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+
+    let mut solution = [0.0; N];
+    for row in (0..N).rev() {
+        let known = (row + 1..N).map(|k| matrix[row][k] * solution[k]).sum::<f64>();
+        solution[row] = (rhs[row] - known) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+/// Fits a polynomial of degree N - 1 to (x, y) pairs, returning its coefficients from the
+/// constant term up.
+fn polynomial<const N: usize>(points: &[(f64, f64)]) -> Result<[f64; N]> {
+    if points.len() < N {
+        return Err(anyhow!("at least {N} points are needed"));
+    }
+
+    // the normal equations, summing powers of x up to twice the degree
+    let mut matrix = [[0.0; N]; N];
+    let mut rhs = [0.0; N];
+    for &(x, y) in points {
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell += x.powi((i + j) as i32);
+            }
+            rhs[i] += y * x.powi(i as i32);
+        }
+    }
+    solve(matrix, rhs).ok_or_else(|| anyhow!("the points need more than one distinct x value"))
+}
+
+/// Writes value to FORMULA_DIGITS significant figures, without trailing zeros.
+fn rounded(value: f32) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return value.to_string();
+    }
+    let decimals = (FORMULA_DIGITS - 1 - value.abs().log10().floor() as i32).max(0) as usize;
+    let text = format!("{value:.decimals$}");
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        text
+    }
+}
+
+/// Writes the terms of a polynomial, highest power first, with number writing each coefficient.
+/// Terms with a coefficient of zero are left out.
+fn polynomial_text(coeffs: &[f32], number: impl Fn(f32) -> String) -> String {
+    let mut text = String::new();
+    for (power, &coeff) in coeffs.iter().enumerate().rev().filter(|(_, &coeff)| coeff != 0.0) {
+        let sign = if coeff < 0.0 { "-" } else { "+" };
+        if text.is_empty() {
+            text.push_str(if coeff < 0.0 { "-" } else { "" });
+        } else {
+            text.push_str(&format!(" {sign} "));
+        }
+        text.push_str(&number(coeff.abs()));
+        match power {
+            0 => {}
+            1 => text.push('x'),
+            _ => text.push_str(&format!("x^{power}")),
+        }
+    }
+    if text.is_empty() { "0".to_owned() } else { text }
+}
+
+impl Fit {
+    /// Fits model to points, failing if there are too few or their values are out of the model's
+    /// domain.
+    pub fn new(model: Model, points: &[cgmath::Vector2<f32>]) -> Result<Self> {
+        let pairs = points.iter().map(|point| (point.x as f64, point.y as f64));
+        match model {
+            Model::Linear => {
+                let [c, m] = polynomial(&pairs.collect::<Vec<_>>())?;
+                Ok(Fit::Polynomial(vec![c as f32, m as f32]))
+            }
+            Model::Quadratic => {
+                let [c, b, a] = polynomial(&pairs.collect::<Vec<_>>())?;
+                Ok(Fit::Polynomial(vec![c as f32, b as f32, a as f32]))
+            }
+            Model::Exponential => {
+                if points.iter().any(|point| point.y <= 0.0) {
+                    return Err(anyhow!("an exponential fit needs every y to be positive"));
+                }
+                let [ln_a, b] = polynomial(&pairs.map(|(x, y)| (x, y.ln())).collect::<Vec<_>>())?;
+                Ok(Fit::Exponential { a: ln_a.exp() as f32, b: b as f32 })
+            }
+            Model::Power => {
+                if points.iter().any(|point| point.x <= 0.0 || point.y <= 0.0) {
+                    return Err(anyhow!("a power fit needs every x and y to be positive"));
+                }
+                let [ln_a, b] = polynomial(&pairs.map(|(x, y)| (x.ln(), y.ln())).collect::<Vec<_>>())?;
+                Ok(Fit::Power { a: ln_a.exp() as f32, b: b as f32 })
+            }
+        }
+    }
+
+    pub fn evaluate(&self, x: f32) -> f32 {
+        match self {
+            Fit::Polynomial(coeffs) => coeffs.iter().rev().fold(0.0, |sum, coeff| sum * x + coeff),
+            Fit::Exponential { a, b } => a * (b * x).exp(),
+            Fit::Power { a, b } => a * x.powf(*b),
+        }
+    }
+
+    /// Returns the fitted formula with its coefficients rounded, for showing to the user.
+    pub fn formula(&self) -> String {
+        match self {
+            Fit::Polynomial(coeffs) => format!("y = {}", polynomial_text(coeffs, rounded)),
+            Fit::Exponential { a, b } => format!("y = {}e^({}x)", rounded(*a), rounded(*b)),
+            Fit::Power { a, b } => format!("y = {}x^{}", rounded(*a), rounded(*b)),
+        }
+    }
+
+    /// Returns the fit as an equation to plot, with its coefficients in full, or None if it isn't
+    /// a polynomial and so can't be written as one.
+    pub fn equation(&self) -> Option<String> {
+        match self {
+            Fit::Polynomial(coeffs) => Some(polynomial_text(coeffs, |coeff| coeff.to_string())),
+            Fit::Exponential { .. } | Fit::Power { .. } => None,
+        }
+    }
+
+    /// Returns points along the curve between x_min and x_max, to draw it where it can't be
+    /// plotted as an equation.
+    pub fn sample(&self, x_min: f32, x_max: f32) -> Vec<cgmath::Vector2<f32>> {
+        (0..CURVE_SAMPLES)
+            .map(|i| x_min + (x_max - x_min) * i as f32 / (CURVE_SAMPLES - 1) as f32)
+            .map(|x| cgmath::vec2(x, self.evaluate(x)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(f: impl Fn(f32) -> f32) -> Vec<cgmath::Vector2<f32>> {
+        (1..=6).map(|x| cgmath::vec2(x as f32, f(x as f32))).collect()
+    }
+
+    #[test]
+    fn exact_curves_are_recovered() {
+        use approx::relative_eq;
+
+        let Fit::Polynomial(coeffs) = Fit::new(Model::Quadratic, &points(|x| 2.0 * x * x - 3.0 * x + 1.0)).unwrap() else {
+            panic!("a quadratic fit is a polynomial");
+        };
+        for (coeff, expected) in coeffs.iter().zip([1.0, -3.0, 2.0]) {
+            assert!(relative_eq!(*coeff, expected, epsilon = 1e-3));
+        }
+
+        let Fit::Exponential { a, b } = Fit::new(Model::Exponential, &points(|x| 3.0 * (0.5 * x).exp())).unwrap() else {
+            panic!("an exponential fit is exponential");
+        };
+        assert!(relative_eq!(a, 3.0, epsilon = 1e-3));
+        assert!(relative_eq!(b, 0.5, epsilon = 1e-4));
+
+        let fit = Fit::new(Model::Power, &points(|x| 2.0 * x.powf(1.5))).unwrap();
+        assert!(relative_eq!(fit.evaluate(4.0), 16.0, epsilon = 1e-2));
+    }
+
+    #[test]
+    fn linear_fits_pass_between_noisy_points() {
+        let fit = Fit::new(Model::Linear, &[cgmath::vec2(0.0, 1.0), cgmath::vec2(1.0, 1.0), cgmath::vec2(2.0, 3.0), cgmath::vec2(3.0, 3.0)]).unwrap();
+
+        assert_eq!(fit.formula(), "y = 0.8x + 0.8");
+        assert_eq!(fit.equation().as_deref(), Some("0.8x + 0.8"));
+    }
+
+    #[test]
+    fn unfittable_points_are_errors() {
+        assert!(Fit::new(Model::Linear, &[cgmath::vec2(1.0, 2.0)]).is_err());
+        assert!(Fit::new(Model::Linear, &[cgmath::vec2(1.0, 2.0), cgmath::vec2(1.0, 3.0)]).is_err());
+        assert!(Fit::new(Model::Exponential, &points(|x| x - 3.0)).is_err());
+        assert!(Fit::new(Model::Power, &[cgmath::vec2(-1.0, 2.0), cgmath::vec2(1.0, 3.0)]).is_err());
+    }
+
+    #[test]
+    fn formulas_round_coefficients() {
+        assert_eq!(Fit::Polynomial(vec![-0.5, 0.0, 1.23456]).formula(), "y = 1.235x^2 - 0.5");
+        assert_eq!(Fit::Exponential { a: 2.0, b: -0.125 }.formula(), "y = 2e^(-0.125x)");
+        assert_eq!(Fit::Power { a: 1234.5678, b: 2.0 }.formula(), "y = 1235x^2");
+    }
+}
@@ -0,0 +1,52 @@
+//! Native file and message dialogs. Android has none, so there nothing can be opened or saved
+//! through them and errors are only logged.
+
+use std::path::PathBuf;
+
+/// A name for a kind of file and the extensions it has.
+pub type Filter = (&'static str, &'static [&'static str]);
+
+/// Asks for a file to open. Returns None if the dialog was cancelled.
+#[cfg(not(target_os = "android"))]
+pub fn pick_file(filters: &[Filter]) -> Option<PathBuf> {
+    file_dialog(filters).pick_file()
+}
+
+/// Asks where to save a file, suggesting name. Returns None if the dialog was cancelled.
+#[cfg(not(target_os = "android"))]
+pub fn save_file(filters: &[Filter], name: &str) -> Option<PathBuf> {
+    file_dialog(filters).set_file_name(name).save_file()
+}
+
+#[cfg(not(target_os = "android"))]
+fn file_dialog(filters: &[Filter]) -> rfd::FileDialog {
+    filters.iter().fold(rfd::FileDialog::new(), |dialog, (name, extensions)| dialog.add_filter(*name, extensions))
+}
+
+/// Shows a modal error message box.
+#[cfg(not(target_os = "android"))]
+pub fn error(description: String) {
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("graphing calculator")
+        .set_description(description)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
+
+#[cfg(target_os = "android")]
+pub fn pick_file(_filters: &[Filter]) -> Option<PathBuf> {
+    log::warn!("files can't be opened on Android");
+    None
+}
+
+#[cfg(target_os = "android")]
+pub fn save_file(_filters: &[Filter], _name: &str) -> Option<PathBuf> {
+    log::warn!("files can't be saved on Android");
+    None
+}
+
+#[cfg(target_os = "android")]
+pub fn error(description: String) {
+    log::error!("{description}");
+}
@@ -1,17 +1,55 @@
+mod analysis;
+mod accessibility;
+mod complex_map;
+mod csv_export;
+mod csv_import;
+mod diagnostics;
+mod equation_list;
+mod help;
+mod parameters;
+mod plot_view;
+mod polar_region;
+mod series_list;
+mod settings;
+mod toasts;
+mod vector_field;
+pub mod points;
+pub mod titles;
+
+pub use accessibility::AccessibleName;
+pub use analysis::CurveAnalysis;
+pub use complex_map::ComplexMap;
+pub use csv_export::{CsvExport, ExportAction};
+pub use csv_import::{CsvImport, ImportAction};
+pub use diagnostics::Diagnostics;
+pub use equation_list::{EquationList, PALETTE};
+pub use help::Help;
+pub use parameters::{Parameter, Parameters};
+pub use plot_view::{PlotView, ZoomControl};
+pub use polar_region::PolarShading;
+pub use series_list::{FitRequest, SeriesAction, SeriesList};
+pub use settings::{present_mode, Settings};
+pub use toasts::Toasts;
+pub use vector_field::Streamlines;
+
 pub struct GuiRenderer {
     egui_state: egui_winit::State,
     egui_renderer: egui_wgpu::Renderer,
 }
 
 impl GuiRenderer {
-    pub fn new(
-        device: &wgpu::Device,
+    /// Creates egui's state for the window and sets up AccessKit, which has to be done before the
+    /// window is first shown, so this is separate from creating the renderer.
+    pub fn window_state<T: From<egui_winit::accesskit_winit::Event> + Send>(
         window: &winit::window::Window,
-        color_format: wgpu::TextureFormat,
-    ) -> Self {
+        proxy: winit::event_loop::EventLoopProxy<T>,
+    ) -> egui_winit::State {
         let egui_context = egui::Context::default();
+        // zooming is handled through the interface scale setting instead, which egui's own
+        // shortcuts would fight with
+        egui_context.options_mut(|options| options.zoom_with_keyboard = false);
 
-        let egui_state = egui_winit::State::new(
+        let mut egui_state = egui_winit::State::new(
             egui_context, 
             egui::viewport::ViewportId::ROOT,
             &window,
@@ -19,23 +57,51 @@ impl GuiRenderer {
             None,
             None,
         );
+        egui_state.init_accesskit(window, proxy);
+        egui_state
+    }
+
+    pub fn new(
+        device: &wgpu::Device,
+        egui_state: egui_winit::State,
+        target: &graphing_engine::RenderTargetConfig,
+    ) -> Self {
         let egui_renderer = egui_wgpu::Renderer::new(
             device, 
-            color_format,
+            target.format,
             None,
-            1,
+            target.sample_count,
             false,
         );
 
-
         Self {
             egui_state,
             egui_renderer,
         }
     }
 
-    pub fn input(&mut self, window: &winit::window::Window, event: &winit::event::WindowEvent) -> bool {
-        self.egui_state.on_window_event(window, event).consumed
+    pub fn input(&mut self, window: &winit::window::Window, event: &winit::event::WindowEvent) -> egui_winit::EventResponse {
+        self.egui_state.on_window_event(window, event)
+    }
+
+    /// Passes on a request from assistive technology, such as a screen reader. The tree of widgets
+    /// is only built for AccessKit while one is listening.
+    pub fn accesskit_event(&mut self, event: egui_winit::accesskit_winit::WindowEvent) {
+        use egui_winit::accesskit_winit::WindowEvent;
+
+        match event {
+            WindowEvent::InitialTreeRequested => self.ctx().enable_accesskit(),
+            WindowEvent::ActionRequested(request) => self.egui_state.on_accesskit_action_request(request),
+            WindowEvent::AccessibilityDeactivated => self.ctx().disable_accesskit(),
+        }
+    }
+
+    pub fn register_native_texture(&mut self, device: &wgpu::Device, view: &wgpu::TextureView) -> egui::TextureId {
+        self.egui_renderer.register_native_texture(device, view, wgpu::FilterMode::Linear)
+    }
+
+    pub fn update_native_texture(&mut self, device: &wgpu::Device, view: &wgpu::TextureView, id: egui::TextureId) {
+        self.egui_renderer.update_egui_texture_from_wgpu_texture(device, view, wgpu::FilterMode::Linear, id);
     }
 
     pub fn ctx(&self) -> &egui::Context {
@@ -47,6 +113,7 @@ impl GuiRenderer {
         self.ctx().begin_pass(input);
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         device: &wgpu::Device,
@@ -55,7 +122,8 @@ impl GuiRenderer {
         window: &winit::window::Window,
         view: &wgpu::TextureView,
         screen_descriptor: &egui_wgpu::ScreenDescriptor,
-    ) {
+        clear_color: wgpu::Color,
+    ) -> std::time::Duration {
         self.ctx().set_pixels_per_point(screen_descriptor.pixels_per_point);
 
         let full_output = self.ctx().end_pass();
@@ -78,7 +146,7 @@ impl GuiRenderer {
                     view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Load,
+                        load: wgpu::LoadOp::Clear(clear_color),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -93,6 +161,10 @@ impl GuiRenderer {
         for id in &full_output.textures_delta.free {
             self.egui_renderer.free_texture(id);
         }
-        
+
+        // how long until egui wants to be drawn again, e.g. for animations and blinking cursors
+        full_output.viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .map_or(std::time::Duration::MAX, |output| output.repaint_delay)
     }
 }
@@ -0,0 +1,15 @@
+/// Names for controls whose visible text is a symbol, or which have none, so that screen readers
+/// have something meaningful to read out.
+pub trait AccessibleName {
+    /// Sets the name read out for the control in place of its visible text.
+    fn accessible_name(self, widget: egui::WidgetType, name: impl ToString) -> Self;
+}
+
+impl AccessibleName for egui::Response {
+    fn accessible_name(self, widget: egui::WidgetType, name: impl ToString) -> Self {
+        let name = name.to_string();
+        let enabled = self.enabled();
+        self.widget_info(|| egui::WidgetInfo::labeled(widget, enabled, &name));
+        self
+    }
+}
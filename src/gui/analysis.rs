@@ -0,0 +1,96 @@
+use graphing_engine::{format_number, Analysis, Bounds, Extremum, LineId, Trend};
+
+use crate::gui::equation_list::EquationRow;
+
+/// Digits shown are those which matter at this fraction of the visible range.
+const PRECISION: f32 = 1e-5;
+
+/// The roots, turning points and end behaviour of the selected equation within the view.
+pub struct CurveAnalysis {
+    /// The last analysis, with the line, coefficients and view it was found for, so that it is
+    /// only redone when one of them changes.
+    cached: Option<(LineId, Vec<f32>, Bounds, Analysis)>,
+}
+
+fn trend_text(trend: Trend, step: f32) -> String {
+    match trend {
+        Trend::Up => "+∞".to_owned(),
+        Trend::Down => "−∞".to_owned(),
+        Trend::Level(y) => format_number(y, step),
+    }
+}
+
+impl CurveAnalysis {
+    pub fn new() -> Self {
+        Self {
+            cached: None,
+        }
+    }
+
+    /// Returns the analysis of the line with id in the current view, reusing the last one if
+    /// nothing has changed.
+    fn analysis(&mut self, id: LineId, engine: &graphing_engine::State) -> Option<&Analysis> {
+        let coeffs = engine.line_coeffs(id).filter(|coeffs| !coeffs.is_empty())?;
+        let bounds = engine.visible_bounds();
+
+        let stale = !matches!(&self.cached, Some((l, c, b, _)) if *l == id && c == coeffs && *b == bounds);
+        if stale {
+            let analysis = graphing_engine::analyse(coeffs, bounds.x_min, bounds.x_max);
+            self.cached = Some((id, coeffs.to_vec(), bounds, analysis));
+        }
+        self.cached.as_ref().map(|(_, _, _, analysis)| analysis)
+    }
+
+    /// Shows the analysis of row, if a single row with a valid equation is selected.
+    pub fn show(&mut self, ui: &mut egui::Ui, row: Option<&EquationRow>, engine: &graphing_engine::State) {
+        let Some(row) = row else {
+            return;
+        };
+        let bounds = engine.visible_bounds();
+        let x_step = (bounds.x_max - bounds.x_min) * PRECISION;
+        let y_step = (bounds.y_max - bounds.y_min) * PRECISION;
+        let Some(analysis) = self.analysis(row.id, engine) else {
+            return;
+        };
+
+        egui::CollapsingHeader::new(format!("Analysis of {}", row.text)).default_open(true).show(ui, |ui| {
+            egui::Grid::new("analysis").num_columns(2).show(ui, |ui| {
+                ui.label("y-intercept");
+                ui.label(format_number(analysis.y_intercept, y_step));
+                ui.end_row();
+
+                ui.label("Roots");
+                if analysis.roots.is_empty() {
+                    ui.weak("none in view");
+                } else {
+                    let roots = analysis.roots.iter().map(|x| format_number(*x, x_step));
+                    ui.label(roots.collect::<Vec<_>>().join(", "));
+                }
+                ui.end_row();
+
+                ui.label("Extrema");
+                ui.vertical(|ui| {
+                    if analysis.extrema.is_empty() {
+                        ui.weak("none in view");
+                    }
+                    for (point, kind) in &analysis.extrema {
+                        let kind = match kind {
+                            Extremum::Minimum => "min",
+                            Extremum::Maximum => "max",
+                        };
+                        ui.label(format!("{kind} ({}, {})", format_number(point.x, x_step), format_number(point.y, y_step)));
+                    }
+                });
+                ui.end_row();
+
+                let (left, right) = analysis.ends;
+                ui.label("Ends");
+                ui.vertical(|ui| {
+                    ui.label(format!("x → −∞: y → {}", trend_text(left, y_step)));
+                    ui.label(format!("x → +∞: y → {}", trend_text(right, y_step)));
+                });
+                ui.end_row();
+            });
+        });
+    }
+}
@@ -0,0 +1,92 @@
+use graphing_engine::GridMapping;
+
+/// Seconds the animation takes to go from the grid to its image, or back.
+const ANIMATION_SECONDS: f32 = 3.0;
+
+/// Shows the image of the grid under a complex function w = f(z), for seeing how it stretches and
+/// turns the plane.
+pub struct ComplexMap {
+    shown: bool,
+    mapping: GridMapping,
+    /// Whether the grid is moving back and forth between itself and its image, and which way.
+    animating: bool,
+    forwards: bool,
+    error: Option<String>,
+}
+
+/// Returns progress moved on by dt seconds of the animation, turning round at either end, and
+/// the direction it is then going in.
+fn step(progress: f32, forwards: bool, dt: f32) -> (f32, bool) {
+    let step = dt / ANIMATION_SECONDS;
+    let progress = if forwards { progress + step } else { progress - step };
+    if progress >= 1.0 {
+        (1.0, false)
+    } else if progress <= 0.0 {
+        (0.0, true)
+    } else {
+        (progress, forwards)
+    }
+}
+
+impl ComplexMap {
+    pub fn new() -> Self {
+        Self {
+            shown: false,
+            mapping: GridMapping::default(),
+            animating: false,
+            forwards: true,
+            error: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, engine: &mut graphing_engine::State) {
+        egui::CollapsingHeader::new("Complex mapping").show(ui, |ui| {
+            ui.checkbox(&mut self.shown, "Show the image of the grid under w = f(z)");
+            ui.add_enabled_ui(self.shown, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("f(z) =");
+                    ui.text_edit_singleline(&mut self.mapping.function)
+                        .on_hover_text("A polynomial with x standing for z, such as x^2 or x^3 - x");
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut self.mapping.progress, 0.0..=1.0).text("from z to f(z)"));
+                    let label = if self.animating { "Stop" } else { "Animate" };
+                    if ui.button(label).clicked() {
+                        self.animating = !self.animating;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut self.mapping.divisions, 1..=40).text("grid lines"));
+                    if ui.button("Map the view").on_hover_text("Map the grid over the region in view").clicked() {
+                        self.mapping.bounds = engine.visible_bounds();
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        });
+
+        if self.shown && self.animating {
+            let dt = ui.input(|input| input.stable_dt);
+            (self.mapping.progress, self.forwards) = step(self.mapping.progress, self.forwards, dt);
+            ui.ctx().request_repaint();
+        }
+        let mapping = self.shown.then(|| self.mapping.clone());
+        if engine.grid_mapping() != mapping.as_ref() {
+            self.error = engine.set_grid_mapping(mapping).err().map(|e| e.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animation_turns_round_at_the_ends() {
+        assert_eq!(step(0.5, true, ANIMATION_SECONDS / 4.0), (0.75, true));
+        assert_eq!(step(0.9, true, ANIMATION_SECONDS / 4.0), (1.0, false));
+        assert_eq!(step(0.1, false, ANIMATION_SECONDS / 4.0), (0.0, true));
+    }
+}
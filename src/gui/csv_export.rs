@@ -0,0 +1,88 @@
+use graphing_engine::{Bounds, LineId};
+
+/// Fewest and most samples which can be asked for.
+const SAMPLE_RANGE: std::ops::RangeInclusive<usize> = 2..=100_000;
+
+/// What was picked in the export prompt.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportAction {
+    Export,
+    Cancel,
+}
+
+/// An equation to write to CSV, with how many points to sample it at and over what range of x.
+pub struct CsvExport {
+    pub id: LineId,
+    /// The equation, shown in the title and written as the y header.
+    pub name: String,
+    samples: usize,
+    x_min: f32,
+    x_max: f32,
+}
+
+/// Returns count evenly spaced values from min to max, including both.
+fn sample_xs(count: usize, min: f32, max: f32) -> impl Iterator<Item = f32> {
+    let last = count.saturating_sub(1).max(1) as f32;
+    (0..count).map(move |i| min + (max - min) * i as f32 / last)
+}
+
+impl CsvExport {
+    /// Starts with the range of x in view.
+    pub fn new(id: LineId, name: String, bounds: Bounds) -> Self {
+        Self {
+            id,
+            name,
+            samples: 101,
+            x_min: bounds.x_min,
+            x_max: bounds.x_max,
+        }
+    }
+
+    /// Returns the equation evaluated at each sample, leaving out any x where it is undefined.
+    pub fn points(&self, engine: &graphing_engine::State) -> Vec<cgmath::Vector2<f32>> {
+        sample_xs(self.samples, self.x_min, self.x_max)
+            .filter_map(|x| Some(cgmath::vec2(x, engine.evaluate(self.id, x)?)))
+            .filter(|point| point.y.is_finite())
+            .collect()
+    }
+
+    /// Shows a window for picking the number of samples and the range of x, returning the button
+    /// pressed, if any.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<ExportAction> {
+        let mut action = None;
+
+        egui::Window::new(format!("Export {}", self.name))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.add(egui::DragValue::new(&mut self.samples).range(SAMPLE_RANGE).prefix("Samples: "));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.x_min).prefix("x from ").speed(0.1));
+                    ui.add(egui::DragValue::new(&mut self.x_max).prefix("to ").speed(0.1));
+                });
+                ui.horizontal(|ui| {
+                    let valid = self.x_min < self.x_max;
+                    if ui.add_enabled(valid, egui::Button::new("Export…")).clicked() {
+                        action = Some(ExportAction::Export);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        action = Some(ExportAction::Cancel);
+                    }
+                });
+            });
+
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_include_both_ends() {
+        assert_eq!(sample_xs(5, -1.0, 1.0).collect::<Vec<_>>(), [-1.0, -0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(sample_xs(1, 2.0, 3.0).collect::<Vec<_>>(), [2.0]);
+    }
+}
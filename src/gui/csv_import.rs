@@ -0,0 +1,95 @@
+use graphing_engine::{Chart, Marker};
+
+use crate::data::{Samples, Table};
+use crate::gui::series_list::{chart_name, marker_name};
+
+/// What was picked in the column prompt.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImportAction {
+    Import,
+    Cancel,
+}
+
+/// A table read from a dropped CSV file, which of its columns to plot as x and y and how to draw
+/// them.
+pub struct CsvImport {
+    name: String,
+    table: Table,
+    x: usize,
+    y: usize,
+    pub chart: Chart,
+    pub marker: Marker,
+    /// Join the points with lines in the order of the rows, as for a time series.
+    pub connected: bool,
+}
+
+impl CsvImport {
+    pub fn new(name: String, table: Table) -> Self {
+        Self {
+            name,
+            table,
+            x: 0,
+            y: 1,
+            chart: Chart::default(),
+            marker: Marker::default(),
+            connected: false,
+        }
+    }
+
+    /// Returns the points of the picked columns. Bars take the x column as the names of their
+    /// categories.
+    pub fn samples(&self) -> Samples {
+        match self.chart {
+            Chart::Bars => self.table.categories(self.x, self.y),
+            Chart::Points | Chart::BoxPlot | Chart::Line => self.table.samples(self.x, self.y),
+        }
+    }
+
+    /// Shows a window for picking the x and y columns, the chart, the marker and whether to join
+    /// the points, returning the button pressed, if any.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<ImportAction> {
+        let mut action = None;
+
+        egui::Window::new(format!("Import {}", self.name))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                egui::ComboBox::from_label("Chart")
+                    .selected_text(chart_name(self.chart))
+                    .show_ui(ui, |ui| {
+                        for chart in Chart::ALL {
+                            ui.selectable_value(&mut self.chart, chart, chart_name(chart));
+                        }
+                    });
+                let x_label = if self.chart == Chart::Bars { "Categories" } else { "x" };
+                for (label, column) in [(x_label, &mut self.x), ("y", &mut self.y)] {
+                    egui::ComboBox::from_label(label)
+                        .selected_text(&self.table.headers[*column])
+                        .show_ui(ui, |ui| {
+                            for (i, header) in self.table.headers.iter().enumerate() {
+                                ui.selectable_value(column, i, header);
+                            }
+                        });
+                }
+                egui::ComboBox::from_label("Marker")
+                    .selected_text(marker_name(self.marker))
+                    .show_ui(ui, |ui| {
+                        for marker in Marker::ALL {
+                            ui.selectable_value(&mut self.marker, marker, marker_name(marker));
+                        }
+                    });
+                ui.add_enabled(self.chart == Chart::Points, egui::Checkbox::new(&mut self.connected, "Connect points"));
+                ui.horizontal(|ui| {
+                    if ui.button("Import").clicked() {
+                        action = Some(ImportAction::Import);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        action = Some(ImportAction::Cancel);
+                    }
+                });
+            });
+
+        action
+    }
+}
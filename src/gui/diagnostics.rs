@@ -0,0 +1,48 @@
+/// Details about the GPU and startup options which users can copy into bug reports.
+pub struct Diagnostics {
+    entries: Vec<(&'static str, String)>,
+}
+
+impl Diagnostics {
+    pub fn new(adapter_info: &wgpu::AdapterInfo, safe_mode: bool) -> Self {
+        let entries = vec![
+            ("adapter", adapter_info.name.clone()),
+            ("backend", format!("{:?}", adapter_info.backend)),
+            ("device type", format!("{:?}", adapter_info.device_type)),
+            ("driver", format!("{} {}", adapter_info.driver, adapter_info.driver_info)),
+            ("safe mode", safe_mode.to_string()),
+        ];
+
+        Self {
+            entries,
+        }
+    }
+
+    pub fn report(&self) -> String {
+        self.entries.iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Shows the details, along with GPU timings if profiling is enabled.
+    pub fn show(&self, ui: &mut egui::Ui, gpu_timings: &[(&'static str, std::time::Duration)]) {
+        egui::CollapsingHeader::new("Diagnostics").show(ui, |ui| {
+            egui::Grid::new("diagnostics").show(ui, |ui| {
+                for (name, value) in &self.entries {
+                    ui.label(*name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+                for (scope, duration) in gpu_timings {
+                    ui.label(format!("gpu {scope}"));
+                    ui.label(format!("{:.3} ms", duration.as_secs_f64() * 1000.0));
+                    ui.end_row();
+                }
+            });
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text = self.report());
+            }
+        });
+    }
+}
@@ -0,0 +1,616 @@
+use graphing_engine::{format_number, Color, Dash, EngineError, LegendEntry, LineId, LineStyle, ParseError};
+use rand::Rng;
+
+use crate::gui::{AccessibleName, CsvExport};
+use crate::history::History;
+use crate::session;
+
+/// Precision evaluated values are shown to.
+const EVALUATE_STEP: f32 = 1e-4;
+
+/// Colours cycled through when assigning a palette to a selection.
+pub const PALETTE: [Color<f32>; 6] = [
+    Color { r: 0.78, g: 0.16, b: 0.16, a: 1.0 },
+    Color { r: 0.18, g: 0.45, b: 0.75, a: 1.0 },
+    Color { r: 0.20, g: 0.58, b: 0.24, a: 1.0 },
+    Color { r: 0.55, g: 0.27, b: 0.68, a: 1.0 },
+    Color { r: 0.93, g: 0.51, b: 0.11, a: 1.0 },
+    Color { r: 0.10, g: 0.10, b: 0.10, a: 1.0 },
+];
+
+pub struct EquationRow {
+    pub id: LineId,
+    pub text: String,
+    /// Name shown in the legend instead of the text, if not empty.
+    pub name: String,
+    pub color: Color<f32>,
+    pub visible: bool,
+    pub selected: bool,
+    pub style: LineStyle,
+    /// Why the text couldn't be parsed, if it can't.
+    pub error: Option<ParseError>,
+    /// The x the equation is evaluated at, if the evaluate field is open.
+    pub evaluate_at: Option<f32>,
+    /// Whether to mark the evaluated point on the curve.
+    pub marker: bool,
+    /// The history entry being shown while browsing it with the arrow keys.
+    pub history_index: Option<usize>,
+}
+
+/// A labelled point on a curve where its equation has been evaluated.
+pub struct Marker {
+    pub world: cgmath::Vector2<f32>,
+    pub color: egui::Color32,
+    pub text: String,
+}
+
+pub struct EquationList {
+    rows: Vec<EquationRow>,
+    anchor: Option<usize>,
+}
+
+/// Updates the selected flags of rows after the row at index is clicked.
+///
+/// A plain click selects only the clicked row, ctrl toggles it, and shift selects the range
+/// between the anchor and the clicked row. Returns the new anchor.
+fn select(
+    selected: &mut [bool],
+    index: usize,
+    anchor: Option<usize>,
+    modifiers: egui::Modifiers,
+) -> Option<usize> {
+    if modifiers.shift {
+        let start = anchor.unwrap_or(index);
+        let (low, high) = (start.min(index), start.max(index));
+        for (i, s) in selected.iter_mut().enumerate() {
+            *s = (low..=high).contains(&i);
+        }
+        Some(start)
+    } else if modifiers.command {
+        selected[index] = !selected[index];
+        Some(index)
+    } else {
+        for (i, s) in selected.iter_mut().enumerate() {
+            *s = i == index;
+        }
+        Some(index)
+    }
+}
+
+/// Shows a menu of stroke controls for a line, returning true if the style was changed.
+fn line_style(ui: &mut egui::Ui, style: &mut LineStyle) -> bool {
+    let before = *style;
+
+    ui.menu_button("Style", |ui| {
+        ui.add(egui::Slider::new(&mut style.width, 0.5..=10.0).text("Width"));
+        egui::ComboBox::from_label("Dash")
+            .selected_text(format!("{:?}", style.dash))
+            .show_ui(ui, |ui| {
+                for dash in [Dash::Solid, Dash::Dashed, Dash::Dotted] {
+                    ui.selectable_value(&mut style.dash, dash, format!("{dash:?}"));
+                }
+            });
+        ui.add(egui::Slider::new(&mut style.opacity, 0.0..=1.0).text("Opacity"));
+    });
+
+    *style != before
+}
+
+/// Logs a failure to change the line of a row. Each row has its own line, so this is a bug rather
+/// than anything to tell the user about.
+fn log_error(result: Result<(), EngineError>) {
+    if let Err(e) = result {
+        log::error!("changing a line failed: {e}");
+    }
+}
+
+/// Returns why the equation given to update_line couldn't be parsed, if it couldn't.
+fn parse_error(result: Result<(), EngineError>) -> Option<ParseError> {
+    match result {
+        Err(EngineError::Parse(error)) => Some(error),
+        result => {
+            log_error(result);
+            None
+        }
+    }
+}
+
+/// Lays out an equation with the part at fault for error highlighted, followed by the message.
+fn error_layout(text: &str, error: &ParseError, visuals: &egui::Visuals) -> egui::text::LayoutJob {
+    let plain = egui::TextFormat {
+        color: visuals.text_color(),
+        font_id: egui::FontId::monospace(12.0),
+        ..Default::default()
+    };
+    let highlighted = egui::TextFormat {
+        background: visuals.error_fg_color.gamma_multiply(0.4),
+        ..plain.clone()
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    job.append(&text[..error.span.start], 0.0, plain.clone());
+    job.append(&text[error.span.clone()], 0.0, highlighted);
+    job.append(&text[error.span.end..], 0.0, plain);
+    job.append(&error.message, 8.0, egui::TextFormat {
+        color: visuals.error_fg_color,
+        ..Default::default()
+    });
+    job
+}
+
+/// Returns a random opaque colour for a new row.
+fn random_color() -> Color<f32> {
+    let r = rand::thread_rng().gen_range(0.0..=1.0);
+    let g = rand::thread_rng().gen_range(0.0..=1.0);
+    let b = rand::thread_rng().gen_range(0.0..=1.0);
+    Color { r, g, b, a: 1.0 }
+}
+
+/// Splits pasted text into the equation on each of its lines, skipping blank ones. Returns
+/// nothing if there is only one, so it is pasted into the box as usual.
+fn pasted_equations(text: &str) -> Option<Vec<&str>> {
+    let lines = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    (lines.len() > 1).then_some(lines)
+}
+
+/// Takes a paste of several equations from this frame's input if the text box with id has focus,
+/// so the box doesn't join them into one line.
+fn take_paste(ui: &egui::Ui, id: egui::Id) -> Option<String> {
+    if !ui.memory(|memory| memory.has_focus(id)) {
+        return None;
+    }
+    ui.input_mut(|input| {
+        let index = input.events.iter().position(|event| {
+            matches!(event, egui::Event::Paste(text) if pasted_equations(text).is_some())
+        })?;
+        match input.events.remove(index) {
+            egui::Event::Paste(text) => Some(text),
+            _ => None,
+        }
+    })
+}
+
+/// Returns the selection anchor once the row at index is removed, following the row it was on.
+fn anchor_after_removal(anchor: Option<usize>, index: usize) -> Option<usize> {
+    match anchor {
+        Some(a) if a == index => None,
+        Some(a) if a > index => Some(a - 1),
+        anchor => anchor,
+    }
+}
+
+impl EquationList {
+    pub fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            anchor: None,
+        }
+    }
+
+    /// Shows a row for each equation. Right clicking a row's number offers to export samples of
+    /// it, returning the export to prompt for, if picked.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        engine: &mut graphing_engine::State,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        history: &mut History,
+    ) -> Option<CsvExport> {
+        ui.label("Equations");
+        let add = ui.button("+")
+            .on_hover_text("Add equation")
+            .accessible_name(egui::WidgetType::Button, "Add equation");
+        if add.clicked() {
+            let row = Self::new_row(String::new(), engine, device);
+            self.rows.push(row);
+        }
+
+        if self.rows.iter().any(|row| row.selected) {
+            self.bulk_toolbar(ui, engine, queue);
+        }
+
+        let modifiers = ui.input(|i| i.modifiers);
+        let mut clicked = None;
+        let mut removed = None;
+        let mut export = None;
+        let mut pasted = None;
+
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let tint = match row.error {
+                Some(_) => ui.visuals().error_fg_color.gamma_multiply(0.15),
+                None => egui::Color32::TRANSPARENT,
+            };
+            egui::Frame::none().fill(tint).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let handle = egui::RichText::new(format!("{}", i + 1)).color(egui::Rgba::from_rgba_unmultiplied(
+                        row.color.r,
+                        row.color.g,
+                        row.color.b,
+                        if row.visible { 1.0 } else { 0.3 },
+                    ));
+                    let name = format!("Equation {}", i + 1);
+                    let handle = ui.selectable_label(row.selected, handle)
+                        .accessible_name(egui::WidgetType::SelectableLabel, format!("Select {name}"));
+                    if handle.clicked() {
+                        clicked = Some(i);
+                    }
+                    handle.context_menu(|ui| {
+                        let enabled = row.error.is_none() && !row.text.trim().is_empty();
+                        if ui.add_enabled(enabled, egui::Button::new("Export samples to CSV…")).clicked() {
+                            export = Some(CsvExport::new(row.id, row.text.clone(), engine.visible_bounds()));
+                            ui.close_menu();
+                        }
+                    });
+
+                    let text_id = egui::Id::new(("equation text", row.id));
+                    if let Some(text) = take_paste(ui, text_id) {
+                        pasted = Some((i, text));
+                    }
+                    let response = ui.add(egui::TextEdit::singleline(&mut row.text).id(text_id))
+                        .accessible_name(egui::WidgetType::TextEdit, &name);
+                    let mut changed = response.changed();
+                    if changed {
+                        row.history_index = None;
+                    }
+
+                    // the arrow keys only browse the history in an empty box, or one already
+                    // showing an entry, so they don't take over while editing
+                    if response.has_focus() && (row.text.is_empty() || row.history_index.is_some()) {
+                        let index = ui.input(|i| {
+                            if i.key_pressed(egui::Key::ArrowUp) {
+                                history.older(row.history_index)
+                            } else if i.key_pressed(egui::Key::ArrowDown) {
+                                history.newer(row.history_index)
+                            } else {
+                                row.history_index
+                            }
+                        });
+                        if index != row.history_index {
+                            row.history_index = index;
+                            row.text = index.and_then(|i| history.entry(i)).unwrap_or_default().to_owned();
+                            changed = true;
+                        }
+                    }
+
+                    if let Some(text) = history.show_menu(ui) {
+                        row.text = text;
+                        row.history_index = None;
+                        changed = true;
+                    }
+
+                    if changed {
+                        row.error = parse_error(engine.update_line(row.id, &row.text));
+                    }
+                    if response.lost_focus() && row.error.is_none() {
+                        history.record(&row.text);
+                    }
+
+                    ui.add(egui::TextEdit::singleline(&mut row.name).hint_text("name").desired_width(60.0))
+                        .on_hover_text("Name shown in the legend")
+                        .accessible_name(egui::WidgetType::TextEdit, format!("Name of {name}"));
+
+                    if engine.is_line_pending(row.id) {
+                        ui.spinner();
+                    }
+
+                    let mut evaluate = row.evaluate_at.is_some();
+                    let toggle = ui.toggle_value(&mut evaluate, "f(x)")
+                        .on_hover_text("Evaluate at a point")
+                        .accessible_name(egui::WidgetType::SelectableLabel, format!("Evaluate {name}"));
+                    if toggle.changed() {
+                        row.evaluate_at = evaluate.then_some(0.0);
+                    }
+
+                    if line_style(ui, &mut row.style) {
+                        log_error(engine.set_line_style(queue, row.id, row.style));
+                    }
+
+                    let delete = ui.small_button("✖")
+                        .on_hover_text("Delete")
+                        .accessible_name(egui::WidgetType::Button, format!("Delete {name}"));
+                    if delete.clicked() {
+                        removed = Some(i);
+                    }
+                });
+
+                if let Some(error) = &row.error {
+                    ui.label(error_layout(&row.text, error, ui.visuals()));
+                }
+
+                if let Some(x) = &mut row.evaluate_at {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(x).prefix("x = ").speed(0.1));
+                        match engine.evaluate(row.id, *x) {
+                            Some(y) => ui.label(format!("y = {}", format_number(y, EVALUATE_STEP))),
+                            None => ui.weak("undefined"),
+                        };
+                        ui.checkbox(&mut row.marker, "Marker");
+                    });
+                }
+            });
+        }
+
+        if let Some(index) = removed {
+            self.remove(index, engine);
+            // indices after the removed row have shifted, so a click or paste in the same frame
+            // is dropped
+            clicked = None;
+            pasted = None;
+        }
+
+        if let Some((index, text)) = pasted {
+            self.paste(index, &text, engine, device);
+        }
+
+        if let Some(index) = clicked {
+            let mut selected = self.rows.iter().map(|row| row.selected).collect::<Vec<_>>();
+            self.anchor = select(&mut selected, index, self.anchor, modifiers);
+            for (row, s) in self.rows.iter_mut().zip(selected) {
+                row.selected = s;
+            }
+        }
+        export
+    }
+
+    /// Returns a marker for each visible row with an evaluated point marked on its curve.
+    pub fn markers(&self, engine: &graphing_engine::State) -> Vec<Marker> {
+        self.rows.iter()
+            .filter(|row| row.visible && row.marker)
+            .filter_map(|row| {
+                let x = row.evaluate_at?;
+                let y = engine.evaluate(row.id, x)?;
+                Some(Marker {
+                    world: cgmath::vec2(x, y),
+                    color: egui::Rgba::from_rgba_unmultiplied(row.color.r, row.color.g, row.color.b, 1.0).into(),
+                    text: format!("({}, {})", format_number(x, EVALUATE_STEP), format_number(y, EVALUATE_STEP)),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the row selected, if exactly one is.
+    pub fn selected_row(&self) -> Option<&EquationRow> {
+        let mut selected = self.rows.iter().filter(|row| row.selected);
+        match (selected.next(), selected.next()) {
+            (Some(row), None) => Some(row),
+            _ => None,
+        }
+    }
+
+    /// Returns the legend entry for each visible row with a valid equation, in order. Rows are
+    /// listed by name, or by their equation if they have none.
+    pub fn legend_entries(&self) -> Vec<LegendEntry> {
+        self.rows.iter()
+            .filter(|row| row.visible && row.error.is_none() && !row.text.trim().is_empty())
+            .map(|row| LegendEntry {
+                name: if row.name.trim().is_empty() { row.text.trim() } else { row.name.trim() }.to_owned(),
+                color: row.color,
+            })
+            .collect()
+    }
+
+    /// Returns the text of each equation, in order.
+    pub fn equations(&self) -> impl Iterator<Item = &str> {
+        self.rows.iter().map(|row| row.text.as_str())
+    }
+
+    /// Returns each equation with its colour, visibility and style, in order, for saving.
+    pub fn saved(&self) -> Vec<session::Equation> {
+        self.rows.iter()
+            .map(|row| session::Equation {
+                text: row.text.clone(),
+                name: row.name.clone(),
+                color: row.color,
+                visible: row.visible,
+                style: row.style,
+            })
+            .collect()
+    }
+
+    /// Replaces every row, and its line in the engine, with the saved equations.
+    pub fn restore(
+        &mut self,
+        equations: Vec<session::Equation>,
+        engine: &mut graphing_engine::State,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        for row in self.rows.drain(..) {
+            log_error(engine.remove_line(row.id));
+        }
+        self.anchor = None;
+
+        for equation in equations {
+            self.add(equation, engine, device, queue);
+        }
+    }
+
+    /// Adds a row for equation after the others, plotting its line.
+    pub fn add(
+        &mut self,
+        equation: session::Equation,
+        engine: &mut graphing_engine::State,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        let id = engine.add_line(device, Vec::new(), equation.color);
+        let error = parse_error(engine.update_line(id, &equation.text));
+        log_error(engine.set_line_visible(id, equation.visible));
+        log_error(engine.set_line_style(queue, id, equation.style));
+        self.rows.push(EquationRow {
+            id,
+            text: equation.text,
+            name: equation.name,
+            color: equation.color,
+            visible: equation.visible,
+            selected: false,
+            style: equation.style,
+            error,
+            evaluate_at: None,
+            marker: false,
+            history_index: None,
+        });
+    }
+
+    /// Returns a new row for text with a random colour, plotting its line.
+    fn new_row(text: String, engine: &mut graphing_engine::State, device: &wgpu::Device) -> EquationRow {
+        let color = random_color();
+        let id = engine.add_line(device, Vec::new(), color);
+        let error = if text.is_empty() { None } else { parse_error(engine.update_line(id, &text)) };
+        EquationRow {
+            id,
+            text,
+            name: String::new(),
+            color,
+            visible: true,
+            selected: false,
+            style: LineStyle::default(),
+            error,
+            evaluate_at: None,
+            marker: false,
+            history_index: None,
+        }
+    }
+
+    /// Pastes each line of text as an equation of its own, parsed on its own row. The first
+    /// replaces the row at index if that is empty, and the rest are inserted after it.
+    fn paste(&mut self, index: usize, text: &str, engine: &mut graphing_engine::State, device: &wgpu::Device) {
+        let Some(lines) = pasted_equations(text) else {
+            return;
+        };
+        let mut lines = lines.into_iter();
+        let row = &mut self.rows[index];
+        if row.text.trim().is_empty() {
+            if let Some(line) = lines.next() {
+                row.text = line.to_owned();
+                row.error = parse_error(engine.update_line(row.id, &row.text));
+                row.history_index = None;
+            }
+        }
+
+        let mut inserted = 0;
+        for line in lines {
+            let row = Self::new_row(line.to_owned(), engine, device);
+            inserted += 1;
+            self.rows.insert(index + inserted, row);
+        }
+        self.anchor = self.anchor.map(|a| if a > index { a + inserted } else { a });
+    }
+
+    /// Removes the row at index and its line, returning false if there is no such row.
+    pub fn remove(&mut self, index: usize, engine: &mut graphing_engine::State) -> bool {
+        if index >= self.rows.len() {
+            return false;
+        }
+        let row = self.rows.remove(index);
+        log_error(engine.remove_line(row.id));
+        self.anchor = anchor_after_removal(self.anchor, index);
+        true
+    }
+
+    /// Returns why the equation at index couldn't be parsed, if there is such a row and it
+    /// couldn't.
+    pub fn error(&self, index: usize) -> Option<&ParseError> {
+        self.rows.get(index)?.error.as_ref()
+    }
+
+    /// Replaces the text of the equation at index, returning false if there is no such row.
+    pub fn set_equation(&mut self, index: usize, text: String, engine: &mut graphing_engine::State) -> bool {
+        match self.rows.get_mut(index) {
+            Some(row) => {
+                row.error = parse_error(engine.update_line(row.id, &text));
+                row.text = text;
+                true
+            }
+            None => false
+        }
+    }
+
+    fn bulk_toolbar(&mut self, ui: &mut egui::Ui, engine: &mut graphing_engine::State, queue: &wgpu::Queue) {
+        ui.horizontal(|ui| {
+            let count = self.rows.iter().filter(|row| row.selected).count();
+            ui.label(format!("{count} selected"));
+
+            if ui.button("Hide").clicked() {
+                self.set_selected_visible(engine, false);
+            }
+            if ui.button("Show").clicked() {
+                self.set_selected_visible(engine, true);
+            }
+            if ui.button("Palette").clicked() {
+                for (row, color) in self.rows.iter_mut().filter(|row| row.selected).zip(PALETTE.iter().cycle()) {
+                    row.color = *color;
+                    log_error(engine.set_line_color(queue, row.id, row.color));
+                }
+            }
+            if ui.button("Delete").clicked() {
+                for row in self.rows.iter().filter(|row| row.selected) {
+                    log_error(engine.remove_line(row.id));
+                }
+                self.rows.retain(|row| !row.selected);
+                self.anchor = None;
+            }
+        });
+    }
+
+    fn set_selected_visible(&mut self, engine: &mut graphing_engine::State, visible: bool) {
+        for row in self.rows.iter_mut().filter(|row| row.selected) {
+            row.visible = visible;
+            log_error(engine.set_line_visible(row.id, visible));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_after_removal() {
+        assert_eq!(anchor_after_removal(Some(3), 1), Some(2));
+        assert_eq!(anchor_after_removal(Some(1), 3), Some(1));
+        assert_eq!(anchor_after_removal(Some(2), 2), None);
+        assert_eq!(anchor_after_removal(None, 0), None);
+    }
+
+    #[test]
+    fn test_pasted_equations() {
+        assert_eq!(pasted_equations("x^2\n2x + 1\r\n\n  3  \n"), Some(vec!["x^2", "2x + 1", "3"]));
+        // a single equation is left for the text box to paste
+        assert_eq!(pasted_equations("x^2 - 1\n"), None);
+        assert_eq!(pasted_equations("\n\n"), None);
+    }
+
+    #[test]
+    fn test_select_plain_click() {
+        let mut selected = [true, false, true];
+        let anchor = select(&mut selected, 1, None, egui::Modifiers::NONE);
+
+        assert_eq!(selected, [false, true, false]);
+        assert_eq!(anchor, Some(1));
+    }
+
+    #[test]
+    fn test_select_command_toggles() {
+        let mut selected = [true, false, false];
+        select(&mut selected, 2, Some(0), egui::Modifiers::COMMAND);
+        assert_eq!(selected, [true, false, true]);
+
+        select(&mut selected, 0, Some(2), egui::Modifiers::COMMAND);
+        assert_eq!(selected, [false, false, true]);
+    }
+
+    #[test]
+    fn test_select_shift_range() {
+        let mut selected = [false; 5];
+        let anchor = select(&mut selected, 3, Some(1), egui::Modifiers::SHIFT);
+
+        assert_eq!(selected, [false, true, true, true, false]);
+        // the anchor stays put so the range can be extended again
+        assert_eq!(anchor, Some(1));
+    }
+}
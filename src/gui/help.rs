@@ -0,0 +1,69 @@
+use crate::gui::AccessibleName;
+
+/// Keys for working the panel without a mouse, which egui and the app handle rather than the
+/// graph.
+const PANEL_KEYS: [(&str, &str); 7] = [
+    ("Tab / Shift+Tab", "Move between controls"),
+    ("Enter / Space", "Press the focused button or pick the focused item"),
+    ("← / →", "Adjust the focused slider"),
+    ("Esc", "Leave the focused control, giving the arrow keys back to the graph"),
+    ("↑ / ↓", "Recall earlier expressions in an empty equation"),
+    ("Ctrl+= / Ctrl+- / Ctrl+0", "Scale the panel up, down or back to normal"),
+    ("Delete", "Remove the selected points"),
+];
+
+/// A window documenting the equation syntax and the controls of the graph, built from the
+/// engine's own tables so it matches what is actually supported.
+pub struct Help {
+    open: bool,
+}
+
+impl Help {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+        }
+    }
+
+    /// Shows the button which opens and closes the help.
+    pub fn show_button(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "?").on_hover_text("Help").accessible_name(egui::WidgetType::SelectableLabel, "Help");
+    }
+
+    /// Shows the help window, if it is open, with the graph's keys as bindings has them.
+    pub fn show(&mut self, ctx: &egui::Context, bindings: &graphing_engine::KeyBindings) {
+        egui::Window::new("Help")
+            .open(&mut self.open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.heading("Equations");
+                egui::Grid::new("help syntax").striped(true).show(ui, |ui| {
+                    for (example, description) in graphing_engine::EQUATION_SYNTAX {
+                        ui.monospace(example);
+                        ui.label(description);
+                        ui.end_row();
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.heading("Controls");
+                egui::Grid::new("help controls").striped(true).show(ui, |ui| {
+                    for (control, description) in bindings.controls_help() {
+                        ui.strong(control);
+                        ui.label(description);
+                        ui.end_row();
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.heading("Keyboard");
+                egui::Grid::new("help keyboard").striped(true).show(ui, |ui| {
+                    for (keys, description) in PANEL_KEYS {
+                        ui.strong(keys);
+                        ui.label(description);
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+}
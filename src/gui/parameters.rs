@@ -0,0 +1,119 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// A slider for a free parameter of the equations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: char,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl Parameter {
+    fn new(name: char) -> Self {
+        // 1 matches the value the parser gives parameters it has no value for
+        Self {
+            name,
+            value: 1.0,
+            min: -10.0,
+            max: 10.0,
+            step: 0.1,
+        }
+    }
+}
+
+/// Sliders for the parameters used across all equations, such as a and b in ax + b.
+pub struct Parameters {
+    list: Vec<Parameter>,
+}
+
+/// Keeps the parameters named in names, in order, adding any which are missing. Returns the names
+/// of those added.
+fn merge(list: &mut Vec<Parameter>, names: &BTreeSet<char>) -> Vec<char> {
+    list.retain(|parameter| names.contains(&parameter.name));
+
+    let added = names.iter()
+        .copied()
+        .filter(|name| !list.iter().any(|parameter| parameter.name == *name))
+        .collect::<Vec<_>>();
+    list.extend(added.iter().copied().map(Parameter::new));
+    list.sort_by_key(|parameter| parameter.name);
+
+    added
+}
+
+impl Parameters {
+    pub fn new() -> Self {
+        Self {
+            list: Vec::new(),
+        }
+    }
+
+    /// Adds sliders for parameters the equations have started using and drops those no longer used.
+    pub fn sync<'a>(&mut self, equations: impl Iterator<Item = &'a str>, engine: &mut graphing_engine::State) {
+        let names = equations.flat_map(graphing_engine::equation_parameters).collect();
+        for name in merge(&mut self.list, &names) {
+            engine.set_parameter(name, Parameter::new(name).value);
+        }
+    }
+
+    /// Returns the parameters, in order of name.
+    pub fn list(&self) -> &[Parameter] {
+        &self.list
+    }
+
+    /// Replaces the parameters with those in list, passing their values on to the engine.
+    pub fn restore(&mut self, mut list: Vec<Parameter>, engine: &mut graphing_engine::State) {
+        list.sort_by_key(|parameter| parameter.name);
+        for parameter in &list {
+            engine.set_parameter(parameter.name, parameter.value);
+        }
+        self.list = list;
+    }
+
+    /// Shows a slider for each parameter, with its range and step, if there are any.
+    pub fn show(&mut self, ui: &mut egui::Ui, engine: &mut graphing_engine::State) {
+        if self.list.is_empty() {
+            return;
+        }
+
+        egui::CollapsingHeader::new("Parameters").default_open(true).show(ui, |ui| {
+            for parameter in &mut self.list {
+                ui.horizontal(|ui| {
+                    let label = ui.label(parameter.name.to_string());
+                    let slider = egui::Slider::new(&mut parameter.value, parameter.min..=parameter.max)
+                        .step_by(parameter.step as f64);
+                    if ui.add(slider).labelled_by(label.id).changed() {
+                        engine.set_parameter(parameter.name, parameter.value);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut parameter.min).prefix("min ").speed(0.1));
+                    ui.add(egui::DragValue::new(&mut parameter.max).prefix("max ").speed(0.1));
+                    ui.add(egui::DragValue::new(&mut parameter.step).prefix("step ").speed(0.01).range(0.0..=f32::MAX));
+                });
+                parameter.max = parameter.max.max(parameter.min);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut list = vec![Parameter { value: 4.0, ..Parameter::new('b') }, Parameter::new('c')];
+
+        let added = merge(&mut list, &BTreeSet::from(['a', 'b']));
+
+        assert_eq!(added, ['a']);
+        assert_eq!(list.iter().map(|parameter| parameter.name).collect::<Vec<_>>(), ['a', 'b']);
+        // parameters still in use keep their values
+        assert_eq!(list[1].value, 4.0);
+    }
+}
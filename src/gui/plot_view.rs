@@ -0,0 +1,271 @@
+use graphing_engine::{format_number, Crosshair, HoveredPoint, RenderTargetConfig};
+
+use crate::gui::{AccessibleName, GuiRenderer};
+
+/// An offscreen texture the graph is rendered into, which the GUI then draws as an image.
+///
+/// Rendering separately from the window surface lets the graph be drawn at a different
+/// resolution to the rest of the interface and read back without the panel on top.
+pub struct PlotView {
+    texture: Option<(wgpu::Texture, wgpu::TextureView)>,
+    texture_id: Option<egui::TextureId>,
+    /// The area of the window the plot covers, in physical pixels.
+    rect: egui::Rect,
+    /// Texture pixels per physical pixel of the window.
+    resolution_scale: f32,
+}
+
+/// A button pressed in the zoom controls floating over the plot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ZoomControl {
+    In,
+    Out,
+    Home,
+}
+
+/// Returns the texture size for a plot covering size physical pixels at the resolution scale,
+/// which is never smaller than a single pixel.
+fn texture_size(size: egui::Vec2, resolution_scale: f32) -> winit::dpi::PhysicalSize<u32> {
+    winit::dpi::PhysicalSize::new(
+        ((size.x * resolution_scale).round() as u32).max(1),
+        ((size.y * resolution_scale).round() as u32).max(1),
+    )
+}
+
+impl PlotView {
+    pub fn new() -> Self {
+        Self {
+            texture: None,
+            texture_id: None,
+            rect: egui::Rect::NOTHING,
+            resolution_scale: 1.0,
+        }
+    }
+
+    /// Makes sure the texture matches rect, in logical points, at the resolution scale and
+    /// returns its size.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        gui_renderer: &mut GuiRenderer,
+        target: &RenderTargetConfig,
+        rect: egui::Rect,
+        pixels_per_point: f32,
+        resolution_scale: f32,
+    ) -> winit::dpi::PhysicalSize<u32> {
+        self.rect = rect * pixels_per_point;
+        self.resolution_scale = resolution_scale;
+        let size = texture_size(self.rect.size(), resolution_scale);
+
+        let unchanged = self.texture.as_ref().is_some_and(|(texture, _)| {
+            texture.width() == size.width && texture.height() == size.height
+        });
+        if unchanged {
+            return size;
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Plot Texture"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: target.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: target.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        self.texture_id = Some(match self.texture_id {
+            Some(id) => {
+                gui_renderer.update_native_texture(device, &view, id);
+                id
+            }
+            None => gui_renderer.register_native_texture(device, &view),
+        });
+        if let Some((old, _)) = self.texture.replace((texture, view)) {
+            old.destroy();
+        }
+
+        size
+    }
+
+    pub fn view(&self) -> Option<&wgpu::TextureView> {
+        self.texture.as_ref().map(|(_, view)| view)
+    }
+
+    /// Draws the plot texture behind the rest of the interface at rect, in logical points.
+    pub fn paint(&self, ctx: &egui::Context, rect: egui::Rect) {
+        let Some(texture_id) = self.texture_id else {
+            return;
+        };
+
+        ctx.layer_painter(egui::LayerId::background()).image(
+            texture_id,
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+    }
+
+    /// Shows zoom in, zoom out and home buttons in the top right corner of the plot, for mouse
+    /// and touch users without a scroll wheel or pinch.
+    pub fn zoom_controls(&self, ctx: &egui::Context) -> Option<ZoomControl> {
+        let plot = self.rect / ctx.pixels_per_point();
+        let mut pressed = None;
+
+        egui::Area::new(egui::Id::new("zoom controls"))
+            .pivot(egui::Align2::RIGHT_TOP)
+            .fixed_pos(plot.right_top() + egui::vec2(-8.0, 8.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.vertical_centered_justified(|ui| {
+                        let buttons = [
+                            ("+", "Zoom in", ZoomControl::In),
+                            ("−", "Zoom out", ZoomControl::Out),
+                            ("⌂", "Reset view", ZoomControl::Home),
+                        ];
+                        for (text, hover, control) in buttons {
+                            let button = egui::Button::new(egui::RichText::new(text).size(16.0)).min_size(egui::vec2(24.0, 24.0));
+                            if ui.add(button).on_hover_text(hover).accessible_name(egui::WidgetType::Button, hover).clicked() {
+                                pressed = Some(control);
+                            }
+                        }
+                    });
+                });
+            });
+
+        pressed
+    }
+
+    /// Converts a position in the texture to logical points in the window.
+    fn to_points(&self, ctx: &egui::Context, p: winit::dpi::PhysicalPosition<f32>) -> egui::Pos2 {
+        let physical = self.rect.min + egui::vec2(p.x, p.y) / self.resolution_scale;
+        (physical.to_vec2() / ctx.pixels_per_point()).to_pos2()
+    }
+
+    /// Draws a box zoom selection, given in texture pixels, over the plot.
+    pub fn paint_selection(
+        &self,
+        ctx: &egui::Context,
+        (a, b): (winit::dpi::PhysicalPosition<f32>, winit::dpi::PhysicalPosition<f32>),
+    ) {
+        let rect = egui::Rect::from_two_pos(self.to_points(ctx, a), self.to_points(ctx, b));
+
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("plot selection")));
+        let color = ctx.style().visuals.selection.bg_fill;
+        painter.rect_filled(rect, 0.0, color.gamma_multiply(0.2));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, color));
+    }
+
+    /// Draws guide lines through the pointer, with the x coordinate labelled on the x axis and
+    /// the y coordinate on the y axis. Axes off the edge of the plot are labelled at the edge.
+    pub fn paint_crosshair(&self, ctx: &egui::Context, crosshair: &Crosshair) {
+        let plot = self.rect / ctx.pixels_per_point();
+        let cursor = self.to_points(ctx, crosshair.position);
+        let origin = self.to_points(ctx, crosshair.origin);
+
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("plot crosshair")));
+        let visuals = &ctx.style().visuals;
+        let stroke = egui::Stroke::new(1.0, visuals.widgets.noninteractive.fg_stroke.color.gamma_multiply(0.5));
+        painter.vline(cursor.x, plot.y_range(), stroke);
+        painter.hline(plot.x_range(), cursor.y, stroke);
+
+        let font = egui::FontId::monospace(12.0);
+        let label = |pos: egui::Pos2, anchor: egui::Align2, text: String| {
+            let galley = painter.layout_no_wrap(text, font.clone(), visuals.text_color());
+            let rect = anchor.anchor_size(pos, galley.size()).expand(2.0);
+            painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+            painter.galley(rect.min + egui::vec2(2.0, 2.0), galley, visuals.text_color());
+        };
+        let axis_y = origin.y.clamp(plot.top(), plot.bottom() - 16.0);
+        let axis_x = origin.x.clamp(plot.left(), plot.right() - 16.0);
+        label(egui::pos2(cursor.x + 4.0, axis_y + 4.0), egui::Align2::LEFT_TOP, format_number(crosshair.world.x, crosshair.world_per_pixel));
+        label(egui::pos2(axis_x + 4.0, cursor.y - 4.0), egui::Align2::LEFT_BOTTOM, format_number(crosshair.world.y, crosshair.world_per_pixel));
+    }
+
+    /// Draws a dot at position, given in texture pixels, with text beside it.
+    pub fn paint_marker(&self, ctx: &egui::Context, position: winit::dpi::PhysicalPosition<f32>, color: egui::Color32, text: &str) {
+        let pos = self.to_points(ctx, position);
+        if !(self.rect / ctx.pixels_per_point()).contains(pos) {
+            return;
+        }
+
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("plot markers")));
+        let visuals = &ctx.style().visuals;
+        painter.circle(pos, 4.0, color, egui::Stroke::new(1.0, visuals.extreme_bg_color));
+
+        let galley = painter.layout_no_wrap(text.to_owned(), egui::FontId::monospace(12.0), visuals.text_color());
+        let rect = egui::Align2::LEFT_BOTTOM.anchor_size(pos + egui::vec2(6.0, -6.0), galley.size()).expand(2.0);
+        painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+        painter.galley(rect.min + egui::vec2(2.0, 2.0), galley, visuals.text_color());
+    }
+
+    /// Draws a ring around a selected point at position, given in texture pixels, with its
+    /// coordinates beside it. The radius of the point is in logical pixels.
+    pub fn paint_point_selection(&self, ctx: &egui::Context, position: winit::dpi::PhysicalPosition<f32>, radius: f32, text: &str) {
+        let pos = self.to_points(ctx, position);
+        if !(self.rect / ctx.pixels_per_point()).contains(pos) {
+            return;
+        }
+
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("plot selected points")));
+        let visuals = &ctx.style().visuals;
+        painter.circle_stroke(pos, radius + 3.0, egui::Stroke::new(2.0, visuals.selection.bg_fill));
+
+        let galley = painter.layout_no_wrap(text.to_owned(), egui::FontId::monospace(12.0), visuals.text_color());
+        let rect = egui::Align2::LEFT_TOP.anchor_size(pos + egui::vec2(radius + 6.0, radius + 6.0), galley.size()).expand(2.0);
+        painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+        painter.galley(rect.min + egui::vec2(2.0, 2.0), galley, visuals.text_color());
+    }
+
+    /// Shows a tooltip beside a point under the pointer with its exact coordinates, and the name
+    /// of its series if it has one.
+    pub fn show_point_tooltip(&self, ctx: &egui::Context, hovered: &HoveredPoint) {
+        let pos = self.to_points(ctx, hovered.position);
+        let id = egui::Id::new("point tooltip");
+        egui::show_tooltip_at(ctx, egui::LayerId::background(), id, pos + egui::vec2(8.0, 8.0), |ui| {
+            if let Some(name) = hovered.series.as_deref().filter(|name| !name.trim().is_empty()) {
+                ui.strong(name);
+            }
+            ui.monospace(format!("({}, {})", hovered.world.x, hovered.world.y));
+        });
+    }
+
+    /// Converts a position in the window, in physical pixels, to a position in the texture.
+    pub fn to_texture_space(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.rect.min.x) * self.resolution_scale,
+            (y - self.rect.min.y) * self.resolution_scale,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texture_size() {
+        assert_eq!(texture_size(egui::vec2(800.0, 600.0), 1.0), winit::dpi::PhysicalSize::new(800, 600));
+        assert_eq!(texture_size(egui::vec2(800.0, 600.0), 0.5), winit::dpi::PhysicalSize::new(400, 300));
+        assert_eq!(texture_size(egui::vec2(0.0, 600.0), 2.0), winit::dpi::PhysicalSize::new(1, 1200));
+    }
+
+    #[test]
+    fn test_to_texture_space() {
+        let view = PlotView {
+            texture: None,
+            texture_id: None,
+            rect: egui::Rect::from_min_size(egui::pos2(200.0, 0.0), egui::vec2(600.0, 600.0)),
+            resolution_scale: 0.5,
+        };
+
+        assert_eq!(view.to_texture_space(200.0, 0.0), (0.0, 0.0));
+        assert_eq!(view.to_texture_space(500.0, 100.0), (150.0, 50.0));
+    }
+}
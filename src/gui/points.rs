@@ -0,0 +1,107 @@
+use graphing_engine::{Anchor, Annotation, AnnotationText, State};
+
+use crate::gui::AccessibleName;
+
+fn anchor_name(anchor: Anchor) -> &'static str {
+    match anchor {
+        Anchor::Above => "Above",
+        Anchor::Below => "Below",
+        Anchor::Left => "Left",
+        Anchor::Right => "Right",
+        Anchor::TopLeft => "Top left",
+        Anchor::TopRight => "Top right",
+        Anchor::BottomLeft => "Bottom left",
+        Anchor::BottomRight => "Bottom right",
+    }
+}
+
+/// Shows how many points are on the graph with buttons to remove the selected ones or all of
+/// them, and the label of the selected point when there is just one. Delete also removes the
+/// selected points while no text box has focus.
+pub fn show(ui: &mut egui::Ui, engine: &mut State) {
+    let count = engine.points().len();
+    if count == 0 {
+        return;
+    }
+    let selected = engine.selected_points().len();
+
+    ui.horizontal(|ui| {
+        ui.label(if count == 1 { "1 point".to_string() } else { format!("{count} points") });
+        if selected > 1 {
+            ui.weak(format!("{selected} selected"));
+        }
+
+        let remove = ui.add_enabled(selected > 0, egui::Button::new("Remove"))
+            .on_hover_text("Remove the selected points")
+            .on_disabled_hover_text("Click a point on the graph to select it, with shift held to select more");
+        let delete_pressed = !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_pressed(egui::Key::Delete));
+        if remove.clicked() || delete_pressed {
+            engine.remove_selected_points();
+        }
+
+        if ui.button("Clear").on_hover_text("Remove every point").clicked() {
+            engine.clear_points();
+        }
+    });
+
+    if let (1, Some(&index)) = (selected, engine.selected_points().first()) {
+        show_label(ui, engine, index);
+    }
+}
+
+/// Shows controls for the text beside the point at index, where it sits and how far it is
+/// shifted from there.
+fn show_label(ui: &mut egui::Ui, engine: &mut State, index: usize) {
+    let Some(point) = engine.points().get(index) else {
+        return;
+    };
+    let mut label = point.label.clone();
+
+    ui.horizontal(|ui| {
+        let mut shown = label.is_some();
+        if ui.checkbox(&mut shown, "Label").changed() {
+            label = shown.then(Annotation::coordinates);
+        }
+        let Some(annotation) = &mut label else {
+            return;
+        };
+
+        let mut coordinates = annotation.text == AnnotationText::Coordinates;
+        if ui.checkbox(&mut coordinates, "Coordinates").changed() {
+            annotation.text = if coordinates {
+                AnnotationText::Coordinates
+            } else {
+                AnnotationText::Custom(String::new())
+            };
+        }
+        if let AnnotationText::Custom(text) = &mut annotation.text {
+            ui.add(egui::TextEdit::singleline(text).desired_width(80.0).hint_text("text"))
+                .accessible_name(egui::WidgetType::TextEdit, "Label text");
+        }
+    });
+
+    if let Some(annotation) = &mut label {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("label anchor")
+                .selected_text(anchor_name(annotation.anchor))
+                .show_ui(ui, |ui| {
+                    for anchor in Anchor::ALL {
+                        ui.selectable_value(&mut annotation.anchor, anchor, anchor_name(anchor));
+                    }
+                })
+                .response
+                .accessible_name(egui::WidgetType::ComboBox, "Label position");
+            ui.label("Offset");
+            ui.add(egui::DragValue::new(&mut annotation.offset.x).suffix(" px"))
+                .accessible_name(egui::WidgetType::DragValue, "Label x offset");
+            ui.add(egui::DragValue::new(&mut annotation.offset.y).suffix(" px"))
+                .accessible_name(egui::WidgetType::DragValue, "Label y offset");
+        });
+    }
+
+    if engine.points().get(index).is_some_and(|point| point.label != label) {
+        if let Err(e) = engine.set_point_label(index, label) {
+            log::error!("labelling point {index} failed: {e}");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{anyhow, bail, Result};
+use graphing_engine::{Color, PolarRegion, PolarSide};
+
+use super::vector_field::number;
+
+/// Splits an inequality such as r < 1 + cos(theta) into the side of the boundary it holds on and
+/// the boundary's expression in theta. r may be on either side.
+fn parse_inequality(text: &str) -> Result<(PolarSide, &str)> {
+    // the two character relations are looked for first, so <= isn't split at its <
+    let (left, relation, right) = ["<=", ">=", "<", ">"].into_iter()
+        .find_map(|relation| text.split_once(relation).map(|(left, right)| (left.trim(), relation, right.trim())))
+        .ok_or_else(|| anyhow!("expected an inequality such as r < 1 + cos(theta)"))?;
+    let less = relation.starts_with('<');
+    match (left, right) {
+        ("r", expression) => Ok((if less { PolarSide::Inside } else { PolarSide::Outside }, expression)),
+        (expression, "r") => Ok((if less { PolarSide::Outside } else { PolarSide::Inside }, expression)),
+        _ => bail!("one side of the inequality should be r alone"),
+    }
+}
+
+/// Compiles a boundary's expression in theta with Rhai, returning its distance from the origin
+/// at each angle.
+fn compile(expression: &str) -> Result<impl Fn(f32) -> Option<f32>> {
+    let engine = rhai::Engine::new();
+    let ast = engine.compile_expression(expression).map_err(|e| anyhow!("{expression}: {e}"))?;
+    Ok(move |theta: f32| {
+        let mut scope = rhai::Scope::new();
+        scope.push("theta", theta as rhai::FLOAT);
+        number(engine.eval_ast_with_scope(&mut scope, &ast).ok()?)
+    })
+}
+
+/// A region given by an inequality in polar coordinates, shaded on the graph.
+pub struct PolarShading {
+    shown: bool,
+    inequality: String,
+    /// The inequality last passed to the engine, to tell when it changes.
+    applied: Option<String>,
+    error: Option<String>,
+}
+
+impl PolarShading {
+    pub fn new() -> Self {
+        Self {
+            shown: false,
+            inequality: "r < 1 + cos(theta)".to_owned(),
+            applied: None,
+            error: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, engine: &mut graphing_engine::State) {
+        egui::CollapsingHeader::new("Polar region").show(ui, |ui| {
+            ui.checkbox(&mut self.shown, "Shade a region in polar coordinates");
+            ui.add_enabled_ui(self.shown, |ui| {
+                ui.text_edit_singleline(&mut self.inequality)
+                    .on_hover_text("An inequality in r and theta, such as r < 2 * sin(3 * theta). Powers are written with **.");
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        });
+
+        let wanted = self.shown.then(|| self.inequality.clone());
+        if wanted == self.applied {
+            return;
+        }
+        self.error = None;
+        let region = wanted.as_deref()
+            .map(|inequality| -> Result<PolarRegion> {
+                let (side, expression) = parse_inequality(inequality)?;
+                Ok(PolarRegion {
+                    radius: Box::new(compile(expression)?),
+                    side,
+                    fill: Color { r: 0.55, g: 0.3, b: 0.75, a: 0.25 },
+                    color: Color { r: 0.55, g: 0.3, b: 0.75, a: 1.0 },
+                })
+            })
+            .transpose()
+            .unwrap_or_else(|e| {
+                self.error = Some(e.to_string());
+                None
+            });
+        engine.set_polar_region(region);
+        self.applied = wanted;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inequalities_give_the_side_shaded() {
+        assert_eq!(parse_inequality("r < 1 + cos(theta)").unwrap(), (PolarSide::Inside, "1 + cos(theta)"));
+        assert_eq!(parse_inequality("r >= 2").unwrap(), (PolarSide::Outside, "2"));
+        assert_eq!(parse_inequality("theta > r").unwrap(), (PolarSide::Inside, "theta"));
+        assert!(parse_inequality("r = 1").is_err());
+        assert!(parse_inequality("x < 1").is_err());
+    }
+
+    #[test]
+    fn boundaries_are_evaluated_at_an_angle() {
+        let radius = compile("1 + cos(theta)").unwrap();
+        assert_eq!(radius(0.0), Some(2.0));
+        assert_eq!(compile("2").unwrap()(1.0), Some(2.0));
+        assert_eq!(compile("x").unwrap()(1.0), None);
+        assert!(compile("1 +").is_err());
+    }
+}
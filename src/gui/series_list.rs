@@ -0,0 +1,160 @@
+use graphing_engine::{Chart, Color, LegendEntry, Marker, Series};
+
+use crate::data::fit::Model;
+use crate::gui::AccessibleName;
+
+pub fn marker_name(marker: Marker) -> &'static str {
+    match marker {
+        Marker::Circle => "Circle",
+        Marker::Square => "Square",
+        Marker::Triangle => "Triangle",
+        Marker::Cross => "Cross",
+        Marker::Diamond => "Diamond",
+    }
+}
+
+pub fn chart_name(chart: Chart) -> &'static str {
+    match chart {
+        Chart::Points => "Points",
+        Chart::BoxPlot => "Box plot",
+        Chart::Bars => "Bars",
+        Chart::Line => "Line",
+    }
+}
+
+/// A curve to fit to a series, picked from its context menu.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FitRequest {
+    pub series: usize,
+    pub model: Model,
+}
+
+/// Something to do with a series, picked from its context menu.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SeriesAction {
+    Fit(FitRequest),
+    /// Write the series with this index to a CSV file.
+    Export(usize),
+}
+
+/// The data series on the graph, listed below the equations with controls for how each is drawn.
+pub struct SeriesList {
+    series: Vec<Series>,
+}
+
+impl SeriesList {
+    pub fn new() -> Self {
+        Self {
+            series: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, series: Series) {
+        self.series.push(series);
+    }
+
+    /// Returns every series, in order.
+    pub fn list(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Replaces every series with the saved ones.
+    pub fn restore(&mut self, series: Vec<Series>) {
+        self.series = series;
+    }
+
+    /// Returns the legend entry for each visible series with a name, in order.
+    pub fn legend_entries(&self) -> Vec<LegendEntry> {
+        self.series.iter()
+            .filter(|series| series.visible && !series.name.trim().is_empty())
+            .map(|series| LegendEntry { name: series.name.trim().to_owned(), color: series.color })
+            .collect()
+    }
+
+    /// Shows a row for each series with whether it is shown, its colour, name, how it is drawn,
+    /// its marker, whether its points are joined, and a button to delete it. Right clicking the
+    /// point count offers curves to fit and an export to CSV, returning the one picked, if any.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<SeriesAction> {
+        if self.series.is_empty() {
+            return None;
+        }
+
+        let mut removed = None;
+        let mut action = None;
+        egui::CollapsingHeader::new("Data").default_open(true).show(ui, |ui| {
+            for (i, series) in self.series.iter_mut().enumerate() {
+                let name = format!("Series {}", i + 1);
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut series.visible, "")
+                        .on_hover_text("Show")
+                        .accessible_name(egui::WidgetType::Checkbox, format!("Show {name}"));
+
+                    let Color { r, g, b, a } = series.color;
+                    let mut rgba = [r, g, b, a];
+                    let color = ui.color_edit_button_rgba_unmultiplied(&mut rgba)
+                        .accessible_name(egui::WidgetType::ColorButton, format!("Colour of {name}"));
+                    if color.changed() {
+                        let [r, g, b, a] = rgba;
+                        series.color = Color { r, g, b, a };
+                    }
+
+                    ui.add(egui::TextEdit::singleline(&mut series.name).hint_text("name").desired_width(80.0))
+                        .on_hover_text("Name shown in the legend")
+                        .accessible_name(egui::WidgetType::TextEdit, format!("Name of {name}"));
+                    let count = egui::Label::new(egui::RichText::new(format!("{} points", series.points.len())).weak())
+                        .sense(egui::Sense::click());
+                    ui.add(count)
+                        .on_hover_text("Right click to fit a curve or export the points")
+                        .context_menu(|ui| {
+                            for model in Model::ALL {
+                                if ui.button(format!("Fit {} curve", model.name().to_lowercase())).clicked() {
+                                    action = Some(SeriesAction::Fit(FitRequest { series: i, model }));
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button("Export to CSV…").clicked() {
+                                action = Some(SeriesAction::Export(i));
+                                ui.close_menu();
+                            }
+                        });
+
+                    egui::ComboBox::from_id_salt(("series chart", i))
+                        .selected_text(chart_name(series.chart))
+                        .show_ui(ui, |ui| {
+                            for chart in Chart::ALL {
+                                ui.selectable_value(&mut series.chart, chart, chart_name(chart));
+                            }
+                        })
+                        .response
+                        .accessible_name(egui::WidgetType::ComboBox, format!("Chart of {name}"));
+                    egui::ComboBox::from_id_salt(("series marker", i))
+                        .selected_text(marker_name(series.marker))
+                        .show_ui(ui, |ui| {
+                            for marker in Marker::ALL {
+                                ui.selectable_value(&mut series.marker, marker, marker_name(marker));
+                            }
+                        })
+                        .response
+                        .accessible_name(egui::WidgetType::ComboBox, format!("Marker of {name}"));
+                    ui.add_enabled_ui(series.chart == Chart::Points, |ui| {
+                        ui.checkbox(&mut series.connected, "Connect")
+                            .on_hover_text("Join the points with lines in order");
+                    });
+
+                    let delete = ui.small_button("✖")
+                        .on_hover_text("Delete")
+                        .accessible_name(egui::WidgetType::Button, format!("Delete {name}"));
+                    if delete.clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+        });
+
+        if let Some(index) = removed {
+            self.series.remove(index);
+        }
+        action
+    }
+}
@@ -0,0 +1,277 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use graphing_engine::{Corner, Projection, Snap, SnapTarget, DEFAULT_LABEL_SIZE};
+use serde::{Deserialize, Serialize};
+
+use crate::gui::AccessibleName;
+
+/// How much the interface scale changes with each press of Ctrl+= or Ctrl+-.
+const UI_SCALE_STEP: f32 = 0.1;
+const UI_SCALE_RANGE: RangeInclusive<f32> = 0.5..=3.0;
+
+/// User adjustable options for how the app presents frames. Kept between runs in the
+/// preferences file, where missing options take their defaults.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Wait for vertical blank before presenting, which avoids tearing.
+    pub vsync: bool,
+    /// Upper limit on frames drawn per second, if any.
+    pub fps_cap: Option<u32>,
+    /// Resolution the graph is rendered at relative to the window, to trade sharpness for speed.
+    pub plot_resolution: f32,
+    pub projection: Projection,
+    /// Draw guide lines through the pointer with its coordinates at the axes.
+    pub crosshair: bool,
+    /// What dragged points snap to, and from how far.
+    pub snap: Snap,
+    /// Number of parts minor gridlines split the space between labelled lines into, or None to
+    /// let the graph choose.
+    pub grid_subdivisions: Option<u32>,
+    /// Label the x axis in fractions of π.
+    pub pi_ticks: bool,
+    /// Draw arrowheads on the positive ends of the axes, labelled x and y.
+    pub axis_arrows: bool,
+    /// Label the origin with a 0.
+    pub origin_label: bool,
+    /// Label each quadrant in view with its number, from I to IV.
+    pub quadrant_labels: bool,
+    /// Font size of the axis labels in logical pixels.
+    pub label_size: f32,
+    /// Light or dark mode for both the panel and the graph, following the system by default.
+    pub theme: egui::ThemePreference,
+    /// Size of the panel and other controls relative to the window's scale factor. The graph
+    /// isn't affected.
+    pub ui_scale: f32,
+    /// Show a legend naming the visible curves.
+    pub legend: bool,
+    pub legend_corner: Corner,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            vsync: true,
+            fps_cap: None,
+            plot_resolution: 1.0,
+            projection: Projection::default(),
+            crosshair: false,
+            snap: Snap::default(),
+            grid_subdivisions: None,
+            pi_ticks: false,
+            axis_arrows: false,
+            origin_label: false,
+            quadrant_labels: false,
+            label_size: DEFAULT_LABEL_SIZE,
+            theme: egui::ThemePreference::System,
+            ui_scale: 1.0,
+            legend: false,
+            legend_corner: Corner::default(),
+        }
+    }
+}
+
+/// Picks the present mode for the vsync setting from those the surface supports.
+///
+/// Fifo is always supported, so it is used for vsync and as the last resort without it.
+pub fn present_mode(vsync: bool, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    if vsync {
+        return wgpu::PresentMode::Fifo;
+    }
+
+    [wgpu::PresentMode::Mailbox, wgpu::PresentMode::Immediate]
+        .into_iter()
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(wgpu::PresentMode::Fifo)
+}
+
+fn corner_name(corner: Corner) -> &'static str {
+    match corner {
+        Corner::TopLeft => "Top left",
+        Corner::TopRight => "Top right",
+        Corner::BottomLeft => "Bottom left",
+        Corner::BottomRight => "Bottom right",
+    }
+}
+
+/// Returns scale moved by steps of UI_SCALE_STEP, kept in UI_SCALE_RANGE and rounded to the step
+/// so repeated presses don't drift.
+fn step_ui_scale(scale: f32, steps: f32) -> f32 {
+    let scale = ((scale + steps * UI_SCALE_STEP) / UI_SCALE_STEP).round() * UI_SCALE_STEP;
+    scale.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end())
+}
+
+impl Settings {
+    /// The minimum time between frames, if the frame rate is capped.
+    pub fn frame_interval(&self) -> Option<Duration> {
+        self.fps_cap
+            .filter(|&fps| fps > 0)
+            .map(|fps| Duration::from_secs_f64(1.0 / fps as f64))
+    }
+
+    /// Applies the Ctrl+= and Ctrl+- shortcuts to the interface scale, and Ctrl+0 to reset it,
+    /// returning true if it changed.
+    pub fn ui_scale_shortcuts(&mut self, ctx: &egui::Context) -> bool {
+        use egui::gui_zoom::kb_shortcuts;
+
+        let before = self.ui_scale;
+        ctx.input_mut(|i| {
+            if i.consume_shortcut(&kb_shortcuts::ZOOM_RESET) {
+                self.ui_scale = 1.0;
+            }
+            if i.consume_shortcut(&kb_shortcuts::ZOOM_IN) || i.consume_shortcut(&kb_shortcuts::ZOOM_IN_SECONDARY) {
+                self.ui_scale = step_ui_scale(self.ui_scale, 1.0);
+            }
+            if i.consume_shortcut(&kb_shortcuts::ZOOM_OUT) {
+                self.ui_scale = step_ui_scale(self.ui_scale, -1.0);
+            }
+        });
+        self.ui_scale != before
+    }
+
+    /// Shows the settings, returning true if any were changed.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        egui::CollapsingHeader::new("Settings").show(ui, |ui| {
+            self.theme.radio_buttons(ui);
+            ui.add(egui::Slider::new(&mut self.ui_scale, UI_SCALE_RANGE).step_by(UI_SCALE_STEP as f64).text("Interface scale"))
+                .on_hover_text("Ctrl+= and Ctrl+- also change this, and Ctrl+0 resets it");
+            ui.checkbox(&mut self.vsync, "Vsync");
+
+            ui.horizontal(|ui| {
+                let mut capped = self.fps_cap.is_some();
+                if ui.checkbox(&mut capped, "Limit frame rate").changed() {
+                    self.fps_cap = capped.then_some(60);
+                }
+                if let Some(fps) = &mut self.fps_cap {
+                    ui.add(egui::DragValue::new(fps).range(1..=1000).suffix(" fps"))
+                        .accessible_name(egui::WidgetType::DragValue, "Frame rate limit");
+                }
+            });
+
+            ui.add(egui::Slider::new(&mut self.plot_resolution, 0.25..=2.0).text("Graph resolution"));
+
+            ui.horizontal(|ui| {
+                ui.label("Projection");
+                ui.radio_value(&mut self.projection, Projection::Orthographic, "Orthographic");
+                ui.radio_value(&mut self.projection, Projection::Perspective, "Perspective");
+            });
+
+            ui.checkbox(&mut self.crosshair, "Crosshair");
+
+            ui.horizontal(|ui| {
+                ui.label("Snap points to");
+                ui.radio_value(&mut self.snap.target, SnapTarget::Off, "Nothing");
+                ui.radio_value(&mut self.snap.target, SnapTarget::Grid, "Grid");
+                ui.radio_value(&mut self.snap.target, SnapTarget::Curve, "Curves");
+                ui.add_enabled(
+                    self.snap.target != SnapTarget::Off,
+                    egui::DragValue::new(&mut self.snap.radius).range(1.0..=50.0).suffix(" px"),
+                )
+                .on_hover_text("How close a point has to be to snap. Hold Alt while dragging to not snap")
+                .accessible_name(egui::WidgetType::DragValue, "Snap radius");
+            });
+
+            ui.horizontal(|ui| {
+                let mut custom = self.grid_subdivisions.is_some();
+                if ui.checkbox(&mut custom, "Grid subdivisions").changed() {
+                    self.grid_subdivisions = custom.then_some(5);
+                }
+                if let Some(subdivisions) = &mut self.grid_subdivisions {
+                    ui.add(egui::DragValue::new(subdivisions).range(1..=10))
+                        .accessible_name(egui::WidgetType::DragValue, "Grid subdivisions");
+                }
+            });
+
+            ui.checkbox(&mut self.pi_ticks, "Label x axis in π");
+            ui.checkbox(&mut self.axis_arrows, "Axis arrows");
+            ui.checkbox(&mut self.origin_label, "Label origin");
+            ui.checkbox(&mut self.quadrant_labels, "Label quadrants");
+
+            ui.add(egui::Slider::new(&mut self.label_size, 8.0..=32.0).text("Label size"));
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.legend, "Legend");
+                ui.add_enabled_ui(self.legend, |ui| {
+                    egui::ComboBox::from_id_salt("legend corner")
+                        .selected_text(corner_name(self.legend_corner))
+                        .show_ui(ui, |ui| {
+                            for corner in Corner::ALL {
+                                ui.selectable_value(&mut self.legend_corner, corner, corner_name(corner));
+                            }
+                        })
+                        .response
+                        .accessible_name(egui::WidgetType::ComboBox, "Legend corner");
+                });
+            });
+
+            if ui.button("Reset to defaults").clicked() {
+                *self = Self::default();
+            }
+        });
+
+        *self != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_present_mode() {
+        let all = [wgpu::PresentMode::Immediate, wgpu::PresentMode::Mailbox, wgpu::PresentMode::Fifo];
+
+        assert_eq!(present_mode(true, &all), wgpu::PresentMode::Fifo);
+        assert_eq!(present_mode(false, &all), wgpu::PresentMode::Mailbox);
+        assert_eq!(present_mode(false, &[wgpu::PresentMode::Fifo, wgpu::PresentMode::Immediate]), wgpu::PresentMode::Immediate);
+        assert_eq!(present_mode(false, &[wgpu::PresentMode::Fifo]), wgpu::PresentMode::Fifo);
+    }
+
+    #[test]
+    fn test_frame_interval() {
+        assert_eq!(Settings::default().frame_interval(), None);
+
+        let settings = Settings { fps_cap: Some(50), ..Default::default() };
+        assert_eq!(settings.frame_interval(), Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_step_ui_scale() {
+        use approx::relative_eq;
+
+        assert!(relative_eq!(step_ui_scale(1.0, 1.0), 1.1));
+        assert!(relative_eq!(step_ui_scale(1.03, -1.0), 0.9));
+        assert_eq!(step_ui_scale(0.5, -1.0), 0.5);
+        assert_eq!(step_ui_scale(3.0, 2.0), 3.0);
+    }
+
+    #[test]
+    fn ui_scale_shortcuts_count_as_changes() {
+        let ctx = egui::Context::default();
+        let mut settings = Settings::default();
+        let input = egui::RawInput {
+            events: vec![egui::Event::Key {
+                key: egui::Key::Equals,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: egui::Modifiers::COMMAND,
+            }],
+            ..Default::default()
+        };
+
+        let mut changed = false;
+        let _ = ctx.run(input, |ctx| {
+            // as in the app, the settings are shown after the shortcuts are applied, and see the
+            // scale already changed
+            changed = settings.ui_scale_shortcuts(ctx);
+            egui::CentralPanel::default().show(ctx, |ui| changed |= settings.show(ui));
+        });
+
+        assert!(changed);
+        assert!(approx::relative_eq!(settings.ui_scale, 1.1));
+    }
+}
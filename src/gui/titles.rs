@@ -0,0 +1,22 @@
+use graphing_engine::Titles;
+
+/// Shows text boxes for the plot and axis titles, returning true if any were changed.
+pub fn show(ui: &mut egui::Ui, titles: &mut Titles) -> bool {
+    let mut changed = false;
+
+    egui::CollapsingHeader::new("Titles").show(ui, |ui| {
+        egui::Grid::new("titles").num_columns(2).show(ui, |ui| {
+            for (label, text) in [
+                ("Title", &mut titles.title),
+                ("x axis", &mut titles.x_axis),
+                ("y axis", &mut titles.y_axis),
+            ] {
+                let label = ui.label(label);
+                changed |= ui.text_edit_singleline(text).labelled_by(label.id).changed();
+                ui.end_row();
+            }
+        });
+    });
+
+    changed
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use crate::gui::AccessibleName;
+
+/// How long a toast stays up unless it is closed sooner.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+struct Toast {
+    message: String,
+    shown_at: Instant,
+}
+
+/// Error messages shown briefly in the corner of the window, without stopping the user.
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+/// Drops toasts which have been up for TOAST_DURATION at now, returning how long until the next
+/// one expires, if there are any left.
+fn expire(toasts: &mut Vec<Toast>, now: Instant) -> Option<Duration> {
+    toasts.retain(|toast| now.duration_since(toast.shown_at) < TOAST_DURATION);
+    toasts.iter()
+        .map(|toast| TOAST_DURATION - now.duration_since(toast.shown_at))
+        .min()
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self {
+            toasts: Vec::new(),
+        }
+    }
+
+    /// Shows message as an error, and logs it.
+    pub fn error(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        log::error!("{message}");
+        self.toasts.push(Toast { message, shown_at: Instant::now() });
+    }
+
+    /// Shows the toasts stacked up from the bottom right corner, newest at the bottom.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let Some(remaining) = expire(&mut self.toasts, Instant::now()) else {
+            return;
+        };
+        // draw again to take the next toast down when it expires
+        ctx.request_repaint_after(remaining);
+
+        let mut closed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(320.0);
+                        ui.horizontal(|ui| {
+                            ui.colored_label(ui.visuals().error_fg_color, &toast.message);
+                            let close = ui.small_button("✖").accessible_name(egui::WidgetType::Button, "Dismiss");
+                            if close.clicked() {
+                                closed = Some(i);
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(i) = closed {
+            self.toasts.remove(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expire() {
+        let now = Instant::now();
+        let toast = |age| Toast { message: String::new(), shown_at: now - age };
+        let mut toasts = vec![toast(Duration::from_secs(7)), toast(Duration::from_secs(2)), toast(Duration::from_secs(5))];
+
+        assert_eq!(expire(&mut toasts, now), Some(Duration::from_secs(1)));
+        assert_eq!(toasts.len(), 2);
+
+        assert_eq!(expire(&mut toasts, now + Duration::from_secs(4)), None);
+        assert!(toasts.is_empty());
+    }
+}
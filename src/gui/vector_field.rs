@@ -0,0 +1,122 @@
+use anyhow::{anyhow, Result};
+use graphing_engine::{Color, VectorField};
+
+/// Spacing between streamlines in logical pixels, from dense to sparse.
+const SEPARATIONS: std::ops::RangeInclusive<f32> = 10.0..=80.0;
+
+/// Returns the value of a Rhai expression as a number, whether it came out whole or decimal.
+pub(super) fn number(value: rhai::Dynamic) -> Option<f32> {
+    value.as_float().ok()
+        .or_else(|| value.as_int().ok().map(|value| value as rhai::FLOAT))
+        .map(|value| value as f32)
+}
+
+/// The components of a vector field as expressions in x and y, compiled with Rhai, which
+/// unlike equations can use any function of both.
+struct Components {
+    engine: rhai::Engine,
+    x: rhai::AST,
+    y: rhai::AST,
+}
+
+impl Components {
+    fn compile(x: &str, y: &str) -> Result<Self> {
+        let engine = rhai::Engine::new();
+        let compile = |text: &str| engine.compile_expression(text).map_err(|e| anyhow!("{text}: {e}"));
+        let (x, y) = (compile(x)?, compile(y)?);
+        Ok(Self { engine, x, y })
+    }
+
+    /// Returns the value of an expression at (x, y), or None if it fails or isn't a number.
+    fn eval(&self, ast: &rhai::AST, x: f32, y: f32) -> Option<f32> {
+        let mut scope = rhai::Scope::new();
+        scope.push("x", x as rhai::FLOAT);
+        scope.push("y", y as rhai::FLOAT);
+        number(self.engine.eval_ast_with_scope(&mut scope, ast).ok()?)
+    }
+
+    fn at(&self, point: cgmath::Vector2<f32>) -> Option<cgmath::Vector2<f32>> {
+        Some(cgmath::vec2(self.eval(&self.x, point.x, point.y)?, self.eval(&self.y, point.x, point.y)?))
+    }
+}
+
+/// A vector field drawn as streamlines across the graph, given by its components.
+pub struct Streamlines {
+    shown: bool,
+    x: String,
+    y: String,
+    separation: f32,
+    /// The components and spacing last passed to the engine, to tell when they change.
+    applied: Option<(String, String, f32)>,
+    error: Option<String>,
+}
+
+impl Streamlines {
+    pub fn new() -> Self {
+        Self {
+            shown: false,
+            x: "-y".to_owned(),
+            y: "x".to_owned(),
+            separation: 30.0,
+            applied: None,
+            error: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, engine: &mut graphing_engine::State) {
+        egui::CollapsingHeader::new("Vector field").show(ui, |ui| {
+            ui.checkbox(&mut self.shown, "Show streamlines");
+            ui.add_enabled_ui(self.shown, |ui| {
+                egui::Grid::new("vector field").num_columns(2).show(ui, |ui| {
+                    ui.label("dx/dt =");
+                    ui.text_edit_singleline(&mut self.x);
+                    ui.end_row();
+                    ui.label("dy/dt =");
+                    ui.text_edit_singleline(&mut self.y);
+                    ui.end_row();
+                });
+                ui.add(egui::Slider::new(&mut self.separation, SEPARATIONS).text("spacing"));
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        });
+
+        let wanted = self.shown.then(|| (self.x.clone(), self.y.clone(), self.separation));
+        if wanted == self.applied {
+            return;
+        }
+        self.error = None;
+        let field = wanted.as_ref().and_then(|(x, y, separation)| match Components::compile(x, y) {
+            Ok(components) => Some(VectorField {
+                field: Box::new(move |point| components.at(point)),
+                separation: *separation,
+                color: Color { r: 0.3, g: 0.5, b: 0.7, a: 1.0 },
+            }),
+            Err(e) => {
+                self.error = Some(e.to_string());
+                None
+            }
+        });
+        engine.set_vector_field(field);
+        self.applied = wanted;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_are_evaluated_at_a_point() {
+        let components = Components::compile("-y", "x * 2").unwrap();
+        assert_eq!(components.at(cgmath::vec2(1.0, 3.0)), Some(cgmath::vec2(-3.0, 2.0)));
+
+        // whole numbers are taken as well as decimals
+        let constant = Components::compile("1", "0").unwrap();
+        assert_eq!(constant.at(cgmath::vec2(5.0, 5.0)), Some(cgmath::vec2(1.0, 0.0)));
+
+        assert!(Components::compile("x +", "y").is_err());
+        assert_eq!(Components::compile("z", "y").unwrap().at(cgmath::vec2(0.0, 0.0)), None);
+    }
+}
@@ -0,0 +1,167 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+
+use crate::gui::AccessibleName;
+
+/// Most expressions kept, dropping the oldest beyond this.
+const MAX_ENTRIES: usize = 100;
+
+/// Expressions entered in the past, shared across workspaces and kept between runs.
+///
+/// Entries are indexed from the newest, so 0 is the most recent.
+pub struct History {
+    /// Oldest first.
+    entries: Vec<String>,
+    changed: bool,
+}
+
+/// Returns where the history is kept, in the user's data directory.
+fn path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("graphing-calculator").join("history.json"))
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            changed: false,
+        }
+    }
+
+    /// Reads the history saved by an earlier run, starting afresh if there isn't one or it can't
+    /// be read.
+    pub fn load() -> Self {
+        let Some(path) = path().filter(|path| path.exists()) else {
+            return Self::new();
+        };
+        let entries = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(serde_json::from_str(&text)?));
+        match entries {
+            Ok(entries) => Self { entries, changed: false },
+            Err(e) => {
+                log::warn!("couldn't read expression history from {}: {e}", path.display());
+                Self::new()
+            }
+        }
+    }
+
+    /// Writes the history to disk if it has changed since it was loaded or last saved.
+    pub fn save(&mut self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        self.changed = false;
+
+        let path = path().ok_or_else(|| anyhow!("there is no data directory to save the expression history in"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    /// Adds text as the newest entry, moving it up if it was already there. Blank text is ignored.
+    pub fn record(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() || self.entries.last().is_some_and(|last| last == text) {
+            return;
+        }
+
+        self.entries.retain(|entry| entry != text);
+        self.entries.push(text.to_owned());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.changed = true;
+    }
+
+    /// Returns the entry index entries back from the newest.
+    pub fn entry(&self, index: usize) -> Option<&str> {
+        let i = self.entries.len().checked_sub(index + 1)?;
+        Some(&self.entries[i])
+    }
+
+    /// Returns the index of the entry before index, or the newest if index is None. Stays on the
+    /// oldest entry once it is reached.
+    pub fn older(&self, index: Option<usize>) -> Option<usize> {
+        match index {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(i) => Some((i + 1).min(self.entries.len() - 1)),
+        }
+    }
+
+    /// Returns the index of the entry after index, or None to go back past the newest.
+    pub fn newer(&self, index: Option<usize>) -> Option<usize> {
+        index?.checked_sub(1)
+    }
+
+    /// Shows a dropdown of the entries, newest first, returning the one picked, if any.
+    pub fn show_menu(&self, ui: &mut egui::Ui) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut picked = None;
+        ui.menu_button("🕘", |ui| {
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                for entry in self.entries.iter().rev() {
+                    if ui.button(entry).clicked() {
+                        picked = Some(entry.clone());
+                        ui.close_menu();
+                    }
+                }
+            });
+        }).response
+            .on_hover_text("Previous expressions")
+            .accessible_name(egui::WidgetType::Button, "Previous expressions");
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_moves_repeats_to_the_front() {
+        let mut history = History::new();
+        for text in ["x", " 2x ", "", "x^2", "x"] {
+            history.record(text);
+        }
+
+        assert_eq!(history.entries, ["2x", "x^2", "x"]);
+        assert_eq!(history.entry(0), Some("x"));
+        assert_eq!(history.entry(2), Some("2x"));
+        assert_eq!(history.entry(3), None);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut history = History::new();
+        for i in 0..=MAX_ENTRIES {
+            history.record(&format!("{i}x"));
+        }
+
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert_eq!(history.entries[0], "1x");
+    }
+
+    #[test]
+    fn test_older_and_newer() {
+        let mut history = History::new();
+        assert_eq!(history.older(None), None);
+
+        history.record("x");
+        history.record("2x");
+
+        assert_eq!(history.older(None), Some(0));
+        assert_eq!(history.older(Some(0)), Some(1));
+        assert_eq!(history.older(Some(1)), Some(1));
+        assert_eq!(history.newer(Some(1)), Some(0));
+        assert_eq!(history.newer(Some(0)), None);
+        assert_eq!(history.newer(None), None);
+    }
+}
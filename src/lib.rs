@@ -0,0 +1,1420 @@
+#[cfg(target_os = "android")]
+mod android;
+mod data;
+mod dialog;
+mod gui;
+mod history;
+mod preferences;
+mod recovery;
+mod remote;
+mod screenshot;
+mod scripting;
+mod session;
+mod workspace;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use egui_winit::accesskit_winit;
+use graphing_engine::State;
+use pollster::FutureExt;
+use winit::application::ApplicationHandler;
+use winit::dpi::PhysicalSize;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
+use winit::window::{Window, WindowId, WindowLevel};
+
+use gui::{ExportAction, ImportAction, SeriesAction};
+use session::{FileAction, Session};
+use workspace::{TabAction, Workspace};
+
+/// Command line options.
+#[derive(Default)]
+struct Options {
+    /// Rhai script whose hooks are run on engine events.
+    script: Option<PathBuf>,
+    /// Start with the fallback adapter and small buffers for problematic GPUs.
+    safe_mode: bool,
+    /// Time the parts of each frame on the GPU and show them under diagnostics.
+    profile_gpu: bool,
+    /// Open an undecorated, transparent, always on top window for use over other apps.
+    overlay: bool,
+    /// Address to accept remote control connections on.
+    listen: Option<String>,
+    /// File to record the spans of each frame to, in the Chrome trace format.
+    trace: Option<PathBuf>,
+    /// Arguments which weren't recognised, warned about once logging is set up.
+    unknown: Vec<String>,
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--script" => options.script = args.next().map(PathBuf::from),
+                "--safe-mode" => options.safe_mode = true,
+                "--profile-gpu" => options.profile_gpu = true,
+                "--overlay" => options.overlay = true,
+                "--listen" => options.listen = args.next(),
+                "--trace" => options.trace = args.next().map(PathBuf::from),
+                _ => options.unknown.push(arg),
+            }
+        }
+
+        options
+    }
+}
+
+/// Title of the window.
+const TITLE: &str = "graphing calculator";
+
+/// Most skipped rows of an imported file listed in the toast. The rest are only logged.
+const MAX_REPORTED_ROWS: usize = 3;
+
+/// Backends tried in turn when looking for an adapter, and whether to force the fallback
+/// (software) adapter.
+const ADAPTER_FALLBACKS: [(wgpu::Backends, bool); 3] = [
+    (wgpu::Backends::PRIMARY, false),
+    (wgpu::Backends::GL, false),
+    (wgpu::Backends::all(), true),
+];
+
+/// A surface for the window on one of ADAPTER_FALLBACKS, which an adapter is looked for on.
+struct SurfaceCandidate {
+    backends: wgpu::Backends,
+    force_fallback_adapter: bool,
+    instance: wgpu::Instance,
+    surface: wgpu::Surface<'static>,
+}
+
+/// Creates a surface for the window on each of ADAPTER_FALLBACKS, in the order they are tried.
+/// This has to be done on the event loop's thread, as Metal only gives out a window's layer there.
+fn create_surfaces(window: &Arc<Window>, safe_mode: bool) -> Vec<SurfaceCandidate> {
+    // safe mode tries the software adapter first but still takes a hardware one if there is none
+    let skip = if safe_mode { ADAPTER_FALLBACKS.len() - 1 } else { 0 };
+    let attempts = ADAPTER_FALLBACKS.iter().cycle().skip(skip).take(ADAPTER_FALLBACKS.len());
+
+    attempts
+        .filter_map(|&(backends, force_fallback_adapter)| {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+                backends,
+                ..Default::default()
+            });
+            match instance.create_surface(window.clone()) {
+                Ok(surface) => Some(SurfaceCandidate { backends, force_fallback_adapter, instance, surface }),
+                Err(e) => {
+                    log::warn!("failed to create surface for {backends:?}: {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Finds an adapter which can present to one of the surfaces, trying them in turn.
+fn request_adapter(candidates: Vec<SurfaceCandidate>) -> Result<(wgpu::Instance, wgpu::Surface<'static>, wgpu::Adapter)> {
+    for SurfaceCandidate { backends, force_fallback_adapter, instance, surface } in candidates {
+        let adapter = instance.request_adapter(
+            &wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                force_fallback_adapter,
+                compatible_surface: Some(&surface),
+            },
+        ).block_on();
+        match adapter {
+            Some(adapter) => return Ok((instance, surface, adapter)),
+            None => log::warn!("no adapter found for {backends:?} (fallback {force_fallback_adapter})"),
+        }
+    }
+
+    Err(anyhow!("no graphics adapter compatible with this window was found"))
+}
+
+/// The graphics device and the surface of the window it draws into.
+struct Gpu {
+    instance: wgpu::Instance,
+    surface: wgpu::Surface<'static>,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+}
+
+/// Finds an adapter for one of the window's surfaces and opens a device on it. This can take
+/// seconds on some drivers, so it is done off the event loop's thread.
+fn request_gpu(candidates: Vec<SurfaceCandidate>, profile_gpu: bool) -> Result<Gpu> {
+    let (instance, surface, adapter) = request_adapter(candidates)?;
+    log::info!("using adapter {:?}", adapter.get_info());
+
+    // GL and software adapters often can't meet the default limits
+    let required_limits = match adapter.get_info().backend {
+        wgpu::Backend::Vulkan | wgpu::Backend::Metal | wgpu::Backend::Dx12 => wgpu::Limits::default(),
+        _ => wgpu::Limits::downlevel_defaults(),
+    };
+
+    let mut wanted_features = wgpu::Features::PIPELINE_CACHE;
+    if profile_gpu {
+        wanted_features |= wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES;
+    }
+    let required_features = adapter.features() & wanted_features;
+    let (device, queue) = adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: None,
+            required_features,
+            required_limits: required_limits.using_resolution(adapter.limits()),
+            memory_hints: wgpu::MemoryHints::Performance,
+        },
+        None,
+    ).block_on()?;
+
+    Ok(Gpu { instance, surface, adapter, device, queue })
+}
+
+/// Draws a frame saying the calculator is starting, shown while the engine is created.
+fn draw_splash(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    surface: &wgpu::Surface,
+    config: &wgpu::SurfaceConfiguration,
+    window: &Window,
+    gui_renderer: &mut gui::GuiRenderer,
+) {
+    let output = match surface.get_current_texture() {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("failed to draw the splash: {e}");
+            return;
+        }
+    };
+    let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Splash Encoder"),
+    });
+
+    gui_renderer.begin_pass(window);
+    egui::CentralPanel::default().show(gui_renderer.ctx(), |ui| {
+        ui.centered_and_justified(|ui| ui.label("Starting…"));
+    });
+    let screen_descriptor = egui_wgpu::ScreenDescriptor {
+        size_in_pixels: [config.width, config.height],
+        pixels_per_point: gui_renderer.ctx().pixels_per_point(),
+    };
+    gui_renderer.render(device, queue, &mut encoder, window, &view, &screen_descriptor, wgpu::Color::TRANSPARENT);
+
+    queue.submit(std::iter::once(encoder.finish()));
+    output.present();
+}
+
+/// Events sent to the event loop from outside it.
+enum UserEvent {
+    /// A request from a screen reader or other assistive technology.
+    AccessKit(accesskit_winit::Event),
+    /// A remote control request is waiting to be carried out.
+    Remote,
+    /// The graphics device being opened at startup is ready, or couldn't be opened.
+    GpuReady(Box<Result<Gpu>>),
+    /// The graphics device stopped working, such as when its driver crashed, with why.
+    DeviceLost(String),
+}
+
+impl From<accesskit_winit::Event> for UserEvent {
+    fn from(event: accesskit_winit::Event) -> Self {
+        Self::AccessKit(event)
+    }
+}
+
+/// Writes log records and spans to stderr, filtered by RUST_LOG as env_logger did, and records
+/// spans to trace in the Chrome trace format if given, which can be opened in Perfetto or
+/// chrome://tracing. The trace is written until the returned guard is dropped.
+fn init_tracing(trace: Option<&Path>) -> Option<tracing_chrome::FlushGuard> {
+    use tracing_subscriber::prelude::*;
+
+    let (chrome, guard) = match trace {
+        Some(path) => {
+            let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new().file(path).build();
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+    // also takes the log crate's records, so the engine's and dependencies' logging still shows
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(tracing_subscriber::EnvFilter::from_default_env()))
+        .with(chrome)
+        .init();
+    guard
+}
+
+pub async fn run() {
+    let options = Options::from_args(std::env::args().skip(1));
+    let _trace = init_tracing(options.trace.as_deref());
+    for arg in &options.unknown {
+        log::warn!("ignoring unknown argument {arg}");
+    }
+    let event_loop = EventLoop::<UserEvent>::with_user_event().build().unwrap();
+
+    run_app(event_loop, options);
+}
+
+/// Runs the calculator until its window is closed.
+fn run_app(event_loop: EventLoop<UserEvent>, options: Options) {
+    recovery::install_panic_hook();
+    let mut window_state = App::new(options, event_loop.create_proxy());
+    let _ = event_loop.run_app(&mut window_state);
+}
+
+/// The window while the graphics device is being opened, before there is anything to draw with.
+struct Starting {
+    window: Arc<Window>,
+    egui_state: egui_winit::State,
+    /// Requests from assistive technology, passed on once the interface exists.
+    accesskit_events: Vec<accesskit_winit::WindowEvent>,
+}
+
+struct App {
+    options: Options,
+    proxy: EventLoopProxy<UserEvent>,
+    starting: Option<Starting>,
+    state: Option<AppState>,
+}
+
+impl App {
+    pub fn new(options: Options, proxy: EventLoopProxy<UserEvent>) -> Self {
+        Self { 
+            options,
+            proxy,
+            starting: None,
+            state: None,
+        }
+    }
+
+    /// Finishes starting once the graphics device is open, or explains why it couldn't be.
+    fn start(&mut self, event_loop: &ActiveEventLoop, gpu: Result<Gpu>) {
+        let Some(starting) = self.starting.take() else {
+            return;
+        };
+
+        let state = gpu.and_then(|gpu| {
+            AppState::new(gpu, starting.window, starting.egui_state, &self.options, self.proxy.clone())
+        });
+        match state {
+            Ok(mut state) => {
+                for event in starting.accesskit_events {
+                    state.gui_renderer.accesskit_event(event);
+                }
+                self.state = Some(state);
+            }
+            Err(e) => {
+                log::error!("failed to start: {e:#}");
+                dialog::error(format!(
+                    "The graphics device could not be initialised.\n\n{e:#}\n\n\
+                    Updating your graphics drivers or starting with --safe-mode may help."
+                ));
+                event_loop.exit();
+            }
+        }
+    }
+}
+
+impl ApplicationHandler<UserEvent> for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        // Android destroys the surface while the app is in the background, and only it is
+        // recreated on returning, keeping everything else as it was
+        if let Some(state) = &mut self.state {
+            if let Err(e) = state.resume() {
+                log::error!("failed to recreate the surface: {e:#}");
+                event_loop.exit();
+            }
+            return;
+        }
+        if self.starting.is_some() {
+            return;
+        }
+
+        // the window is shown once AccessKit is set up, which it has to be before the first showing
+        let mut attributes = Window::default_attributes()
+            .with_title(format!("{TITLE} (starting)"))
+            .with_visible(false);
+        if self.options.overlay {
+            attributes = attributes
+                .with_transparent(true)
+                .with_decorations(false)
+                .with_window_level(WindowLevel::AlwaysOnTop);
+        }
+
+        let window = match event_loop.create_window(attributes) {
+            Ok(window) => Arc::new(window),
+            Err(e) => {
+                log::error!("failed to create the window: {e}");
+                dialog::error(format!("The window could not be created.\n\n{e}"));
+                event_loop.exit();
+                return;
+            }
+        };
+        let egui_state = gui::GuiRenderer::window_state(&window, self.proxy.clone());
+        window.set_visible(true);
+
+        // the window stays responsive while the device is opened, and says it is starting
+        let candidates = create_surfaces(&window, self.options.safe_mode);
+        let profile_gpu = self.options.profile_gpu;
+        let proxy = self.proxy.clone();
+        std::thread::spawn(move || {
+            let gpu = request_gpu(candidates, profile_gpu);
+            let _ = proxy.send_event(UserEvent::GpuReady(Box::new(gpu)));
+        });
+        self.starting = Some(Starting {
+            window,
+            egui_state,
+            accesskit_events: Vec::new(),
+        });
+    }
+
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        if let Some(state) = &mut self.state {
+            state.suspend();
+        }
+    }
+
+    fn window_event(
+            &mut self,
+            event_loop: &ActiveEventLoop,
+            window_id: WindowId,
+            event: WindowEvent,
+        ) {
+        let Some(state) = self.state.as_mut() else {
+            // there is nothing to draw or edit until the device is open, but the window can close
+            if matches!(event, WindowEvent::CloseRequested) {
+                event_loop.exit();
+            }
+            return;
+        };
+
+        if window_id == state.window().id() && !state.input(&event) {
+            match event {
+                WindowEvent::Resized(physical_size) => {
+                    state.resize(physical_size);
+                    state.invalidate();
+                }
+                // the engine's scale factor is set from the window's every frame
+                WindowEvent::ScaleFactorChanged { .. } => state.invalidate(),
+                WindowEvent::DroppedFile(path) => state.drop_file(&path),
+                WindowEvent::CloseRequested => {
+                    state.shutdown();
+                    event_loop.exit();
+                }
+                WindowEvent::RedrawRequested => {
+                    match state.render() {
+                        Ok(_) => {}
+                        Err(wgpu::SurfaceError::Lost) => state.resize(state.size()),
+                        Err(wgpu::SurfaceError::OutOfMemory) => {
+                            if let Err(e) = recovery::write() {
+                                log::error!("failed to save the graphs for recovery: {e:#}");
+                            }
+                            event_loop.exit();
+                        }
+                        Err(e) => {
+                            state.toasts.error(format!("Drawing the window failed: {e}"));
+                            state.invalidate();
+                        }
+                    }
+                    if state.close_requested {
+                        state.shutdown();
+                        event_loop.exit();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: UserEvent) {
+        let event = match event {
+            UserEvent::GpuReady(gpu) => return self.start(event_loop, *gpu),
+            UserEvent::DeviceLost(message) => {
+                // the workspaces were written out when the device was lost
+                if let Some(state) = &mut self.state {
+                    state.shutdown();
+                }
+                dialog::error(format!(
+                    "The graphics device stopped working, so the calculator has to close.\n\n{message}\n\n\
+                    Your graphs have been saved and will be restored when it is next started."
+                ));
+                event_loop.exit();
+                return;
+            }
+            UserEvent::AccessKit(event) if self.state.is_none() => {
+                if let Some(starting) = &mut self.starting {
+                    starting.accesskit_events.push(event.window_event);
+                }
+                return;
+            }
+            event => event,
+        };
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        match event {
+            UserEvent::AccessKit(event) => {
+                if event.window_id == state.window().id() {
+                    state.gui_renderer.accesskit_event(event.window_event);
+                    state.invalidate();
+                }
+            }
+            // carried out after the next frame, once the size of the plot is known for screenshots
+            UserEvent::Remote => state.invalidate(),
+            UserEvent::GpuReady(_) | UserEvent::DeviceLost(_) => {}
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(state) = &self.state else {
+            return;
+        };
+
+        // sleep until something changes rather than drawing identical frames
+        match state.next_frame() {
+            Some(at) if at > Instant::now() => event_loop.set_control_flow(ControlFlow::WaitUntil(at)),
+            Some(_) => {
+                event_loop.set_control_flow(ControlFlow::Wait);
+                state.window().request_redraw();
+            }
+            None => event_loop.set_control_flow(ControlFlow::Wait),
+        }
+    }
+}
+
+struct AppState {
+    instance: wgpu::Instance,
+    /// The surface drawn into, which is None while the app is suspended.
+    surface: Option<wgpu::Surface<'static>>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    present_modes: Vec<wgpu::PresentMode>,
+    /// Settings, the default view and recent files, loaded at startup and saved at shutdown.
+    preferences: preferences::Preferences,
+    last_frame: Option<Instant>,
+    /// When the next frame should be drawn, or None if nothing has changed since the last one.
+    redraw_at: Option<Instant>,
+    /// Whether the window is an undecorated overlay, which needs its own move and close controls.
+    overlay: bool,
+    close_requested: bool,
+
+    size: PhysicalSize<u32>,
+    window: Arc<Window>,
+    cursor: graphing_engine::CursorIcon,
+
+    workspaces: Vec<Workspace>,
+    /// Index of the workspace being shown.
+    active: usize,
+    /// Used to create the engines of new workspaces.
+    engine_config: graphing_engine::Config,
+    gui_renderer: gui::GuiRenderer,
+
+    target: graphing_engine::RenderTargetConfig,
+    plot_view: gui::PlotView,
+    diagnostics: gui::Diagnostics,
+    /// A dropped CSV file waiting for its columns to be picked.
+    csv_import: Option<gui::CsvImport>,
+    /// An equation waiting for the samples to export to be picked.
+    csv_export: Option<gui::CsvExport>,
+    /// Multiple of the plot's resolution screenshots are saved at.
+    screenshot_scale: u32,
+    /// A screenshot of the active workspace waiting for its lines to be tessellated.
+    screenshot: Option<screenshot::Pending>,
+    help: gui::Help,
+    /// Errors shown in the corner of the window.
+    toasts: gui::Toasts,
+    history: history::History,
+    /// Accepts requests from other processes when started with --listen.
+    remote: Option<remote::RemoteServer>,
+}
+
+impl AppState {
+    pub fn new(
+        gpu: Gpu,
+        window_arc: Arc<Window>,
+        egui_state: egui_winit::State,
+        options: &Options,
+        proxy: EventLoopProxy<UserEvent>,
+    ) -> Result<Self> {
+        let Gpu { instance, surface, adapter, device, queue } = gpu;
+        let lost_proxy = proxy.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            // dropping the device at exit also reports it as lost
+            if !matches!(reason, wgpu::DeviceLostReason::Unknown | wgpu::DeviceLostReason::DeviceInvalid) {
+                return;
+            }
+            log::error!("the graphics device was lost: {message}");
+            if let Err(e) = recovery::write() {
+                log::error!("failed to save the graphs for recovery: {e:#}");
+            }
+            let _ = lost_proxy.send_event(UserEvent::DeviceLost(message));
+        });
+        // the window may have been resized while the device was being opened
+        let size = window_arc.inner_size();
+        window_arc.set_title(TITLE);
+
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = surface_caps.formats.iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+        
+        let preferences = preferences::Preferences::load();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            present_mode: gui::present_mode(preferences.settings.vsync, &surface_caps.present_modes),
+            alpha_mode: if options.overlay {
+                // the clear colour and alpha blending both produce premultiplied output
+                [wgpu::CompositeAlphaMode::PreMultiplied, wgpu::CompositeAlphaMode::Inherit]
+                    .into_iter()
+                    .find(|mode| surface_caps.alpha_modes.contains(mode))
+                    .unwrap_or_else(|| {
+                        log::warn!("surface does not support transparency, the overlay will be opaque");
+                        surface_caps.alpha_modes[0]
+                    })
+            } else {
+                surface_caps.alpha_modes[0]
+            },
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        let mut engine_config = if options.safe_mode {
+            graphing_engine::Config::safe_mode()
+        } else {
+            graphing_engine::Config::default()
+        };
+        engine_config.pipeline_cache_path = wgpu::util::pipeline_cache_key(&adapter.get_info())
+            .map(|key| std::env::temp_dir().join("graphing-calculator").join(key));
+        engine_config.gpu_profiling = options.profile_gpu;
+        let target = graphing_engine::RenderTargetConfig::new(config.format);
+
+        // building the engine's pipelines and loading fonts takes a moment, so say so meanwhile
+        let mut gui_renderer = gui::GuiRenderer::new(&device, egui_state, &target);
+        gui_renderer.ctx().set_zoom_factor(preferences.settings.ui_scale);
+        if size.width > 0 && size.height > 0 {
+            surface.configure(&device, &config);
+            draw_splash(&device, &queue, &surface, &config, &window_arc, &mut gui_renderer);
+        }
+
+        let mut graphing_engine = State::new(
+            &device,
+            &queue,
+            size,
+            &target,
+            &engine_config,
+            window_arc.scale_factor() as f32,
+        );
+        if let Some(bounds) = preferences.default_viewport {
+            graphing_engine.fit_bounds(bounds);
+        }
+
+        let diagnostics = gui::Diagnostics::new(&adapter.get_info(), options.safe_mode);
+
+        let mut toasts = gui::Toasts::new();
+        let mut workspace = Workspace::new("Graph 1".to_owned(), graphing_engine);
+        workspace.script_commands = options.script.as_ref().and_then(|path| {
+            match scripting::ScriptHost::load(path) {
+                Ok(mut host) => {
+                    let commands = host.commands();
+                    workspace.engine.on_event(move |event| host.handle(event));
+                    Some(commands)
+                }
+                Err(e) => {
+                    toasts.error(format!("Could not load script {}: {e}", path.display()));
+                    None
+                }
+            }
+        });
+        let remote = options.listen.as_deref().and_then(|address| {
+            let wake = move || {
+                let _ = proxy.send_event(UserEvent::Remote);
+            };
+            match remote::RemoteServer::listen(address, wake) {
+                Ok(server) => {
+                    // the port is only known here when 0 was asked for
+                    window_arc.set_title(&format!("{TITLE} (listening on {})", server.address()));
+                    Some(server)
+                }
+                Err(e) => {
+                    toasts.error(format!("Could not listen for remote control on {address}: {e}"));
+                    None
+                }
+            }
+        });
+
+        let mut state = Self {
+            instance,
+            surface: Some(surface),
+            device,
+            queue,
+            config,
+            present_modes: surface_caps.present_modes,
+            preferences,
+            last_frame: None,
+            redraw_at: Some(Instant::now()),
+            overlay: options.overlay,
+            close_requested: false,
+            size,
+            window: window_arc,
+            cursor: graphing_engine::CursorIcon::Default,
+            workspaces: vec![workspace],
+            active: 0,
+            engine_config,
+            gui_renderer,
+            target,
+            plot_view: gui::PlotView::new(),
+            diagnostics,
+            csv_import: None,
+            csv_export: None,
+            screenshot_scale: 2,
+            screenshot: None,
+            help: gui::Help::new(),
+            toasts,
+            history: history::History::load(),
+            remote,
+        };
+        // a recovered graph may be what crashed the calculator, so safe mode leaves it on disk for
+        // the next normal start
+        if !options.safe_mode {
+            state.restore_recovered();
+        } else if recovery::is_waiting() {
+            state.toasts.error("Graphs from when the calculator closed unexpectedly weren't restored in safe mode, and will be on the next normal start");
+        }
+        Ok(state)
+    }
+
+    /// Creates an engine for a new workspace, drawing into the window's target.
+    fn new_engine(&self) -> State {
+        let mut engine = State::new(
+            &self.device,
+            &self.queue,
+            self.size,
+            &self.target,
+            &self.engine_config,
+            self.window.scale_factor() as f32,
+        );
+        if let Some(bounds) = self.preferences.default_viewport {
+            engine.fit_bounds(bounds);
+        }
+        engine
+    }
+
+    /// Reopens the workspaces written out when the calculator last crashed, if it did, the first
+    /// in place of the empty one it starts with.
+    fn restore_recovered(&mut self) {
+        let recovered = match recovery::take() {
+            Ok(Some(recovered)) => recovered,
+            Ok(None) => return,
+            Err(e) => {
+                self.toasts.error(format!("Could not restore the graphs from when the calculator closed unexpectedly: {e:#}"));
+                return;
+            }
+        };
+
+        for (i, recovery::RecoveredWorkspace { name, session }) in recovered.into_iter().enumerate() {
+            if i == 0 {
+                self.workspaces[0].name = name;
+            } else {
+                let engine = self.new_engine();
+                self.workspaces.push(Workspace::new(name, engine));
+            }
+            let workspace = self.workspaces.last_mut().expect("there is always a workspace");
+            if let Err(e) = workspace.restore(session, &self.device, &self.queue) {
+                self.toasts.error(format!("Could not fully restore {}: {e:#}", workspace.name));
+            }
+        }
+        self.toasts.error("The calculator closed unexpectedly last time, and its graphs were restored");
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn size(&self) -> winit::dpi::PhysicalSize<u32> {
+        self.size
+    }
+    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        if new_size.width > 0 && new_size.height > 0 {
+            self.size = new_size;
+
+            self.config.width = new_size.width;
+            self.config.height = new_size.height;
+
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+        }
+    }
+
+    /// Drops the surface, which the system may destroy while the app is suspended, and saves
+    /// what shutdown does in case the app isn't resumed.
+    pub fn suspend(&mut self) {
+        self.shutdown();
+        self.surface = None;
+    }
+
+    /// Recreates the surface dropped by suspend for the window, at its current size.
+    pub fn resume(&mut self) -> Result<()> {
+        if self.surface.is_some() {
+            return Ok(());
+        }
+        let surface = self.instance.create_surface(self.window.clone())?;
+        self.surface = Some(surface);
+        // the screen may have been rotated meanwhile
+        self.resize(self.window.inner_size());
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Saves anything which should outlive the window. Call before exiting.
+    pub fn shutdown(&mut self) {
+        // workspaces share a cache file, and any of them has every pipeline in it
+        if let Err(e) = self.workspaces[self.active].engine.save_pipeline_cache() {
+            log::warn!("failed to save pipeline cache: {e}");
+        }
+        if let Err(e) = self.preferences.save() {
+            log::warn!("failed to save preferences: {e:#}");
+        }
+    }
+
+    /// Marks the window as needing to be redrawn as soon as possible.
+    pub fn invalidate(&mut self) {
+        self.schedule_redraw(Duration::ZERO);
+    }
+
+    /// Makes sure a frame is drawn within delay.
+    fn schedule_redraw(&mut self, delay: Duration) {
+        let Some(at) = Instant::now().checked_add(delay) else {
+            return;
+        };
+        self.redraw_at = Some(self.redraw_at.map_or(at, |redraw_at| redraw_at.min(at)));
+    }
+
+    /// Returns when the next frame should be drawn, respecting the frame rate cap, or None if
+    /// nothing needs drawing.
+    pub fn next_frame(&self) -> Option<Instant> {
+        // there is nothing to draw into while suspended
+        self.surface.as_ref()?;
+        let at = self.redraw_at?;
+        let earliest = self.last_frame.zip(self.preferences.settings.frame_interval())
+            .map(|(last_frame, interval)| last_frame + interval);
+
+        Some(earliest.map_or(at, |earliest| at.max(earliest)))
+    }
+
+    fn apply_settings(&mut self) {
+        let present_mode = gui::present_mode(self.preferences.settings.vsync, &self.present_modes);
+        if present_mode != self.config.present_mode {
+            log::info!("switching present mode to {present_mode:?}");
+            self.config.present_mode = present_mode;
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+        }
+        // takes effect from the next frame, so the panel isn't laid out at one scale and drawn at
+        // another
+        self.gui_renderer.ctx().set_zoom_factor(self.preferences.settings.ui_scale);
+    }
+
+    pub fn apply_script_commands(&mut self) {
+        for workspace in &mut self.workspaces {
+            let Some(commands) = &workspace.script_commands else {
+                continue;
+            };
+
+            for command in commands.borrow_mut().drain(..) {
+                match command {
+                    scripting::ScriptCommand::SetEquation { index, text } => {
+                        if !workspace.equation_list.set_equation(index, text, &mut workspace.engine) {
+                            log::warn!("script set equation {index} which does not exist");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_tab_action(&mut self, action: TabAction) {
+        match action {
+            TabAction::Select(index) => self.active = index,
+            TabAction::Add => {
+                let engine = self.new_engine();
+                let name = format!("Graph {}", self.workspaces.len() + 1);
+                self.workspaces.push(Workspace::new(name, engine));
+                self.active = self.workspaces.len() - 1;
+            }
+            TabAction::Close(index) => {
+                self.workspaces.remove(index);
+                self.active = workspace::active_after_close(self.active, index, self.workspaces.len());
+            }
+        }
+        self.invalidate();
+    }
+
+    /// Asks which columns of a dropped CSV file to plot as points, and with which marker.
+    pub fn drop_file(&mut self, path: &std::path::Path) {
+        if !path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv")) {
+            log::warn!("ignoring dropped file {}, which is not a CSV file", path.display());
+            return;
+        }
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let import = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| data::Table::parse(&text))
+            .map(|table| gui::CsvImport::new(name, table));
+        match import {
+            Ok(import) => self.csv_import = Some(import),
+            Err(e) => self.toasts.error(format!("Could not import {}: {e:#}", path.display())),
+        }
+        self.invalidate();
+    }
+
+    /// Adds the columns picked from a CSV file to the active graph as a series, in the next colour
+    /// of the palette. Rows which couldn't be plotted are reported by line.
+    fn import_points(&mut self, import: &gui::CsvImport) {
+        let samples = import.samples();
+        if !samples.errors.is_empty() {
+            for error in &samples.errors {
+                log::warn!("skipped a row of {}, {error}", samples.name);
+            }
+            let mut message = format!("Skipped {} rows of {}", samples.errors.len(), samples.name);
+            for error in samples.errors.iter().take(MAX_REPORTED_ROWS) {
+                message.push_str(&format!("\n{error}"));
+            }
+            if samples.errors.len() > MAX_REPORTED_ROWS {
+                message.push_str("\n…");
+            }
+            self.toasts.error(message);
+        }
+
+        let series_list = &mut self.workspaces[self.active].series_list;
+        series_list.add(graphing_engine::Series {
+            name: samples.name,
+            color: gui::PALETTE[series_list.list().len() % gui::PALETTE.len()],
+            marker: import.marker,
+            connected: import.connected,
+            visible: true,
+            chart: import.chart,
+            points: samples.points,
+            categories: samples.categories,
+        });
+    }
+
+    /// Asks where to save CSV text, suggesting a file named after name, and writes it there.
+    fn save_csv(&mut self, name: &str, csv: String) {
+        let Some(path) = dialog::save_file(&[("CSV", &["csv"])], &format!("{name}.csv")) else {
+            return;
+        };
+
+        if let Err(e) = std::fs::write(&path, csv) {
+            self.toasts.error(format!("Could not export to {}: {e}", path.display()));
+        }
+    }
+
+    /// Writes the series at index to a CSV file, with its categories as x if it has them.
+    fn export_series(&mut self, index: usize) {
+        let workspace = &self.workspaces[self.active];
+        let Some(series) = workspace.series_list.list().get(index) else {
+            return;
+        };
+        let name = match series.name.trim() {
+            "" => format!("{} series {}", workspace.name, index + 1),
+            name => name.to_owned(),
+        };
+        let csv = data::to_csv("x", &name, &series.points, &series.categories);
+        self.save_csv(&name, csv);
+    }
+
+    /// Writes samples of the equation picked in export to a CSV file.
+    fn export_equation(&mut self, export: &gui::CsvExport) {
+        let workspace = &self.workspaces[self.active];
+        let csv = data::to_csv("x", &export.name, &export.points(&workspace.engine), &[]);
+        let name = format!("{} samples", workspace.name);
+        self.save_csv(&name, csv);
+    }
+
+    /// Asks where to save a screenshot and starts rendering the graph there at the screenshot
+    /// scale.
+    fn save_screenshot(&mut self, plot_size: PhysicalSize<u32>) {
+        if self.screenshot.is_some() {
+            self.toasts.error("A screenshot is already being saved");
+            return;
+        }
+        let name = format!("{}.png", self.workspaces[self.active].name);
+        let Some(path) = dialog::save_file(&[("PNG image", &["png"])], &name) else {
+            return;
+        };
+
+        self.screenshot = Some(screenshot::Pending::new(path, plot_size, self.screenshot_scale));
+    }
+
+    /// Saves the pending screenshot if the graph's lines have been tessellated at its size, or
+    /// leaves it to be tried again next frame.
+    fn capture_screenshot(&mut self) {
+        let Some(pending) = self.screenshot.take() else {
+            return;
+        };
+        let result = pending.try_capture(&self.device, &self.queue, &self.target, &mut self.workspaces[self.active].engine);
+        // the engine was set up for the screenshot and is put back by the next frame
+        self.invalidate();
+        let response = match result {
+            Ok(false) => {
+                self.screenshot = Some(pending);
+                return;
+            }
+            Ok(true) => remote::Response::ok(),
+            Err(e) => {
+                if pending.request.is_none() {
+                    self.toasts.error(format!("Could not save a screenshot to {}: {e:#}", pending.path().display()));
+                }
+                remote::Response::error(format!("{e:#}"))
+            }
+        };
+        if let Some(request) = pending.request {
+            request.reply(response);
+        }
+    }
+
+    /// Carries out the requests from remote clients, answering each.
+    fn apply_remote_requests(&mut self, plot_size: PhysicalSize<u32>) {
+        let Some(remote) = &self.remote else {
+            return;
+        };
+        for pending in remote.take_requests() {
+            match self.apply_remote_request(&pending.request, plot_size) {
+                Some(response) => pending.reply(response),
+                // answered once the screenshot is saved, frames later
+                None => if let Some(screenshot) = &mut self.screenshot {
+                    screenshot.request = Some(pending);
+                },
+            }
+        }
+    }
+
+    /// Carries out request, returning its response, or None for a screenshot which is answered
+    /// once it is saved.
+    fn apply_remote_request(&mut self, request: &remote::Request, plot_size: PhysicalSize<u32>) -> Option<remote::Response> {
+        use remote::{Request, Response};
+
+        self.invalidate();
+        let workspace = &mut self.workspaces[self.active];
+        let list = &mut workspace.equation_list;
+        let response = match request {
+            Request::AddEquation { text } => {
+                let index = list.equations().count();
+                let equation = session::Equation {
+                    text: text.clone(),
+                    name: String::new(),
+                    color: gui::PALETTE[index % gui::PALETTE.len()],
+                    visible: true,
+                    style: graphing_engine::LineStyle::default(),
+                };
+                list.add(equation, &mut workspace.engine, &self.device, &self.queue);
+                Response::equation(index, list.error(index).map(ToString::to_string))
+            }
+            Request::UpdateEquation { index, text } => {
+                if !list.set_equation(*index, text.clone(), &mut workspace.engine) {
+                    return Some(Response::error(format!("there is no equation {index}")));
+                }
+                Response::equation(*index, list.error(*index).map(ToString::to_string))
+            }
+            Request::RemoveEquation { index } => {
+                if !list.remove(*index, &mut workspace.engine) {
+                    return Some(Response::error(format!("there is no equation {index}")));
+                }
+                Response::ok()
+            }
+            &Request::SetViewport { x_min, x_max, y_min, y_max } => {
+                if !(x_min < x_max && y_min < y_max) {
+                    return Some(Response::error("the minimums must be less than the maximums"));
+                }
+                workspace.engine.fit_bounds(graphing_engine::Bounds { x_min, x_max, y_min, y_max });
+                Response::ok()
+            }
+            Request::Screenshot { path } => {
+                if self.screenshot.is_some() {
+                    return Some(Response::error("a screenshot is already being saved"));
+                }
+                match remote::screenshot_path(path) {
+                    Ok(path) => {
+                        self.screenshot = Some(screenshot::Pending::new(path.to_owned(), plot_size, self.screenshot_scale));
+                        return None;
+                    }
+                    Err(e) => Response::error(format!("{e:#}")),
+                }
+            }
+        };
+        Some(response)
+    }
+
+    fn apply_file_action(&mut self, action: FileAction) {
+        let workspace = &mut self.workspaces[self.active];
+        let open = |workspace: &mut Workspace, path: PathBuf| Session::load(&path).and_then(|session| {
+            let restored = workspace.restore(session, &self.device, &self.queue);
+            workspace.set_path(path);
+            restored
+        });
+        let result = match action {
+            FileAction::Open => dialog::pick_file(&[session::FILTER]).map_or(Ok(()), |path| open(workspace, path)),
+            FileAction::OpenRecent(index) => match self.preferences.recent_files.get(index) {
+                Some(path) => open(workspace, path.clone()),
+                None => Ok(()),
+            },
+            FileAction::Save | FileAction::SaveAs => {
+                let path = match (&workspace.path, action) {
+                    (Some(path), FileAction::Save) => Some(path.clone()),
+                    _ => dialog::save_file(&[session::FILTER], &format!("{}.json", workspace.name)),
+                };
+                match path {
+                    Some(path) => workspace.session().save(&path).map(|_| workspace.set_path(path)),
+                    None => Ok(()),
+                }
+            }
+            FileAction::Export => dialog::save_file(&[("Text", &["txt"]), ("JSON", &["json"])], &format!("{}.txt", workspace.name))
+                .map_or(Ok(()), |path| workspace.expressions().export(&path)),
+        };
+
+        match result {
+            Err(e) => {
+                let failed = match action {
+                    FileAction::Open | FileAction::OpenRecent(_) => "Could not open the session",
+                    FileAction::Save | FileAction::SaveAs => "Could not save the session",
+                    FileAction::Export => "Could not export the expressions",
+                };
+                self.toasts.error(format!("{failed}: {e:#}"));
+            }
+            // the workspace's path is that of the session just opened or saved
+            Ok(()) if action != FileAction::Export => {
+                if let Some(path) = &self.workspaces[self.active].path {
+                    self.preferences.record_recent(path);
+                }
+            }
+            Ok(()) => {}
+        }
+        self.invalidate();
+    }
+
+    pub fn input(&mut self, event: &WindowEvent) -> bool {
+        let _span = tracing::debug_span!("input").entered();
+        let gui_response = self.gui_renderer.input(&self.window, event);
+        if gui_response.repaint {
+            self.invalidate();
+        }
+        if gui_response.consumed {
+            return true;
+        }
+
+        // the view is held still while a screenshot of it is waiting to be saved
+        if self.screenshot.is_some() {
+            return false;
+        }
+        let Some(mut input) = graphing_engine::EngineInput::from_window_event_with(event, &self.preferences.key_bindings) else {
+            return false;
+        };
+        if let graphing_engine::EngineInput::PointerMove { x, y }
+            | graphing_engine::EngineInput::Touch { x, y, .. } = &mut input
+        {
+            (*x, *y) = self.plot_view.to_texture_space(*x, *y);
+        }
+        let response = self.workspaces[self.active].engine.input(&input);
+        if response.consumed {
+            self.invalidate();
+        }
+        // only forward changes so the cursor egui picks over the panel is left alone
+        if response.cursor != self.cursor {
+            self.cursor = response.cursor;
+            self.window.set_cursor(match response.cursor {
+                graphing_engine::CursorIcon::Default => winit::window::CursorIcon::Default,
+                graphing_engine::CursorIcon::Grabbing => winit::window::CursorIcon::Grabbing,
+                graphing_engine::CursorIcon::Crosshair => winit::window::CursorIcon::Crosshair,
+            });
+        }
+
+        response.consumed
+    }
+
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let _span = tracing::info_span!("frame").entered();
+        self.last_frame = Some(Instant::now());
+        self.redraw_at = None;
+        let Some(surface) = &self.surface else {
+            return Ok(());
+        };
+        let output = surface.get_current_texture()?;
+
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render Encoder"),
+        });
+
+        // the panel is laid out before the graph is drawn so the graph can fill the space left over
+        self.gui_renderer.begin_pass(&self.window);
+        // egui follows the system theme from winit's events, and the graph follows egui
+        self.gui_renderer.ctx().set_theme(self.preferences.settings.theme);
+
+        let mut settings_changed = self.preferences.settings.ui_scale_shortcuts(self.gui_renderer.ctx());
+        let mut tab_action = None;
+        let mut file_action = None;
+        let mut screenshot_requested = false;
+        let mut series_export = None;
+        egui::SidePanel::new(
+            egui::panel::Side::Left, 
+            egui::Id::new("left panel")
+            )
+            .show(self.gui_renderer.ctx(), |ui| {
+                if self.overlay {
+                    ui.horizontal(|ui| {
+                        let handle = ui.add(egui::Label::new("graphing calculator").sense(egui::Sense::drag()))
+                            .on_hover_cursor(egui::CursorIcon::Move);
+                        if handle.drag_started() {
+                            if let Err(e) = self.window.drag_window() {
+                                log::warn!("failed to move window: {e}");
+                            }
+                        }
+                        if ui.button("Close").clicked() {
+                            self.close_requested = true;
+                        }
+                    });
+                    ui.separator();
+                }
+                ui.horizontal(|ui| {
+                    file_action = session::show_menu(ui, &self.preferences.recent_files);
+                    screenshot_requested = screenshot::show_button(ui, &mut self.screenshot_scale);
+                    self.help.show_button(ui);
+                });
+                tab_action = workspace::show_tabs(ui, &self.workspaces, self.active);
+                ui.separator();
+
+                let workspace = &mut self.workspaces[self.active];
+                if let Some(export) = workspace.equation_list.show(ui, &mut workspace.engine, &self.device, &self.queue, &mut self.history) {
+                    self.csv_export = Some(export);
+                }
+                match workspace.series_list.show(ui) {
+                    Some(SeriesAction::Fit(request)) => {
+                        if let Err(err) = workspace.add_fit(request, &self.device, &self.queue) {
+                            log::error!("Failed to fit a curve: {err}");
+                            self.toasts.error(format!("Couldn't fit a {} curve: {err}", request.model.name().to_lowercase()));
+                        }
+                    }
+                    Some(SeriesAction::Export(index)) => series_export = Some(index),
+                    None => {}
+                }
+                workspace.parameters.sync(workspace.equation_list.equations(), &mut workspace.engine);
+                workspace.parameters.show(ui, &mut workspace.engine);
+                workspace.analysis.show(ui, workspace.equation_list.selected_row(), &workspace.engine);
+                gui::points::show(ui, &mut workspace.engine);
+                workspace.complex_map.show(ui, &mut workspace.engine);
+                workspace.streamlines.show(ui, &mut workspace.engine);
+                workspace.polar_shading.show(ui, &mut workspace.engine);
+                ui.separator();
+                if gui::titles::show(ui, &mut workspace.titles) {
+                    workspace.engine.set_titles(&workspace.titles);
+                }
+                self.diagnostics.show(ui, workspace.engine.gpu_timings());
+                // or'd in, as a shortcut may already have changed the scale this frame
+                settings_changed |= self.preferences.settings.show(ui);
+                self.preferences.show_default_viewport(ui, workspace.engine.visible_bounds());
+            });
+        if let Some(action) = tab_action {
+            self.apply_tab_action(action);
+        }
+        if let Some(action) = file_action {
+            self.apply_file_action(action);
+        }
+
+        // the graph fills whatever the panel leaves, at the chosen fraction of the window's resolution
+        let ctx = self.gui_renderer.ctx().clone();
+        self.help.show(&ctx, &self.preferences.key_bindings);
+        if let Some(action) = self.csv_import.as_mut().and_then(|import| import.show(&ctx)) {
+            let import = self.csv_import.take();
+            if let (ImportAction::Import, Some(import)) = (action, import) {
+                self.import_points(&import);
+            }
+        }
+        if let Some(action) = self.csv_export.as_mut().and_then(|export| export.show(&ctx)) {
+            let export = self.csv_export.take();
+            if let (ExportAction::Export, Some(export)) = (action, export) {
+                self.export_equation(&export);
+            }
+        }
+        if let Some(index) = series_export {
+            self.export_series(index);
+        }
+        let plot_rect = ctx.available_rect();
+        let resolution = self.preferences.settings.plot_resolution;
+        let plot_size = self.plot_view.resize(
+            &self.device,
+            &mut self.gui_renderer,
+            &self.target,
+            plot_rect,
+            ctx.pixels_per_point(),
+            resolution,
+        );
+        self.plot_view.paint(&ctx, plot_rect);
+        let workspace = &mut self.workspaces[self.active];
+        let engine = &mut workspace.engine;
+        match self.plot_view.zoom_controls(&ctx) {
+            Some(gui::ZoomControl::In) => engine.zoom_steps(1.0),
+            Some(gui::ZoomControl::Out) => engine.zoom_steps(-1.0),
+            Some(gui::ZoomControl::Home) => engine.reset_view(),
+            None => {}
+        }
+        for marker in workspace.equation_list.markers(engine) {
+            self.plot_view.paint_marker(&ctx, engine.world_to_screen(marker.world), marker.color, &marker.text);
+        }
+        for &index in engine.selected_points() {
+            if let Some(point) = engine.points().get(index) {
+                let text = engine.point_coordinates(point);
+                self.plot_view.paint_point_selection(&ctx, engine.world_to_screen(point.position), point.radius, &text);
+            }
+        }
+        if let Some(hovered) = engine.hovered_point() {
+            self.plot_view.show_point_tooltip(&ctx, &hovered);
+        }
+        if self.preferences.settings.crosshair {
+            if let Some(crosshair) = engine.crosshair() {
+                self.plot_view.paint_crosshair(&ctx, &crosshair);
+            }
+        }
+        if let Some(selection) = engine.selection() {
+            self.plot_view.paint_selection(&ctx, selection);
+        }
+
+        engine.set_scale_factor(self.window.scale_factor() as f32 * resolution);
+        engine.set_projection(self.preferences.settings.projection);
+        engine.set_snap(self.preferences.settings.snap);
+        let mut theme = match ctx.theme() {
+            egui::Theme::Dark => graphing_engine::Theme::dark(),
+            egui::Theme::Light => graphing_engine::Theme::default(),
+        };
+        if self.overlay {
+            theme = theme.transparent();
+        }
+        theme.grid.minor_subdivisions = self.preferences.settings.grid_subdivisions;
+        theme.grid.axis_arrows = self.preferences.settings.axis_arrows;
+        theme.grid.origin_label = self.preferences.settings.origin_label;
+        theme.grid.quadrant_labels = self.preferences.settings.quadrant_labels;
+        engine.set_theme(theme);
+        engine.set_pi_ticks(self.preferences.settings.pi_ticks);
+        engine.set_label_size(self.preferences.settings.label_size);
+        // the same series aren't tried again, so this is only shown once
+        if let Err(e) = engine.set_series(workspace.series_list.list()) {
+            self.toasts.error(format!("The data series can't be drawn: {e}"));
+        }
+        engine.set_legend(self.preferences.settings.legend.then(|| graphing_engine::Legend {
+            corner: self.preferences.settings.legend_corner,
+            entries: workspace.equation_list.legend_entries().into_iter().chain(workspace.series_list.legend_entries()).collect(),
+        }));
+        // drawing at the plot's size would start the pending screenshot's tessellation over, so the
+        // plot keeps showing its last frame until the screenshot is saved
+        if self.screenshot.is_none() {
+            engine.set_viewport(graphing_engine::Viewport {
+                x: 0.0,
+                y: 0.0,
+                size: plot_size,
+            });
+            engine.update(&self.device, &self.queue);
+        }
+
+        if let Some(plot_view) = self.plot_view.view().filter(|_| self.screenshot.is_none()) {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Plot Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: plot_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(engine.theme().clear_color()),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            engine.grid_prepare(&self.device, &self.queue);
+            
+            if let Err(e) = engine.render(&mut render_pass, plot_size) {
+                self.toasts.error(format!("Drawing the graph failed: {e}"));
+            }
+        }
+        engine.resolve_profiler(&mut encoder);
+        for error in engine.take_errors() {
+            self.toasts.error(format!("Drawing the graph failed: {error}"));
+        }
+        // saving is skipped unless an expression was entered this frame
+        if let Err(e) = self.history.save() {
+            self.toasts.error(format!("Saving the expression history failed: {e}"));
+        }
+        self.toasts.show(&ctx);
+
+        let repaint_delay;
+        {
+            let screen_descriptor = egui_wgpu::ScreenDescriptor {
+                size_in_pixels: [self.config.width, self.config.height],
+                pixels_per_point: ctx.pixels_per_point(),
+            };
+
+            let _span = tracing::debug_span!("gui_render").entered();
+            repaint_delay = self.gui_renderer.render(
+                &self.device,
+                &self.queue,
+                &mut encoder,
+                &self.window,
+                &view,
+                &screen_descriptor,
+                engine.theme().clear_color(),
+            );
+        }
+
+        tracing::debug_span!("present").in_scope(|| {
+            self.queue.submit(std::iter::once(encoder.finish()));
+            output.present();
+        });
+        if screenshot_requested {
+            self.save_screenshot(plot_size);
+        }
+        let engine = &mut self.workspaces[self.active].engine;
+        engine.trim_atlas();
+        engine.read_profiler(&self.device);
+        self.schedule_redraw(repaint_delay);
+        // scripts react to events from this frame's update, and their edits are drawn next frame
+        self.apply_script_commands();
+        self.apply_remote_requests(plot_size);
+        self.capture_screenshot();
+        if self.workspaces[self.active].engine.needs_redraw() {
+            self.invalidate();
+        }
+
+        // the surface can't be reconfigured while a frame from it is held
+        if settings_changed {
+            self.apply_settings();
+        }
+        let workspaces = &self.workspaces;
+        let skipped = recovery::snapshot(|| workspaces.iter()
+            .map(|workspace| recovery::RecoveredWorkspace {
+                name: workspace.name.clone(),
+                session: workspace.session(),
+            })
+            .collect());
+        // the event loop sleeps while nothing changes, so without another frame this one's edits
+        // would wait for the next input to be copied
+        if let Some(wait) = skipped {
+            self.schedule_redraw(wait);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use graphing_engine::{Bounds, KeyBindings};
+use serde::{Deserialize, Serialize};
+
+use crate::gui::Settings;
+
+/// Most sessions listed under Open Recent, dropping the oldest beyond this.
+const MAX_RECENT_FILES: usize = 10;
+
+/// Options kept between runs in a TOML file in the user's config directory, which can be edited
+/// by hand. Missing entries take their defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// The region of the graph new workspaces open on, instead of the usual one around the
+    /// origin.
+    pub default_viewport: Option<Bounds>,
+    /// Sessions opened or saved, newest first.
+    pub recent_files: Vec<PathBuf>,
+    /// Keys the graph is panned with in place of the usual ones, as lists of UI Events codes
+    /// such as Up = ["KeyI"].
+    pub key_bindings: KeyBindings,
+    pub settings: Settings,
+}
+
+/// Returns where the preferences are kept, in the user's config directory.
+fn path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("graphing-calculator").join("preferences.toml"))
+}
+
+impl Preferences {
+    /// Reads the preferences saved by an earlier run, using the defaults if there aren't any or
+    /// they can't be read.
+    pub fn load() -> Self {
+        let Some(path) = path().filter(|path| path.exists()) else {
+            return Self::default();
+        };
+        let preferences = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(toml::from_str(&text)?));
+        match preferences {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                log::warn!("couldn't read preferences from {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = path().ok_or_else(|| anyhow!("there is no config directory to save the preferences in"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Moves path to the top of the recent files, adding it if it isn't there.
+    pub fn record_recent(&mut self, path: &Path) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_owned());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Shows buttons to make the view of the graph the one new workspaces open on, or go back to
+    /// the usual one.
+    pub fn show_default_viewport(&mut self, ui: &mut egui::Ui, current: Bounds) {
+        ui.horizontal(|ui| {
+            ui.label("Default view");
+            if ui.button("Use current").on_hover_text("Open new graphs on the current view").clicked() {
+                self.default_viewport = Some(current);
+            }
+            if ui.add_enabled(self.default_viewport.is_some(), egui::Button::new("Reset")).clicked() {
+                self.default_viewport = None;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use graphing_engine::Key;
+
+    use super::*;
+
+    #[test]
+    fn preferences_survive_a_round_trip() {
+        let mut preferences = Preferences {
+            default_viewport: Some(Bounds { x_min: -2.0, x_max: 2.0, y_min: -1.0, y_max: 1.0 }),
+            recent_files: vec![PathBuf::from("/tmp/graph.json")],
+            ..Default::default()
+        };
+        preferences.settings.fps_cap = Some(30);
+        preferences.settings.theme = egui::ThemePreference::Dark;
+        preferences.key_bindings.bind(Key::Left, vec!["KeyJ".to_owned(), "ArrowLeft".to_owned()]);
+
+        let text = toml::to_string_pretty(&preferences).unwrap();
+
+        assert_eq!(toml::from_str::<Preferences>(&text).unwrap(), preferences);
+    }
+
+    #[test]
+    fn missing_entries_take_their_defaults() {
+        let preferences = toml::from_str::<Preferences>("[settings]\nvsync = false\n").unwrap();
+
+        assert!(!preferences.settings.vsync);
+        assert_eq!(preferences.settings.label_size, Settings::default().label_size);
+        assert_eq!(preferences.default_viewport, None);
+        assert!(preferences.recent_files.is_empty());
+    }
+
+    #[test]
+    fn key_bindings_are_read_by_key() {
+        let preferences = toml::from_str::<Preferences>("[key_bindings]\nUp = [\"KeyI\"]\n").unwrap();
+
+        assert_eq!(preferences.key_bindings.key_for_code("KeyI"), Some(Key::Up));
+        assert_eq!(preferences.key_bindings.key_for_code("KeyW"), None);
+        assert_eq!(preferences.key_bindings.key_for_code("KeyA"), Some(Key::Left));
+    }
+
+    #[test]
+    fn recent_files_are_newest_first_and_capped() {
+        let mut preferences = Preferences::default();
+        for i in 0..=MAX_RECENT_FILES {
+            preferences.record_recent(Path::new(&format!("{i}.json")));
+        }
+        preferences.record_recent(Path::new("5.json"));
+
+        assert_eq!(preferences.recent_files.len(), MAX_RECENT_FILES);
+        assert_eq!(preferences.recent_files[0], Path::new("5.json"));
+        assert_eq!(preferences.recent_files[1], Path::new(&format!("{MAX_RECENT_FILES}.json")));
+        assert!(!preferences.recent_files.contains(&PathBuf::from("0.json")));
+    }
+}
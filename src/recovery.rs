@@ -0,0 +1,154 @@
+//! Keeps a copy of the workspaces where a panic or a lost graphics device can still reach it, and
+//! writes it out so the graphs can be restored on the next start.
+
+use std::path::PathBuf;
+use std::sync::{Mutex, TryLockError};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::session::Session;
+
+/// How often the copy is brought up to date. Imported series can be large, so it isn't copied
+/// every frame, and edits in the last moment before a crash may be lost. A frame within this of
+/// the last copy has another drawn once it has passed, to copy the edits made in it.
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A workspace as it was when the calculator crashed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecoveredWorkspace {
+    pub name: String,
+    pub session: Session,
+}
+
+impl RecoveredWorkspace {
+    /// Returns whether there is anything in the workspace worth restoring.
+    fn has_work(&self) -> bool {
+        let session = &self.session;
+        session.equations.iter().any(|equation| !equation.text.trim().is_empty())
+            || !session.points.is_empty()
+            || !session.series.is_empty()
+    }
+}
+
+struct Snapshot {
+    workspaces: Vec<RecoveredWorkspace>,
+    taken: Option<Instant>,
+}
+
+static SNAPSHOT: Mutex<Snapshot> = Mutex::new(Snapshot { workspaces: Vec::new(), taken: None });
+
+/// Returns where the workspaces are written after a crash, in the user's data directory.
+fn path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("graphing-calculator").join("recovery.json"))
+}
+
+/// Replaces the copy with the workspaces from take, unless it was replaced within the last
+/// SNAPSHOT_INTERVAL. Returns how long until it can be if it wasn't, for the caller to try
+/// again then so the skipped workspaces aren't left out of the copy.
+#[must_use]
+pub fn snapshot(take: impl FnOnce() -> Vec<RecoveredWorkspace>) -> Option<Duration> {
+    let mut snapshot = SNAPSHOT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let wait = snapshot.taken.map_or(Duration::ZERO, |taken| SNAPSHOT_INTERVAL.saturating_sub(taken.elapsed()));
+    if !wait.is_zero() {
+        return Some(wait);
+    }
+    snapshot.workspaces = take();
+    snapshot.taken = Some(Instant::now());
+    None
+}
+
+/// Writes the copy of the workspaces to the recovery file, returning where, or None if there
+/// was nothing worth keeping.
+pub fn write() -> Result<Option<PathBuf>> {
+    let snapshot = match SNAPSHOT.try_lock() {
+        Ok(snapshot) => snapshot,
+        // the copy is only replaced once a new one has been taken, so it is whole either way
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        // the panic happened while the copy was being taken on this thread
+        Err(TryLockError::WouldBlock) => return Err(anyhow!("the workspaces were being copied")),
+    };
+    if !snapshot.workspaces.iter().any(RecoveredWorkspace::has_work) {
+        return Ok(None);
+    }
+
+    let path = path().ok_or_else(|| anyhow!("there is no data directory to save the graphs in"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(&snapshot.workspaces)?)?;
+    Ok(Some(path))
+}
+
+/// Writes the workspaces out when the calculator panics, before the usual message is printed.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match write() {
+            Ok(Some(path)) => eprintln!("the graphs were saved to {} and will be restored on the next start", path.display()),
+            Ok(None) => {}
+            Err(e) => eprintln!("the graphs could not be saved: {e:#}"),
+        }
+        default_hook(info);
+    }));
+}
+
+/// Returns whether there are workspaces written when the calculator last crashed.
+pub fn is_waiting() -> bool {
+    path().is_some_and(|path| path.exists())
+}
+
+/// Reads and removes the workspaces written when the calculator last crashed, if it did.
+pub fn take() -> Result<Option<Vec<RecoveredWorkspace>>> {
+    let Some(path) = path().filter(|path| path.exists()) else {
+        return Ok(None);
+    };
+    let text = std::fs::read_to_string(&path)?;
+    // removed before restoring, so workspaces which crash the calculator again aren't reopened
+    // forever
+    std::fs::remove_file(&path)?;
+    Ok(Some(serde_json::from_str(&text)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use graphing_engine::{Bounds, Color, LineStyle};
+
+    use super::*;
+    use crate::session::Equation;
+
+    #[test]
+    fn snapshots_within_the_interval_are_put_off() {
+        assert_eq!(snapshot(Vec::new), None);
+
+        let wait = snapshot(|| unreachable!("the copy was only just taken"));
+        assert!(wait.is_some_and(|wait| !wait.is_zero() && wait <= SNAPSHOT_INTERVAL));
+    }
+
+    #[test]
+    fn empty_workspaces_are_not_worth_restoring() {
+        let workspace = |text: &str| RecoveredWorkspace {
+            name: "Graph 1".to_owned(),
+            session: Session {
+                equations: vec![Equation {
+                    text: text.to_owned(),
+                    name: String::new(),
+                    color: Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+                    visible: true,
+                    style: LineStyle::default(),
+                }],
+                parameters: Vec::new(),
+                points: Vec::new(),
+                series: Vec::new(),
+                viewport: Bounds { x_min: -1.0, x_max: 1.0, y_min: -1.0, y_max: 1.0 },
+            },
+        };
+
+        assert!(!workspace("  ").has_work());
+        assert!(workspace("x^2").has_work());
+        let mut with_points = workspace("");
+        with_points.session.points.push([1.0, 2.0]);
+        assert!(with_points.has_work());
+    }
+}
@@ -0,0 +1,259 @@
+//! A server which lets other processes, such as notebooks, drive the calculator as a live plot
+//! window. It is started with --listen.
+//!
+//! Clients connect over TCP and send one JSON request per line. Each is answered with one JSON
+//! line once the app has carried it out:
+//!
+//! ```text
+//! {"command": "add_equation", "text": "x^2 - 1"}
+//! {"ok":true,"index":0}
+//! {"command": "update_equation", "index": 0, "text": "x^3"}
+//! {"ok":true,"index":0}
+//! {"command": "set_viewport", "x_min": -5, "x_max": 5, "y_min": -2, "y_max": 2}
+//! {"ok":true}
+//! {"command": "screenshot", "path": "graphs/parabola.png"}
+//! {"ok":true}
+//! {"command": "remove_equation", "index": 3}
+//! {"ok":false,"error":"there is no equation 3"}
+//! ```
+//!
+//! Requests act on the workspace being shown.
+//!
+//! There is no authentication, so the server only listens on loopback addresses, where only
+//! processes on the same machine can connect. Screenshots are only written beneath the directory
+//! the calculator was started in.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Something a client asked the calculator to do.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// Adds an equation after the others.
+    AddEquation { text: String },
+    /// Replaces the text of the equation at index, counting from 0.
+    UpdateEquation { index: usize, text: String },
+    RemoveEquation { index: usize },
+    /// Moves the view so that all of the region is visible.
+    SetViewport { x_min: f32, x_max: f32, y_min: f32, y_max: f32 },
+    /// Saves the graph as a PNG image at path, which is relative to the directory the calculator
+    /// was started in and may not leave it.
+    Screenshot { path: PathBuf },
+}
+
+/// Returns a screenshot's path if it stays beneath the current directory, refusing absolute paths
+/// and any which go up through `..`.
+pub fn screenshot_path(path: &Path) -> Result<&Path> {
+    let inside = path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !inside || path.file_name().is_none() {
+        bail!("screenshots can only be saved to relative paths without .., such as graph.png");
+    }
+    Ok(path)
+}
+
+/// The answer to a request. An equation which fails to parse is still added or updated, so its
+/// index is given alongside the error.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Response {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Response {
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            index: None,
+            error: None,
+        }
+    }
+
+    pub fn error(error: impl ToString) -> Self {
+        Self {
+            ok: false,
+            index: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Answers a request about the equation at index, which failed if error is given.
+    pub fn equation(index: usize, error: Option<String>) -> Self {
+        Self {
+            ok: error.is_none(),
+            index: Some(index),
+            error,
+        }
+    }
+}
+
+/// A request waiting to be carried out, with the connection its response goes back to.
+pub struct Pending {
+    pub request: Request,
+    reply: mpsc::Sender<Response>,
+}
+
+impl Pending {
+    pub fn reply(self, response: Response) {
+        // the client may have disconnected meanwhile
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Accepts connections on a background thread, and passes their requests on to the app.
+pub struct RemoteServer {
+    address: SocketAddr,
+    requests: mpsc::Receiver<Pending>,
+}
+
+impl RemoteServer {
+    /// Listens on address, such as "127.0.0.1:7878", which must be a loopback address as clients
+    /// aren't authenticated. wake is called from a connection's thread whenever it has a request
+    /// waiting, so the app can take it.
+    pub fn listen(address: &str, wake: impl Fn() + Clone + Send + 'static) -> Result<Self> {
+        let addresses = address.to_socket_addrs()?.collect::<Vec<_>>();
+        if let Some(open) = addresses.iter().find(|address| !address.ip().is_loopback()) {
+            bail!("{open} can be reached from other machines, so only loopback addresses such as 127.0.0.1 may be listened on");
+        }
+        let listener = TcpListener::bind(&addresses[..])?;
+        let address = listener.local_addr()?;
+        let (sender, requests) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        log::warn!("failed to accept a remote connection: {e}");
+                        continue;
+                    }
+                };
+                let sender = sender.clone();
+                let wake = wake.clone();
+                std::thread::spawn(move || {
+                    let peer = stream.peer_addr();
+                    if let Err(e) = serve(stream, &sender, wake) {
+                        log::warn!("remote connection from {peer:?} failed: {e}");
+                    }
+                });
+            }
+        });
+
+        log::info!("listening for remote control on {address}");
+        Ok(Self {
+            address,
+            requests,
+        })
+    }
+
+    /// Returns the address being listened on, with the port picked if 0 was asked for.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns the requests which have arrived since this was last called, oldest first.
+    pub fn take_requests(&self) -> Vec<Pending> {
+        self.requests.try_iter().collect()
+    }
+}
+
+/// Answers the requests on a connection in turn until the client closes it or the app exits.
+fn serve(stream: TcpStream, requests: &mpsc::Sender<Pending>, wake: impl Fn()) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => {
+                let (reply, response) = mpsc::channel();
+                if requests.send(Pending { request, reply }).is_err() {
+                    return Ok(());
+                }
+                wake();
+                match response.recv() {
+                    Ok(response) => response,
+                    Err(_) => return Ok(()),
+                }
+            }
+            Err(e) => Response::error(format!("invalid request: {e}")),
+        };
+
+        let mut json = serde_json::to_string(&response)?;
+        json.push('\n');
+        writer.write_all(json.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_read_from_json() {
+        let request: Request = serde_json::from_str(r#"{"command": "update_equation", "index": 2, "text": "x^2"}"#).unwrap();
+        assert_eq!(request, Request::UpdateEquation { index: 2, text: "x^2".to_owned() });
+
+        let request: Request = serde_json::from_str(r#"{"command": "set_viewport", "x_min": -1, "x_max": 1, "y_min": -2, "y_max": 2}"#).unwrap();
+        assert_eq!(request, Request::SetViewport { x_min: -1.0, x_max: 1.0, y_min: -2.0, y_max: 2.0 });
+    }
+
+    #[test]
+    fn responses_leave_out_what_is_not_given() {
+        assert_eq!(serde_json::to_string(&Response::ok()).unwrap(), r#"{"ok":true}"#);
+        assert_eq!(serde_json::to_string(&Response::equation(1, None)).unwrap(), r#"{"ok":true,"index":1}"#);
+        assert_eq!(
+            serde_json::to_string(&Response::error("no such equation")).unwrap(),
+            r#"{"ok":false,"error":"no such equation"}"#,
+        );
+    }
+
+    #[test]
+    fn only_loopback_addresses_are_listened_on() {
+        assert!(RemoteServer::listen("0.0.0.0:0", || {}).is_err());
+        assert!(RemoteServer::listen("[::]:0", || {}).is_err());
+        assert!(RemoteServer::listen("localhost:0", || {}).is_ok());
+    }
+
+    #[test]
+    fn screenshots_stay_beneath_the_current_directory() {
+        assert!(screenshot_path(Path::new("graph.png")).is_ok());
+        assert!(screenshot_path(Path::new("./graphs/graph.png")).is_ok());
+        assert!(screenshot_path(Path::new("/tmp/graph.png")).is_err());
+        assert!(screenshot_path(Path::new("graphs/../../graph.png")).is_err());
+        assert!(screenshot_path(Path::new("")).is_err());
+    }
+
+    #[test]
+    fn requests_are_answered_over_tcp() {
+        let server = RemoteServer::listen("127.0.0.1:0", || {}).unwrap();
+        let mut client = TcpStream::connect(server.address()).unwrap();
+        client.write_all(b"{\"command\": \"remove_equation\", \"index\": 0}\nnot json\n").unwrap();
+
+        let pending = loop {
+            if let Some(pending) = server.take_requests().pop() {
+                break pending;
+            }
+            std::thread::yield_now();
+        };
+        assert_eq!(pending.request, Request::RemoveEquation { index: 0 });
+        pending.reply(Response::ok());
+
+        let mut lines = BufReader::new(client).lines();
+        assert_eq!(lines.next().unwrap().unwrap(), r#"{"ok":true}"#);
+        assert!(lines.next().unwrap().unwrap().starts_with(r#"{"ok":false,"error":"invalid request: "#));
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use winit::dpi::PhysicalSize;
+
+use crate::gui::AccessibleName;
+use crate::remote;
+
+/// Resolution multipliers offered for screenshots.
+pub const SCALES: [u32; 4] = [1, 2, 3, 4];
+
+/// Longest a screenshot waits for the lines to be tessellated at its size.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shows the screenshot button with a menu of resolution multipliers, returning true if it was
+/// clicked.
+pub fn show_button(ui: &mut egui::Ui, scale: &mut u32) -> bool {
+    let clicked = ui.button("📷")
+        .on_hover_text("Save a screenshot of the graph")
+        .accessible_name(egui::WidgetType::Button, "Save screenshot")
+        .clicked();
+    egui::ComboBox::from_id_salt("screenshot scale")
+        .width(40.0)
+        .selected_text(format!("{scale}×"))
+        .show_ui(ui, |ui| {
+            for s in SCALES {
+                ui.selectable_value(scale, s, format!("{s}×"));
+            }
+        })
+        .response
+        .accessible_name(egui::WidgetType::ComboBox, "Screenshot scale");
+    clicked
+}
+
+/// A screenshot waiting for the graph's lines to be tessellated at its size, which is tried again
+/// each frame rather than holding up the event loop.
+pub struct Pending {
+    path: PathBuf,
+    size: PhysicalSize<u32>,
+    scale: u32,
+    started: Instant,
+    /// The remote request it was taken for, answered once it is saved.
+    pub request: Option<remote::Pending>,
+}
+
+impl Pending {
+    /// Starts a screenshot of a plot of size, to be saved to path at scale times its resolution.
+    pub fn new(path: PathBuf, size: PhysicalSize<u32>, scale: u32) -> Self {
+        Self {
+            path,
+            size: PhysicalSize::new(size.width * scale, size.height * scale),
+            scale,
+            started: Instant::now(),
+            request: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Renders the graph offscreen and writes it to the path as a PNG, returning false if its
+    /// lines are still being tessellated so that this should be tried again next frame.
+    ///
+    /// The engine is left set up for the larger render, so the graph shouldn't be drawn at the
+    /// plot's size until the screenshot is saved. Its scale factor is expected to have been set
+    /// for the plot this frame.
+    pub fn try_capture(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &graphing_engine::RenderTargetConfig,
+        engine: &mut graphing_engine::State,
+    ) -> Result<bool> {
+        engine.set_scale_factor(engine.scale_factor() * self.scale as f32);
+        let Some(pixels) = engine.try_capture(device, queue, target, self.size)? else {
+            if self.started.elapsed() > TIMEOUT {
+                bail!("the graph was still being drawn after {} seconds", TIMEOUT.as_secs());
+            }
+            return Ok(false);
+        };
+
+        let file = std::io::BufWriter::new(std::fs::File::create(&self.path)?);
+        let mut encoder = png::Encoder::new(file, self.size.width, self.size.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&pixels)?;
+
+        Ok(true)
+    }
+}
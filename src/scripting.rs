@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use graphing_engine::{EngineEvent, PickResult};
+
+/// Changes requested by a script, applied by the app once the engine has finished updating.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptCommand {
+    SetEquation { index: usize, text: String },
+}
+
+/// Runs a Rhai script whose functions are called when engine events occur.
+///
+/// A script handles an event by defining a function with the matching name:
+///
+/// ```rhai
+/// fn on_viewport_changed(x, y, zoom) {
+///     set_equation(0, "x^2 + " + x);
+/// }
+///
+/// fn on_point_moved(index, x, y) {
+///     set_equation(1, "x + " + y);
+/// }
+///
+/// fn on_selection_changed(kind, id) {
+///     print(kind + " " + id);
+/// }
+/// ```
+pub struct ScriptHost {
+    engine: rhai::Engine,
+    ast: rhai::AST,
+    scope: rhai::Scope<'static>,
+    commands: Rc<RefCell<Vec<ScriptCommand>>>,
+}
+
+impl ScriptHost {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        Self::from_source(&source)
+    }
+
+    pub fn from_source(source: &str) -> Result<Self> {
+        let mut engine = rhai::Engine::new();
+        let commands = Rc::new(RefCell::new(Vec::new()));
+
+        let set_equation_commands = commands.clone();
+        engine.register_fn("set_equation", move |index: rhai::INT, text: &str| {
+            set_equation_commands.borrow_mut().push(ScriptCommand::SetEquation {
+                index: index as usize,
+                text: text.to_owned(),
+            });
+        });
+
+        let ast = engine.compile(source).map_err(|e| anyhow!("{e}"))?;
+        let mut scope = rhai::Scope::new();
+        engine.run_ast_with_scope(&mut scope, &ast).map_err(|e| anyhow!("{e}"))?;
+
+        Ok(Self {
+            engine,
+            ast,
+            scope,
+            commands,
+        })
+    }
+
+    /// Returns the queue which script commands are pushed onto.
+    pub fn commands(&self) -> Rc<RefCell<Vec<ScriptCommand>>> {
+        self.commands.clone()
+    }
+
+    pub fn handle(&mut self, event: &EngineEvent) {
+        let (name, args): (&str, Vec<rhai::Dynamic>) = match event {
+            EngineEvent::ViewportChanged { x, y, zoom, .. } => (
+                "on_viewport_changed",
+                vec![(*x as rhai::FLOAT).into(), (*y as rhai::FLOAT).into(), (*zoom as rhai::FLOAT).into()],
+            ),
+            // kind is "line", "point" or "none", and id is the line id or point index
+            EngineEvent::SelectionChanged { selected } => {
+                let (kind, id) = match selected {
+                    Some(PickResult::Line(id)) => ("line", id.to_raw() as rhai::INT),
+                    Some(PickResult::Point(index)) => ("point", *index as rhai::INT),
+                    None => ("none", -1),
+                };
+                ("on_selection_changed", vec![kind.into(), id.into()])
+            }
+            EngineEvent::PointMoved { index, x, y } => (
+                "on_point_moved",
+                vec![(*index as rhai::INT).into(), (*x as rhai::FLOAT).into(), (*y as rhai::FLOAT).into()],
+            ),
+            // a script setting equations from on_equation_changed would run every frame
+            EngineEvent::EquationChanged { .. } | EngineEvent::PointAdded { .. } | EngineEvent::Error(_) => return,
+        };
+
+        let defined = self.ast.iter_functions().any(|f| f.name == name && f.params.len() == args.len());
+        if defined {
+            if let Err(e) = self.engine.call_fn::<rhai::Dynamic>(&mut self.scope, &self.ast, name, args) {
+                log::error!("script error in {name}: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphing_engine::Bounds;
+
+    const BOUNDS: Bounds = Bounds { x_min: -1.0, x_max: 1.0, y_min: -1.0, y_max: 1.0 };
+
+    #[test]
+    fn test_viewport_hook_pushes_commands() {
+        let mut host = ScriptHost::from_source(r#"
+            fn on_viewport_changed(x, y, zoom) {
+                set_equation(1, "x^" + zoom.to_int());
+            }
+        "#).unwrap();
+
+        host.handle(&EngineEvent::ViewportChanged { x: 0.0, y: 0.0, zoom: 4.0, bounds: BOUNDS });
+
+        assert_eq!(*host.commands().borrow(), [ScriptCommand::SetEquation { index: 1, text: "x^4".to_owned() }]);
+    }
+
+    #[test]
+    fn test_missing_hook_is_ignored() {
+        let mut host = ScriptHost::from_source("let unused = 1;").unwrap();
+
+        host.handle(&EngineEvent::ViewportChanged { x: 0.0, y: 0.0, zoom: 4.0, bounds: BOUNDS });
+
+        assert!(host.commands().borrow().is_empty());
+    }
+}
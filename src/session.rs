@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use graphing_engine::{Bounds, Color, LineStyle, Series};
+use serde::{Deserialize, Serialize};
+
+use crate::dialog;
+use crate::gui;
+
+/// An equation as saved, without its engine label or parse error, which are rebuilt on load.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Equation {
+    pub text: String,
+    /// Name shown in the legend instead of the text, if not empty.
+    #[serde(default)]
+    pub name: String,
+    pub color: Color<f32>,
+    pub visible: bool,
+    #[serde(default)]
+    pub style: LineStyle,
+}
+
+/// Everything needed to reopen a workspace as it was, written to disk as JSON.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub equations: Vec<Equation>,
+    #[serde(default)]
+    pub parameters: Vec<gui::Parameter>,
+    #[serde(default)]
+    pub points: Vec<[f32; 2]>,
+    #[serde(default)]
+    pub series: Vec<Series>,
+    /// The region of the graph in view, which is fitted to the window on load.
+    pub viewport: Bounds,
+}
+
+impl Session {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// The equations and parameter values of a workspace without any styling, for sharing or
+/// importing into other tools.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Expressions {
+    pub equations: Vec<String>,
+    pub parameters: BTreeMap<char, f32>,
+}
+
+impl Expressions {
+    /// Returns the parameters as `a = 1` lines followed by each equation on its own line.
+    pub fn to_text(&self) -> String {
+        let parameters = self.parameters.iter().map(|(name, value)| format!("{name} = {value}"));
+        parameters.chain(self.equations.iter().cloned())
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Writes the expressions to path, as JSON if it has a json extension and as text otherwise.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::to_string_pretty(self)?,
+            _ => self.to_text(),
+        };
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// A file operation asked for from the file menu.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileAction {
+    Open,
+    /// Open the recent file at this index, newest first.
+    OpenRecent(usize),
+    Save,
+    SaveAs,
+    Export,
+}
+
+/// The file dialog filter for session files.
+pub const FILTER: dialog::Filter = ("Graph session", &["json"]);
+
+/// Shows the file menu with the recent files, newest first, returning the action picked from
+/// it, if any.
+pub fn show_menu(ui: &mut egui::Ui, recent_files: &[PathBuf]) -> Option<FileAction> {
+    let mut action = None;
+
+    ui.menu_button("File", |ui| {
+        if ui.button("Open…").clicked() {
+            action = Some(FileAction::Open);
+            ui.close_menu();
+        }
+        ui.add_enabled_ui(!recent_files.is_empty(), |ui| {
+            ui.menu_button("Open Recent", |ui| {
+                for (i, path) in recent_files.iter().enumerate() {
+                    let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+                    if ui.button(name).on_hover_text(path.display().to_string()).clicked() {
+                        action = Some(FileAction::OpenRecent(i));
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        let actions = [
+            ("Save", FileAction::Save),
+            ("Save As…", FileAction::SaveAs),
+            ("Export Expressions…", FileAction::Export),
+        ];
+        for (label, a) in actions {
+            if ui.button(label).clicked() {
+                action = Some(a);
+                ui.close_menu();
+            }
+        }
+    });
+
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphing_engine::{Chart, Marker};
+
+    #[test]
+    fn sessions_survive_a_round_trip() {
+        let session = Session {
+            equations: vec![Equation {
+                text: "ax^2 + b".to_string(),
+                name: "parabola".to_string(),
+                color: Color { r: 0.2, g: 0.4, b: 0.6, a: 1.0 },
+                visible: false,
+                style: LineStyle::default(),
+            }],
+            parameters: vec![gui::Parameter { name: 'a', value: 2.5, min: -5.0, max: 5.0, step: 0.5 }],
+            points: vec![[1.0, -2.0]],
+            series: vec![Series {
+                name: "height".to_string(),
+                color: Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 },
+                marker: Marker::Diamond,
+                connected: true,
+                visible: true,
+                chart: Chart::Bars,
+                points: vec![cgmath::vec2(0.0, 1.5), cgmath::vec2(1.0, 2.5)],
+                categories: vec!["a".to_string(), "b".to_string()],
+            }],
+            viewport: Bounds { x_min: -4.0, x_max: 4.0, y_min: -3.0, y_max: 3.0 },
+        };
+
+        let text = serde_json::to_string_pretty(&session).unwrap();
+
+        assert_eq!(serde_json::from_str::<Session>(&text).unwrap(), session);
+    }
+
+    #[test]
+    fn expressions_as_text_list_parameters_then_equations() {
+        let expressions = Expressions {
+            equations: vec!["ax^2 + b".to_string(), "2x".to_string()],
+            parameters: BTreeMap::from([('b', -1.5), ('a', 2.0)]),
+        };
+
+        assert_eq!(expressions.to_text(), "a = 2\nb = -1.5\nax^2 + b\n2x\n");
+    }
+
+    #[test]
+    fn missing_fields_take_their_defaults() {
+        let text = r#"{
+            "equations": [{ "text": "x", "color": { "r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0 }, "visible": true }],
+            "viewport": { "x_min": -1.0, "x_max": 1.0, "y_min": -1.0, "y_max": 1.0 }
+        }"#;
+
+        let session = serde_json::from_str::<Session>(text).unwrap();
+
+        assert_eq!(session.equations[0].style, LineStyle::default());
+        assert!(session.equations[0].name.is_empty());
+        assert!(session.parameters.is_empty());
+        assert!(session.points.is_empty());
+        assert!(session.series.is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+
+use crate::data::fit::Fit;
+use crate::gui::{self, AccessibleName};
+use crate::scripting;
+use crate::session::{self, Expressions, Session};
+
+/// An independent graph, with its own equations, points and camera.
+pub struct Workspace {
+    pub name: String,
+    pub engine: graphing_engine::State,
+    pub equation_list: gui::EquationList,
+    pub parameters: gui::Parameters,
+    pub analysis: gui::CurveAnalysis,
+    pub complex_map: gui::ComplexMap,
+    pub streamlines: gui::Streamlines,
+    pub polar_shading: gui::PolarShading,
+    pub titles: graphing_engine::Titles,
+    /// Points imported from CSV files, listed in the legend after the equations.
+    pub series_list: gui::SeriesList,
+    /// Commands from the script whose hooks are registered on this workspace's engine, if any.
+    pub script_commands: Option<Rc<RefCell<Vec<scripting::ScriptCommand>>>>,
+    /// The file this workspace was last opened from or saved to.
+    pub path: Option<PathBuf>,
+}
+
+impl Workspace {
+    pub fn new(name: String, engine: graphing_engine::State) -> Self {
+        Self {
+            name,
+            engine,
+            equation_list: gui::EquationList::new(),
+            parameters: gui::Parameters::new(),
+            analysis: gui::CurveAnalysis::new(),
+            complex_map: gui::ComplexMap::new(),
+            streamlines: gui::Streamlines::new(),
+            polar_shading: gui::PolarShading::new(),
+            titles: graphing_engine::Titles::default(),
+            series_list: gui::SeriesList::new(),
+            script_commands: None,
+            path: None,
+        }
+    }
+
+    /// Captures the equations, parameters, points and view for saving.
+    pub fn session(&self) -> Session {
+        Session {
+            equations: self.equation_list.saved(),
+            parameters: self.parameters.list().to_vec(),
+            points: self.engine.points().iter().map(|point| [point.position.x, point.position.y]).collect(),
+            series: self.series_list.list().to_vec(),
+            viewport: self.engine.visible_bounds(),
+        }
+    }
+
+    /// Collects the equations which have been entered and the current parameter values.
+    pub fn expressions(&self) -> Expressions {
+        Expressions {
+            equations: self.equation_list.equations()
+                .filter(|equation| !equation.trim().is_empty())
+                .map(str::to_string)
+                .collect(),
+            parameters: self.parameters.list().iter().map(|parameter| (parameter.name, parameter.value)).collect(),
+        }
+    }
+
+    /// Replaces the contents of this workspace with a saved session. Everything but the points is
+    /// restored if there are more points than can be drawn.
+    pub fn restore(&mut self, session: Session, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<()> {
+        self.equation_list.restore(session.equations, &mut self.engine, device, queue);
+        self.parameters.restore(session.parameters, &mut self.engine);
+        let points = session.points.iter()
+            .map(|&[x, y]| graphing_engine::Point::new(cgmath::vec2(x, y)))
+            .collect::<Vec<_>>();
+        self.series_list.restore(session.series);
+        self.engine.fit_bounds(session.viewport);
+        self.engine.set_points(&points)?;
+        Ok(())
+    }
+
+    /// Fits a curve to a series. Polynomials are added as equations, and other curves as line
+    /// series sampled across the series' points, both named with the fitted formula.
+    pub fn add_fit(&mut self, request: gui::FitRequest, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<()> {
+        let series = self.series_list.list().get(request.series).ok_or_else(|| anyhow!("the series has been deleted"))?;
+        let fit = Fit::new(request.model, &series.points)?;
+        let name = fit.formula();
+        let color = series.color;
+
+        match fit.equation() {
+            Some(text) => {
+                let equation = session::Equation {
+                    text,
+                    name,
+                    color,
+                    visible: true,
+                    style: graphing_engine::LineStyle::default(),
+                };
+                self.equation_list.add(equation, &mut self.engine, device, queue);
+            }
+            None => {
+                let x_min = series.points.iter().map(|point| point.x).fold(f32::INFINITY, f32::min);
+                let x_max = series.points.iter().map(|point| point.x).fold(f32::NEG_INFINITY, f32::max);
+                self.series_list.add(graphing_engine::Series {
+                    name,
+                    color,
+                    marker: graphing_engine::Marker::default(),
+                    connected: false,
+                    visible: true,
+                    chart: graphing_engine::Chart::Line,
+                    points: fit.sample(x_min, x_max),
+                    categories: Vec::new(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Remembers path as the file for this workspace and names the workspace after it.
+    pub fn set_path(&mut self, path: PathBuf) {
+        if let Some(stem) = path.file_stem() {
+            self.name = stem.to_string_lossy().into_owned();
+        }
+        self.path = Some(path);
+    }
+}
+
+/// A change asked for from the workspace tabs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TabAction {
+    Select(usize),
+    Add,
+    Close(usize),
+}
+
+/// Returns the active workspace once the one at closed is removed, following the workspace that
+/// was active or its neighbour if that was the one closed.
+pub fn active_after_close(active: usize, closed: usize, remaining: usize) -> usize {
+    let active = if active > closed { active - 1 } else { active };
+    active.min(remaining.saturating_sub(1))
+}
+
+/// Shows a tab for each workspace, with buttons to add one and close the active one.
+pub fn show_tabs(ui: &mut egui::Ui, workspaces: &[Workspace], active: usize) -> Option<TabAction> {
+    let mut action = None;
+
+    ui.horizontal_wrapped(|ui| {
+        for (i, workspace) in workspaces.iter().enumerate() {
+            if ui.selectable_label(i == active, &workspace.name).clicked() && i != active {
+                action = Some(TabAction::Select(i));
+            }
+        }
+        let new = ui.small_button("+")
+            .on_hover_text("New workspace")
+            .accessible_name(egui::WidgetType::Button, "New workspace");
+        if new.clicked() {
+            action = Some(TabAction::Add);
+        }
+        if workspaces.len() > 1 {
+            let close = ui.small_button("✖")
+                .on_hover_text("Close workspace")
+                .accessible_name(egui::WidgetType::Button, "Close workspace");
+            if close.clicked() {
+                action = Some(TabAction::Close(active));
+            }
+        }
+    });
+
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_after_close() {
+        // closing a tab before the active one shifts it down
+        assert_eq!(active_after_close(2, 0, 2), 1);
+        // closing a later tab leaves it alone
+        assert_eq!(active_after_close(0, 1, 2), 0);
+        // closing the active tab moves to the one after it, or the last
+        assert_eq!(active_after_close(1, 1, 2), 1);
+        assert_eq!(active_after_close(2, 2, 2), 1);
+    }
+}